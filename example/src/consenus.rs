@@ -56,7 +56,7 @@ impl RaftNode {
         loop {
             match self.propc_rx.try_recv() {
                 Ok(data) => {
-                    self.raft.propose(data).unwrap();
+                    self.raft.propose(vec![], data).unwrap();
                 }
                 Err(TryRecvError::Empty) => {}
                 Err(TryRecvError::Disconnected) => {
@@ -0,0 +1,208 @@
+use std::io;
+
+use errors::{Error, Result};
+
+/// CHUNK_MAGIC starts every chunk, plain proposals must not start with it.
+pub const CHUNK_MAGIC: &[u8] = b"\xffRCK";
+
+// magic, proposal id (u64), chunk index (u32), chunk count (u32)
+const HEADER_SIZE: usize = 4 + 8 + 4 + 4;
+
+/// split_proposal splits data into chunks of at most max_chunk_size payload
+/// bytes, each framed with the proposal id, chunk index and chunk count, to
+/// be proposed together with RawNode::propose_chunked. The id must be unique
+/// among the proposals in flight.
+pub fn split_proposal(id: u64, data: &[u8], max_chunk_size: usize) -> Vec<Vec<u8>> {
+    if max_chunk_size == 0 {
+        panic!("max chunk size must be greater than 0");
+    }
+
+    let count = if data.is_empty() {
+        1
+    } else {
+        data.len().div_ceil(max_chunk_size)
+    };
+    let mut chunks = Vec::with_capacity(count);
+    for i in 0..count {
+        let lo = i * max_chunk_size;
+        let hi = std::cmp::min(lo + max_chunk_size, data.len());
+        let mut chunk = Vec::with_capacity(HEADER_SIZE + hi - lo);
+        chunk.extend_from_slice(CHUNK_MAGIC);
+        chunk.extend_from_slice(&id.to_be_bytes());
+        chunk.extend_from_slice(&(i as u32).to_be_bytes());
+        chunk.extend_from_slice(&(count as u32).to_be_bytes());
+        chunk.extend_from_slice(&data[lo..hi]);
+        chunks.push(chunk);
+    }
+    chunks
+}
+
+/// is_chunk returns true if the entry payload was produced by split_proposal.
+pub fn is_chunk(data: &[u8]) -> bool {
+    data.len() >= HEADER_SIZE && data.starts_with(CHUNK_MAGIC)
+}
+
+#[derive(Debug, PartialEq)]
+struct ChunkHeader {
+    id: u64,
+    index: u32,
+    count: u32,
+}
+
+fn parse_header(data: &[u8]) -> Result<ChunkHeader> {
+    if !is_chunk(data) {
+        return Err(Error::Io(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "not a proposal chunk",
+        )));
+    }
+    let mut id = [0; 8];
+    id.copy_from_slice(&data[4..12]);
+    let mut index = [0; 4];
+    index.copy_from_slice(&data[12..16]);
+    let mut count = [0; 4];
+    count.copy_from_slice(&data[16..20]);
+    Ok(ChunkHeader {
+        id: u64::from_be_bytes(id),
+        index: u32::from_be_bytes(index),
+        count: u32::from_be_bytes(count),
+    })
+}
+
+/// ChunkAssembler rebuilds chunked proposals from committed entries, and
+/// drops a proposal of which only a prefix was committed. The chunks applied
+/// so far are only held in memory: after a restart or a snapshot the
+/// application has to feed it the log again from the first chunk, so it must
+/// not compact the log past a chunked proposal until it is fully applied.
+#[derive(Debug, Default)]
+pub struct ChunkAssembler {
+    // id and next expected chunk index of the proposal being assembled.
+    id: u64,
+    next: u32,
+    buffer: Vec<u8>,
+}
+
+impl ChunkAssembler {
+    pub fn new() -> ChunkAssembler {
+        ChunkAssembler::default()
+    }
+
+    /// push feeds the payload of the next committed normal entry. It returns
+    /// the payload itself for entries which are not chunks, the reassembled
+    /// proposal once its last chunk is pushed, and None otherwise.
+    pub fn push(&mut self, data: &[u8]) -> Option<Vec<u8>> {
+        let h = match parse_header(data) {
+            Ok(h) => h,
+            Err(_) => {
+                self.reset();
+                return Some(data.to_vec());
+            }
+        };
+
+        if h.index == 0 {
+            // a new proposal starts, whatever was in progress can never complete.
+            self.reset();
+            self.id = h.id;
+        } else if h.id != self.id || h.index != self.next {
+            // the beginning of this proposal was never committed.
+            self.reset();
+            return None;
+        }
+
+        self.buffer.extend_from_slice(&data[HEADER_SIZE..]);
+        self.next = h.index + 1;
+        if self.next == h.count {
+            let payload = std::mem::take(&mut self.buffer);
+            self.reset();
+            return Some(payload);
+        }
+        None
+    }
+
+    /// is_pending returns true if a proposal is partially assembled.
+    pub fn is_pending(&self) -> bool {
+        self.next != 0
+    }
+
+    fn reset(&mut self) {
+        self.id = 0;
+        self.next = 0;
+        self.buffer.clear();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_split_proposal() {
+        let data: Vec<u8> = (0..10).collect();
+        let tests = vec![(3, 4), (5, 2), (10, 1), (100, 1)];
+        for (max, wcount) in tests {
+            let chunks = split_proposal(7, &data, max);
+            assert_eq!(chunks.len(), wcount);
+            for (i, c) in chunks.iter().enumerate() {
+                assert!(is_chunk(c));
+                assert!(c.len() <= HEADER_SIZE + max);
+                let h = parse_header(c).unwrap();
+                assert_eq!(
+                    h,
+                    ChunkHeader {
+                        id: 7,
+                        index: i as u32,
+                        count: wcount as u32,
+                    }
+                );
+            }
+        }
+
+        let chunks = split_proposal(7, &[], 3);
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].len(), HEADER_SIZE);
+    }
+
+    #[test]
+    fn test_chunk_assembler() {
+        let data: Vec<u8> = (0..10).collect();
+        let mut a = ChunkAssembler::new();
+
+        let chunks = split_proposal(1, &data, 4);
+        assert_eq!(a.push(&chunks[0]), None);
+        assert_eq!(a.push(&chunks[1]), None);
+        assert!(a.is_pending());
+        assert_eq!(a.push(&chunks[2]), Some(data.clone()));
+        assert!(!a.is_pending());
+
+        // plain payloads are passed through.
+        assert_eq!(a.push(b"plain"), Some(b"plain".to_vec()));
+        assert_eq!(a.push(&split_proposal(2, &[], 4)[0]), Some(vec![]));
+    }
+
+    #[test]
+    fn test_chunk_assembler_drops_incomplete() {
+        let data: Vec<u8> = (0..10).collect();
+        let mut a = ChunkAssembler::new();
+
+        // only a prefix of proposal 1 got committed.
+        let chunks = split_proposal(1, &data, 4);
+        assert_eq!(a.push(&chunks[0]), None);
+
+        // proposal 2 is retried and fully committed.
+        let chunks2 = split_proposal(2, &data, 4);
+        for c in &chunks2[..2] {
+            assert_eq!(a.push(c), None);
+        }
+        assert_eq!(a.push(&chunks2[2]), Some(data.clone()));
+
+        // a suffix without its beginning is ignored.
+        assert_eq!(a.push(&chunks[1]), None);
+        assert_eq!(a.push(&chunks[2]), None);
+        assert!(!a.is_pending());
+
+        // an interrupted proposal is dropped by a plain entry.
+        assert_eq!(a.push(&chunks[0]), None);
+        assert_eq!(a.push(b"plain"), Some(b"plain".to_vec()));
+        assert!(!a.is_pending());
+    }
+}
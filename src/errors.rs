@@ -25,9 +25,23 @@ quick_error! {
         ProposalDropped {
             description("raft: proposal dropped")
         }
+        ProposalTooLarge(size: u64, max: u64) {
+            description("raft: proposal too large")
+            display("raft: proposal of {} bytes exceeds the limit of {} bytes", size, max)
+        }
+        ProposalRateLimited {
+            description("raft: proposal rate limited, retry later")
+        }
+        Stopped {
+            description("raft: node stopped")
+        }
         ConfigInvalid(desc: String) {
             description(desc)
         }
+        ConfChangeInvalid(desc: String) {
+            description(desc)
+            display("raft: invalid conf change: {}", desc)
+        }
         Codec(err: ProtobufError) {
             from()
             cause(err)
@@ -43,10 +57,16 @@ impl cmp::PartialEq for Error {
         match (self, other) {
             (&Error::StepPeerNotFound, &Error::StepPeerNotFound) => true,
             (&Error::ProposalDropped, &Error::ProposalDropped) => true,
+            (&Error::ProposalTooLarge(s1, m1), &Error::ProposalTooLarge(s2, m2)) => {
+                s1 == s2 && m1 == m2
+            }
             (&Error::Storage(ref e1), &Error::Storage(ref e2)) => e1 == e2,
             (&Error::Io(ref e1), &Error::Io(ref e2)) => e1.kind() == e2.kind(),
+            (&Error::ProposalRateLimited, &Error::ProposalRateLimited) => true,
             (&Error::StepLocalMsg, &Error::StepLocalMsg) => true,
+            (&Error::Stopped, &Error::Stopped) => true,
             (&Error::ConfigInvalid(ref e1), &Error::ConfigInvalid(ref e2)) => e1 == e2,
+            (&Error::ConfChangeInvalid(ref e1), &Error::ConfChangeInvalid(ref e2)) => e1 == e2,
             _ => false,
         }
     }
@@ -0,0 +1,203 @@
+use std::fmt::Write;
+
+use protobuf;
+use raftpb::{ConfChange, ConfChangeV2, Entry, EntryType, Message, Snapshot};
+use util::{is_conf_change, is_empty_snap};
+
+/// describe_entry returns a concise human-readable description of an
+/// Entry, in the form of `term/index type payload`. The payload of an
+/// EntryConfChange or EntryConfChangeV2 is decoded, normal payloads are
+/// summarized by size.
+pub fn describe_entry(e: &Entry) -> String {
+    let payload = match e.get_entry_type() {
+        EntryType::EntryNormal => format!("{} bytes", e.get_data().len()),
+        EntryType::EntryConfChange => describe_conf_change_data(e.get_data()),
+        EntryType::EntryConfChangeV2 => describe_conf_change_v2_data(e.get_data()),
+    };
+    format!(
+        "{}/{} {:?} {}",
+        e.get_term(),
+        e.get_index(),
+        e.get_entry_type(),
+        payload
+    )
+}
+
+/// describe_entries summarizes a batch of entries by their index and term
+/// ranges and the total payload size, instead of listing every entry.
+pub fn describe_entries(ents: &[Entry]) -> String {
+    if ents.is_empty() {
+        return "[]".to_string();
+    }
+
+    let (first, last) = (&ents[0], &ents[ents.len() - 1]);
+    let bytes: usize = ents.iter().map(|e| e.get_data().len()).sum();
+    let confs = ents
+        .iter()
+        .filter(|e| is_conf_change(e))
+        .count();
+
+    let mut s = format!(
+        "[{}/{}..{}/{}, {} entries, {} bytes",
+        first.get_term(),
+        first.get_index(),
+        last.get_term(),
+        last.get_index(),
+        ents.len(),
+        bytes
+    );
+    if confs > 0 {
+        let _ = write!(s, ", {} conf changes", confs);
+    }
+    s.push(']');
+    s
+}
+
+/// describe_snapshot returns the metadata of a snapshot and the size of its data.
+pub fn describe_snapshot(snap: &Snapshot) -> String {
+    let meta = snap.get_metadata();
+    format!(
+        "[index: {}, term: {}, nodes: {:?}, learners: {:?}, {} bytes]",
+        meta.get_index(),
+        meta.get_term(),
+        meta.get_conf_state().get_nodes(),
+        meta.get_conf_state().get_learners(),
+        snap.get_data().len()
+    )
+}
+
+/// describe_message returns a concise human-readable description of a
+/// Message for logging and debugging, e.g.
+/// `1->2 MsgApp Term:2 Log:1/5 Commit:4 Entries:[2/6..2/7, 2 entries, 8 bytes]`.
+pub fn describe_message(m: &Message) -> String {
+    let mut s = format!(
+        "{}->{} {:?} Term:{} Log:{}/{}",
+        m.get_from(),
+        m.get_to(),
+        m.get_msg_type(),
+        m.get_term(),
+        m.get_log_term(),
+        m.get_index()
+    );
+    if m.get_reject() {
+        let _ = write!(s, " Rejected (Hint: {})", m.get_reject_hint());
+    }
+    if m.get_commit() != 0 {
+        let _ = write!(s, " Commit:{}", m.get_commit());
+    }
+    if !m.get_context().is_empty() {
+        let _ = write!(s, " Context:{} bytes", m.get_context().len());
+    }
+    if !m.get_entries().is_empty() {
+        let _ = write!(s, " Entries:{}", describe_entries(m.get_entries()));
+    }
+    if m.has_snapshot() && !is_empty_snap(m.get_snapshot()) {
+        let _ = write!(s, " Snapshot:{}", describe_snapshot(m.get_snapshot()));
+    }
+    s
+}
+
+fn describe_conf_change_data(data: &[u8]) -> String {
+    match protobuf::parse_from_bytes::<ConfChange>(data) {
+        Ok(cc) => format!("{:?} {}", cc.get_change_type(), cc.get_node_id()),
+        Err(e) => format!("<invalid conf change: {}>", e),
+    }
+}
+
+fn describe_conf_change_v2_data(data: &[u8]) -> String {
+    match protobuf::parse_from_bytes::<ConfChangeV2>(data) {
+        Ok(cc) => {
+            let changes: Vec<String> = cc
+                .get_changes()
+                .iter()
+                .map(|c| format!("{:?} {}", c.get_change_type(), c.get_node_id()))
+                .collect();
+            format!("{:?} [{}]", cc.get_transition(), changes.join(", "))
+        }
+        Err(e) => format!("<invalid conf change: {}>", e),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use protobuf::{Message as PbMessage, RepeatedField};
+    use raftpb::{ConfChangeSingle, ConfChangeType, MessageType};
+
+    fn new_entry(index: u64, term: u64, data: &[u8]) -> Entry {
+        let mut e = Entry::new();
+        e.set_index(index);
+        e.set_term(term);
+        e.set_data(data.to_vec());
+        e
+    }
+
+    #[test]
+    fn test_describe_entry() {
+        let e = new_entry(5, 1, b"hello");
+        assert_eq!(describe_entry(&e), "1/5 EntryNormal 5 bytes");
+
+        let mut cc = ConfChange::new();
+        cc.set_change_type(ConfChangeType::ConfChangeAddNode);
+        cc.set_node_id(3);
+        let mut e = new_entry(6, 2, &cc.write_to_bytes().unwrap());
+        e.set_entry_type(EntryType::EntryConfChange);
+        assert_eq!(describe_entry(&e), "2/6 EntryConfChange ConfChangeAddNode 3");
+
+        let mut cc = ConfChangeV2::new();
+        let mut c = ConfChangeSingle::new();
+        c.set_change_type(ConfChangeType::ConfChangeRemoveNode);
+        c.set_node_id(2);
+        cc.mut_changes().push(c);
+        let mut e = new_entry(7, 2, &cc.write_to_bytes().unwrap());
+        e.set_entry_type(EntryType::EntryConfChangeV2);
+        assert_eq!(
+            describe_entry(&e),
+            "2/7 EntryConfChangeV2 ConfChangeTransitionAuto [ConfChangeRemoveNode 2]"
+        );
+    }
+
+    #[test]
+    fn test_describe_entries() {
+        assert_eq!(describe_entries(&[]), "[]");
+
+        let ents = vec![new_entry(6, 2, b"ab"), new_entry(7, 3, b"cdef")];
+        assert_eq!(
+            describe_entries(&ents),
+            "[2/6..3/7, 2 entries, 6 bytes]"
+        );
+    }
+
+    #[test]
+    fn test_describe_message() {
+        let mut m = Message::new();
+        m.set_msg_type(MessageType::MsgApp);
+        m.set_from(1);
+        m.set_to(2);
+        m.set_term(2);
+        m.set_log_term(1);
+        m.set_index(5);
+        m.set_commit(4);
+        m.set_entries(RepeatedField::from_vec(vec![
+            new_entry(6, 2, b"abcd"),
+            new_entry(7, 2, b"efgh"),
+        ]));
+        assert_eq!(
+            describe_message(&m),
+            "1->2 MsgApp Term:2 Log:1/5 Commit:4 Entries:[2/6..2/7, 2 entries, 8 bytes]"
+        );
+
+        let mut m = Message::new();
+        m.set_msg_type(MessageType::MsgAppResp);
+        m.set_from(2);
+        m.set_to(1);
+        m.set_term(2);
+        m.set_index(5);
+        m.set_reject(true);
+        m.set_reject_hint(3);
+        assert_eq!(
+            describe_message(&m),
+            "2->1 MsgAppResp Term:2 Log:0/5 Rejected (Hint: 3)"
+        );
+    }
+}
@@ -10,13 +10,22 @@ extern crate protobuf;
 extern crate quick_error;
 extern crate rand;
 
+pub mod chunk;
+pub mod codec;
 pub mod errors;
+pub mod formatter;
 pub mod log_unstable;
+pub mod node;
+pub mod payload;
 pub mod progress;
+pub mod quorum;
 pub mod raft;
 pub mod raft_log;
 pub mod raftpb;
+pub mod rate_limit;
 pub mod raw_node;
 pub mod read_only;
+pub mod shared_node;
 pub mod storage;
+pub mod tracker;
 pub mod util;
@@ -113,7 +113,9 @@ impl Unstable {
         self.snapshot = Some(s);
     }
 
-    pub(crate) fn stable_to(&mut self, i: u64, t: u64) {
+    /// stable_to marks the entries up to i as persisted, if the entry at i
+    /// is still unstable and has term t. Returns true if it did.
+    pub(crate) fn stable_to(&mut self, i: u64, t: u64) -> bool {
         if let Some(gt) = self.maybe_term(i) {
             // if i < offset, term is matched with the snapshot
             // only update the unstable entries if term is matched with
@@ -122,18 +124,24 @@ impl Unstable {
                 let start = i - self.offset + 1;
                 self.entries.drain(..start as usize);
                 self.offset = i + 1;
+                return true;
             }
         }
+        false
     }
 
-    pub(crate) fn stable_snap_to(&mut self, i: u64) {
+    /// stable_snap_to marks the unstable snapshot as persisted if its index
+    /// is i. Returns true if it did.
+    pub(crate) fn stable_snap_to(&mut self, i: u64) -> bool {
         if self.snapshot.is_none() {
-            return;
+            return false;
         }
 
         if self.snapshot.as_ref().unwrap().get_metadata().get_index() == i {
             self.snapshot = None;
+            return true;
         }
+        false
     }
 }
 
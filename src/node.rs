@@ -0,0 +1,297 @@
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use errors::{Error, Result};
+use payload::Payload;
+use raft::{Config, Peer, Status};
+use raftpb::{ConfChange, Message};
+use raw_node::{RawNode, Ready};
+use storage::Storage;
+
+enum Request {
+    Propose(Vec<u8>, Vec<u8>),
+    ProposeConfChange(ConfChange),
+    Step(Message),
+    Campaign,
+    ReadIndex(Vec<u8>),
+    Status(Sender<Status>),
+    Advance,
+    Stop,
+}
+
+/// Node drives a RawNode on its own thread, ticking it and handling requests
+/// from any thread, and hands every Ready over through a channel; no other
+/// Ready is emitted until it is advanced.
+pub struct Node {
+    requests: Sender<(Request, Sender<Result<()>>)>,
+    readies: Receiver<Ready>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl Node {
+    /// start creates a RawNode from the given config and spawns the thread
+    /// driving it, ticking it every tick_interval.
+    pub fn start<T: Storage + Send + 'static>(
+        c: &mut Config,
+        storage: T,
+        peers: Vec<Peer>,
+        tick_interval: Duration,
+    ) -> Result<Node> {
+        let raw_node = RawNode::new(c, storage, peers)?;
+        let (req_tx, req_rx) = mpsc::channel();
+        let (ready_tx, ready_rx) = mpsc::channel();
+        let handle = thread::spawn(move || run(raw_node, tick_interval, req_rx, ready_tx));
+        Ok(Node {
+            requests: req_tx,
+            readies: ready_rx,
+            handle: Some(handle),
+        })
+    }
+
+    fn request(&self, req: Request) -> Result<()> {
+        let (tx, rx) = mpsc::channel();
+        if self.requests.send((req, tx)).is_err() {
+            return Err(Error::Stopped);
+        }
+        rx.recv().unwrap_or(Err(Error::Stopped))
+    }
+
+    /// ready returns the channel on which the driver emits every Ready. Each
+    /// of them must be followed by a call to advance.
+    pub fn ready(&self) -> &Receiver<Ready> {
+        &self.readies
+    }
+
+    /// advance notifies the driver that the last Ready has been processed.
+    pub fn advance(&self) -> Result<()> {
+        self.request(Request::Advance)
+    }
+
+    /// propose proposes data be appended to the raft log, see
+    /// RawNode::propose.
+    pub fn propose<P: Payload>(&self, context: Vec<u8>, data: P) -> Result<()> {
+        self.request(Request::Propose(context, data.into_data()))
+    }
+
+    /// propose_conf_change proposes a config change.
+    pub fn propose_conf_change(&self, cc: ConfChange) -> Result<()> {
+        self.request(Request::ProposeConfChange(cc))
+    }
+
+    /// step advances the state machine using the given message.
+    pub fn step(&self, m: Message) -> Result<()> {
+        self.request(Request::Step(m))
+    }
+
+    /// campaign causes the node to transition to candidate state.
+    pub fn campaign(&self) -> Result<()> {
+        self.request(Request::Campaign)
+    }
+
+    /// read_index requests a read state, which is returned in a later Ready.
+    pub fn read_index(&self, rctx: Vec<u8>) -> Result<()> {
+        self.request(Request::ReadIndex(rctx))
+    }
+
+    /// status returns the current status of the raft state machine.
+    pub fn status(&self) -> Result<Status> {
+        let (tx, rx) = mpsc::channel();
+        self.request(Request::Status(tx))?;
+        rx.recv().map_err(|_| Error::Stopped)
+    }
+
+    /// stop stops the driver thread and waits for it to exit. Requests made
+    /// afterwards fail with Stopped. It returns the panic of the driver
+    /// thread, if any.
+    pub fn stop(&mut self) -> thread::Result<()> {
+        let _ = self.request(Request::Stop);
+        match self.handle.take() {
+            Some(h) => h.join(),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for Node {
+    fn drop(&mut self) {
+        // panicking again here would abort the process if the node is dropped
+        // while unwinding.
+        if self.stop().is_err() {
+            error!("raft node driver thread panicked");
+        }
+    }
+}
+
+fn run<T: Storage>(
+    mut raw_node: RawNode<T>,
+    tick_interval: Duration,
+    requests: Receiver<(Request, Sender<Result<()>>)>,
+    readies: Sender<Ready>,
+) {
+    let mut next_tick = Instant::now() + tick_interval;
+    let mut pending: Option<Ready> = None;
+    loop {
+        // Tick before looking at the requests, so that a steady stream of them
+        // can't hold back heartbeats and elections, and catch up on the ticks
+        // missed while handling them.
+        let now = Instant::now();
+        while next_tick <= now {
+            raw_node.tick();
+            next_tick += tick_interval;
+        }
+
+        if pending.is_none() && raw_node.has_ready() {
+            let rd = raw_node.ready();
+            let state = rd.advance_state();
+            if readies.send(rd).is_err() {
+                return;
+            }
+            pending = Some(state);
+        }
+
+        let timeout = next_tick.saturating_duration_since(Instant::now());
+        let (req, resp) = match requests.recv_timeout(timeout) {
+            Ok(r) => r,
+            Err(RecvTimeoutError::Timeout) => continue,
+            Err(RecvTimeoutError::Disconnected) => return,
+        };
+
+        let res = match req {
+            Request::Propose(ctx, data) => raw_node.propose(ctx, data),
+            Request::ProposeConfChange(cc) => raw_node.propose_conf_change(&cc),
+            Request::Step(m) => raw_node.step(m),
+            Request::Campaign => raw_node.campaign(),
+            Request::ReadIndex(rctx) => {
+                raw_node.read_index(rctx);
+                Ok(())
+            }
+            Request::Status(tx) => {
+                let _ = tx.send(raw_node.status());
+                Ok(())
+            }
+            Request::Advance => {
+                if let Some(rd) = pending.take() {
+                    raw_node.advance(rd);
+                }
+                Ok(())
+            }
+            Request::Stop => {
+                let _ = resp.send(Ok(()));
+                return;
+            }
+        };
+        let _ = resp.send(res);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use raft::StateType;
+    use storage::MemStorage;
+    use util::NO_LIMIT;
+
+    // persist_and_advance handles the next Ready like an application would
+    // and returns it.
+    fn persist_and_advance(node: &Node, s: &mut MemStorage) -> Ready {
+        let rd = node.ready().recv().unwrap();
+        if rd.hard_state != Default::default() {
+            s.set_hard_state(rd.hard_state.clone());
+        }
+        s.append(&rd.entries).unwrap();
+        node.advance().unwrap();
+        rd
+    }
+
+    #[test]
+    fn test_node_propose() {
+        let mut s = MemStorage::new();
+        let mut c = Config {
+            id: 1,
+            election_tick: 10,
+            heartbeat_tick: 1,
+            max_size_per_msg: NO_LIMIT,
+            max_inflight_msgs: 256,
+            ..Default::default()
+        };
+        let peers = vec![Peer {
+            id: 1,
+            context: vec![],
+        }];
+        let mut node =
+            Node::start(&mut c, s.clone(), peers, Duration::from_millis(1)).unwrap();
+        persist_and_advance(&node, &mut s);
+
+        node.campaign().unwrap();
+        while node.status().unwrap().soft_state.raft_state != StateType::Leader {
+            persist_and_advance(&node, &mut s);
+        }
+
+        node.propose(vec![], b"foo".to_vec()).unwrap();
+        loop {
+            let rd = persist_and_advance(&node, &mut s);
+            if rd.committed_entries.iter().any(|e| e.get_data() == b"foo") {
+                break;
+            }
+        }
+
+        node.stop().unwrap();
+        assert_eq!(node.propose(vec![], b"bar".to_vec()), Err(Error::Stopped));
+    }
+
+    #[test]
+    fn test_node_driver_panic() {
+        let new_node = || {
+            let (req_tx, _) = mpsc::channel();
+            let (_, ready_rx) = mpsc::channel();
+            Node {
+                requests: req_tx,
+                readies: ready_rx,
+                handle: Some(thread::spawn(|| panic!("driver panicked"))),
+            }
+        };
+
+        // stop reports the panic, and dropping the node afterwards is fine.
+        let mut node = new_node();
+        assert!(node.stop().is_err());
+        drop(node);
+
+        // dropping the node must not panic in turn.
+        drop(new_node());
+    }
+
+    #[test]
+    fn test_node_tick_under_load() {
+        let mut c = Config {
+            id: 1,
+            peers: vec![1, 2, 3],
+            election_tick: 10,
+            heartbeat_tick: 1,
+            max_size_per_msg: NO_LIMIT,
+            max_inflight_msgs: 256,
+            ..Default::default()
+        };
+        let raw_node = RawNode::new(&mut c, MemStorage::new(), vec![]).unwrap();
+        let (req_tx, req_rx) = mpsc::channel();
+        let (ready_tx, _ready_rx) = mpsc::channel();
+        let (resp_tx, _resp_rx) = mpsc::channel();
+        let (status_tx, status_rx) = mpsc::channel();
+
+        // the driver always has a request waiting, for well over an election
+        // timeout, and must still tick the node into campaigning.
+        let start = Instant::now();
+        while start.elapsed() < Duration::from_millis(100) {
+            for _ in 0..1000 {
+                let req = Request::Status(status_tx.clone());
+                req_tx.send((req, resp_tx.clone())).unwrap();
+            }
+        }
+        req_tx.send((Request::Stop, resp_tx)).unwrap();
+        run(raw_node, Duration::from_millis(1), req_rx, ready_tx);
+
+        assert!(status_rx
+            .try_iter()
+            .any(|s| s.soft_state.raft_state != StateType::Follower));
+    }
+}
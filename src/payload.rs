@@ -0,0 +1,110 @@
+use std::sync::Arc;
+
+use errors::Result;
+use raftpb::Entry;
+
+/// Payload is an application command which can be stored in an entry.
+pub trait Payload: Sized {
+    /// into_data converts the payload into the entry data.
+    fn into_data(self) -> Vec<u8>;
+
+    /// from_data decodes a payload from the data of a committed entry.
+    fn from_data(data: Vec<u8>) -> Result<Self>;
+}
+
+impl Payload for Vec<u8> {
+    fn into_data(self) -> Vec<u8> {
+        self
+    }
+
+    fn from_data(data: Vec<u8>) -> Result<Vec<u8>> {
+        Ok(data)
+    }
+}
+
+impl Payload for Box<[u8]> {
+    fn into_data(self) -> Vec<u8> {
+        self.into_vec()
+    }
+
+    fn from_data(data: Vec<u8>) -> Result<Box<[u8]>> {
+        Ok(data.into_boxed_slice())
+    }
+}
+
+impl Payload for Arc<Vec<u8>> {
+    fn into_data(self) -> Vec<u8> {
+        Arc::try_unwrap(self).unwrap_or_else(|shared| shared.as_ref().clone())
+    }
+
+    fn from_data(data: Vec<u8>) -> Result<Arc<Vec<u8>>> {
+        Ok(Arc::new(data))
+    }
+}
+
+/// take_payload moves the data out of the given entry and decodes it.
+pub fn take_payload<P: Payload>(e: &mut Entry) -> Result<P> {
+    P::from_data(e.take_data())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use errors::Error;
+    use std::io;
+
+    // Command is an application payload with a one byte version header.
+    #[derive(Debug, PartialEq)]
+    struct Command(Vec<u8>);
+
+    impl Payload for Command {
+        fn into_data(self) -> Vec<u8> {
+            self.0
+        }
+
+        fn from_data(data: Vec<u8>) -> Result<Command> {
+            if data.first() != Some(&1) {
+                return Err(Error::Io(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "unknown command version",
+                )));
+            }
+            Ok(Command(data))
+        }
+    }
+
+    #[test]
+    fn test_payload_round_trip() {
+        let data = b"data".to_vec();
+        let ptr = data.as_ptr();
+        let mut e = Entry::new();
+        e.set_data(data.into_data());
+        let back: Vec<u8> = take_payload(&mut e).unwrap();
+        assert_eq!(back.as_ptr(), ptr);
+        assert!(e.get_data().is_empty());
+
+        // a shared buffer is copied, a unique one is moved.
+        let shared = Arc::new(b"shared".to_vec());
+        let kept = shared.clone();
+        let copied = shared.into_data();
+        assert_ne!(copied.as_ptr(), kept.as_ptr());
+        let ptr = kept.as_ptr();
+        let moved = kept.into_data();
+        assert_eq!(moved.as_ptr(), ptr);
+
+        let boxed: Box<[u8]> = b"boxed".to_vec().into_boxed_slice();
+        let mut e = Entry::new();
+        e.set_data(boxed.into_data());
+        assert_eq!(&*take_payload::<Box<[u8]>>(&mut e).unwrap(), b"boxed");
+    }
+
+    #[test]
+    fn test_payload_decode_error() {
+        let mut e = Entry::new();
+        e.set_data(Command(vec![1, 2]).into_data());
+        assert_eq!(take_payload::<Command>(&mut e).unwrap(), Command(vec![1, 2]));
+
+        e.set_data(vec![2, 2]);
+        assert!(take_payload::<Command>(&mut e).is_err());
+    }
+}
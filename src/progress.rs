@@ -77,6 +77,9 @@
 /// implementation will have a sending buffer on top of its actual network transport layer (not blocking r
 /// aft node). We want to make sure raft does not overflow that buffer, which can cause message dropping
 /// and triggering a bunch of unnecessary resending repeatedly.
+///
+/// The transport can also report that its buffer for a peer is full with
+/// `Raft::report_send_queue`, which pauses the peer until the buffer drains.
 use std::cmp;
 
 #[derive(Debug, PartialEq, Clone, Copy)]
@@ -92,6 +95,21 @@ impl Default for ProgressState {
     }
 }
 
+/// PausedReason tells why the leader is not sending log entries to a peer.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum PausedReason {
+    /// An append was sent while probing, the leader waits for its response.
+    ProbeAwaitingResponse,
+    /// The inflight limit is reached while replicating.
+    InflightsFull,
+    /// A snapshot is being sent to the peer.
+    PendingSnapshot,
+    /// Replication was paused by an operator.
+    OperatorPaused,
+    /// The transport reported its send queue to the peer as full.
+    SendQueueFull,
+}
+
 #[derive(Debug, Default, Clone, PartialEq)]
 pub struct Progress {
     pub matched: u64,
@@ -102,6 +120,36 @@ pub struct Progress {
     pub recent_active: bool,
     pub ins: Inflights,
     pub is_learner: bool,
+    // replication_paused is set by an operator to stop sending entries and
+    // snapshots to the peer, see Raft::pause_replication.
+    pub replication_paused: bool,
+    // send_queue_full is reported by the transport when its send queue to the
+    // peer is full, see Raft::report_send_queue.
+    pub send_queue_full: bool,
+    // inflight_window caps the number of inflight messages below the capacity
+    // of ins when adaptive inflight is enabled, 0 means no extra cap.
+    pub inflight_window: usize,
+    // ack_streak counts the acknowledgements since the window last changed.
+    pub ack_streak: usize,
+    // unreachable_count counts the consecutive unreachable reports since the
+    // peer last responded, and probe_skip the heartbeats still to be skipped
+    // before probing it again.
+    pub unreachable_count: u32,
+    pub probe_skip: u64,
+    // append_budget is the max size of a MsgApp advertised by the peer in its
+    // last MsgAppResp, 0 for no limit.
+    pub append_budget: u64,
+    // entries_sent and bytes_sent count the entries, and their encoded size,
+    // sent to the peer in MsgApp since this node became leader or the peer
+    // joined, rejects_received its rejected appends, snapshots_sent and
+    // snapshots_failed the snapshots sent to it and reported as failed. Unlike
+    // the rest of the progress they survive state changes, so a slow or
+    // flapping peer stands out in Status.
+    pub entries_sent: u64,
+    pub bytes_sent: u64,
+    pub rejects_received: u64,
+    pub snapshots_sent: u64,
+    pub snapshots_failed: u64,
 }
 
 impl Progress {
@@ -183,17 +231,36 @@ impl Progress {
     }
 
     // IsPaused returns whether sending log entries to this node has been
-    // paused. A node may be paused because it has rejected recent
-    // MsgApps, is currently waiting for a snapshot, or has reached the
-    // MaxInflightMsgs limit.
+    // paused, see paused_reason.
     pub fn is_paused(&self) -> bool {
+        self.paused_reason().is_some()
+    }
+
+    // paused_reason returns why sending log entries to this node has been
+    // paused, if it is. A node may be paused because it has rejected recent
+    // MsgApps, is currently waiting for a snapshot, or has reached the
+    // MaxInflightMsgs limit, replication to it was paused by an operator, or
+    // the transport reported its send queue to the node as full.
+    pub fn paused_reason(&self) -> Option<PausedReason> {
+        if self.replication_paused {
+            return Some(PausedReason::OperatorPaused);
+        }
+        if self.send_queue_full {
+            return Some(PausedReason::SendQueueFull);
+        }
         match self.state {
-            ProgressState::Probe => self.paused,
-            ProgressState::Replicate => self.ins.full(),
+            ProgressState::Probe if self.paused => Some(PausedReason::ProbeAwaitingResponse),
+            ProgressState::Replicate
+                if self.ins.full()
+                    || (self.inflight_window != 0 && self.ins.count >= self.inflight_window) =>
+            {
+                Some(PausedReason::InflightsFull)
+            }
 
             // When the progress of a follower is in `snapshot` state,
             // leader stops sending any `replication message`.
-            ProgressState::Snapshot => true,
+            ProgressState::Snapshot => Some(PausedReason::PendingSnapshot),
+            _ => None,
         }
     }
 
@@ -207,6 +274,80 @@ impl Progress {
         self.state == ProgressState::Snapshot && self.matched >= self.pending_snapshot
     }
 
+    // grow_inflight_window additively grows the inflight window by one message
+    // after a window worth of clean acknowledgements, until it reaches the
+    // capacity of the inflights again.
+    pub fn grow_inflight_window(&mut self) {
+        if self.inflight_window == 0 {
+            return;
+        }
+        self.ack_streak += 1;
+        if self.ack_streak < self.inflight_window {
+            return;
+        }
+        self.ack_streak = 0;
+        self.inflight_window += 1;
+        if self.inflight_window >= self.ins.cap() {
+            self.inflight_window = 0;
+        }
+    }
+
+    // shrink_inflight_window halves the inflight window, down to one message,
+    // after a rejection or an unreachable report.
+    pub fn shrink_inflight_window(&mut self) {
+        let window = if self.inflight_window == 0 {
+            self.ins.cap()
+        } else {
+            self.inflight_window
+        };
+        self.inflight_window = cmp::max(window / 2, 1);
+        self.ack_streak = 0;
+    }
+
+    // back_off_probe is called when the peer is reported unreachable. After the
+    // n-th consecutive report the next min(2^n - 1, max_backoff) heartbeats to
+    // the peer are skipped, so a long-dead peer is probed less and less often.
+    pub fn back_off_probe(&mut self, max_backoff: u64) {
+        if max_backoff == 0 {
+            return;
+        }
+        self.unreachable_count = self.unreachable_count.saturating_add(1);
+        let backoff = if self.unreachable_count >= 64 {
+            u64::MAX
+        } else {
+            (1 << self.unreachable_count) - 1
+        };
+        self.probe_skip = cmp::min(backoff, max_backoff);
+    }
+
+    // skip_probe returns true if the next heartbeat to the peer is skipped
+    // because of the probe backoff.
+    pub fn skip_probe(&mut self) -> bool {
+        if self.probe_skip == 0 {
+            return false;
+        }
+        self.probe_skip -= 1;
+        true
+    }
+
+    // reset_probe_backoff is called whenever the peer responds.
+    pub fn reset_probe_backoff(&mut self) {
+        self.unreachable_count = 0;
+        self.probe_skip = 0;
+    }
+
+    // maybe_probe_from lowers next to the follower's last index + 1 reported by a
+    // heartbeat response while probing, so that a follower lagging far behind is
+    // caught up (or sent a snapshot) without waiting for a rejected MsgApp first.
+    // Returns true if next was changed.
+    pub fn maybe_probe_from(&mut self, last: u64) -> bool {
+        if self.state != ProgressState::Probe || last + 1 >= self.next {
+            return false;
+        }
+        self.next = cmp::max(last + 1, self.matched + 1);
+        true
+    }
+
     // maybe_decr_to returns false if the given to index comes from an out of order message.
     // Otherwise it decreases the progress next index to min(rejected, last) and returns true.
     pub fn maybe_decr_to(&mut self, rejected: u64, last: u64) -> bool {
@@ -240,34 +381,69 @@ pub struct Inflights {
     pub start: usize,
     // number of inflights in the buffer
     pub count: usize,
+    // the max number of inflights
+    pub size: usize,
     // buffer contains the index of the last entry
-    // inside one message.
+    // inside one message. It grows on demand up to size, so that peers which
+    // never fill their window don't pay for it.
     pub buffer: Vec<u64>,
+    // sizes contains the size in bytes of the message at the same position in
+    // buffer, it is only tracked when max_bytes is set.
+    pub sizes: Vec<u64>,
+    // bytes is the total size of the inflight messages.
+    pub bytes: u64,
+    // max_bytes limits bytes, 0 for no limit.
+    pub max_bytes: u64,
 }
 
 impl Inflights {
     pub fn new(cap: usize) -> Inflights {
         Inflights {
-            buffer: Vec::with_capacity(cap),
+            size: cap,
             ..Default::default()
         }
     }
 
+    pub fn with_max_bytes(cap: usize, max_bytes: u64) -> Inflights {
+        let mut ins = Inflights::new(cap);
+        ins.max_bytes = max_bytes;
+        ins
+    }
+
     fn reset(&mut self) {
         self.start = 0;
         self.count = 0;
+        self.bytes = 0;
     }
 
+    // full returns true if no more messages can be sent, either because there
+    // are as many inflight messages as the capacity or because their total size
+    // reached max_bytes.
     pub fn full(&self) -> bool {
-        self.count == self.cap()
+        self.count == self.cap() || (self.max_bytes != 0 && self.bytes >= self.max_bytes)
     }
 
-    fn cap(&self) -> usize {
-        self.buffer.capacity()
+    // cap returns the max number of inflight messages.
+    pub fn cap(&self) -> usize {
+        self.size
+    }
+
+    // oldest returns the last entry index of the oldest inflight message, if any.
+    pub fn oldest(&self) -> Option<u64> {
+        if self.count == 0 {
+            None
+        } else {
+            Some(self.buffer[self.start])
+        }
     }
 
     // add adds an inflight into inflights
     pub(crate) fn add(&mut self, inflight: u64) {
+        self.add_with_bytes(inflight, 0);
+    }
+
+    // add_with_bytes adds an inflight of the given size in bytes into inflights.
+    pub(crate) fn add_with_bytes(&mut self, inflight: u64, bytes: u64) {
         if self.full() {
             panic!("cannot add into a full inflights");
         }
@@ -278,13 +454,35 @@ impl Inflights {
         }
 
         if next == self.buffer.len() {
+            self.grow();
             self.buffer.push(inflight);
         } else {
             self.buffer[next] = inflight;
         }
+        if self.max_bytes != 0 {
+            if next == self.sizes.len() {
+                self.sizes.push(bytes);
+            } else {
+                self.sizes[next] = bytes;
+            }
+            self.bytes += bytes;
+        }
         self.count += 1;
     }
 
+    // grow doubles the buffer, up to size, once it is used up.
+    fn grow(&mut self) {
+        let len = self.buffer.len();
+        if len < self.buffer.capacity() {
+            return;
+        }
+        let new_len = cmp::min(cmp::max(len * 2, 1), self.size);
+        self.buffer.reserve_exact(new_len - len);
+        if self.max_bytes != 0 {
+            self.sizes.reserve_exact(new_len - self.sizes.len());
+        }
+    }
+
     pub(crate) fn free_first_one(&mut self) {
         let to = self.buffer[self.start];
         self.free_to(to);
@@ -303,6 +501,9 @@ impl Inflights {
             if to < self.buffer[idx] {
                 break;
             }
+            if self.max_bytes != 0 {
+                self.bytes -= self.sizes[idx];
+            }
 
             let size = self.cap();
             // increase index and maybe rotate
@@ -338,7 +539,9 @@ mod test {
         let wantin = Inflights {
             start: 0,
             count: 5,
+            size: 10,
             buffer: vec![0, 1, 2, 3, 4],
+            ..Default::default()
         };
 
         assert_eq!(inflight, wantin);
@@ -350,17 +553,19 @@ mod test {
         let wantin2 = Inflights {
             start: 0,
             count: 10,
+            size: 10,
             buffer: vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9],
+            ..Default::default()
         };
 
         assert_eq!(inflight, wantin2);
 
         let mut inflight2 = Inflights {
             start: 5,
-            buffer: Vec::with_capacity(10),
+            size: 10,
+            buffer: vec![0, 0, 0, 0, 0],
             ..Default::default()
         };
-        inflight2.buffer.extend_from_slice(&vec![0, 0, 0, 0, 0]);
 
         for i in 0..5 {
             inflight2.add(i);
@@ -369,7 +574,9 @@ mod test {
         let wantin21 = Inflights {
             start: 5,
             count: 5,
+            size: 10,
             buffer: vec![0, 0, 0, 0, 0, 0, 1, 2, 3, 4],
+            ..Default::default()
         };
 
         assert_eq!(inflight2, wantin21);
@@ -381,7 +588,9 @@ mod test {
         let wantin22 = Inflights {
             start: 5,
             count: 10,
+            size: 10,
             buffer: vec![5, 6, 7, 8, 9, 0, 1, 2, 3, 4],
+            ..Default::default()
         };
 
         assert_eq!(inflight2, wantin22);
@@ -399,7 +608,9 @@ mod test {
         let wantin = Inflights {
             start: 5,
             count: 5,
+            size: 10,
             buffer: vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9],
+            ..Default::default()
         };
 
         assert_eq!(inflight, wantin);
@@ -409,7 +620,9 @@ mod test {
         let wantin2 = Inflights {
             start: 9,
             count: 1,
+            size: 10,
             buffer: vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9],
+            ..Default::default()
         };
 
         assert_eq!(inflight, wantin2);
@@ -423,7 +636,9 @@ mod test {
         let wantin3 = Inflights {
             start: 3,
             count: 2,
+            size: 10,
             buffer: vec![10, 11, 12, 13, 14, 5, 6, 7, 8, 9],
+            ..Default::default()
         };
 
         assert_eq!(inflight, wantin3);
@@ -433,7 +648,9 @@ mod test {
         let wantin4 = Inflights {
             start: 0,
             count: 0,
+            size: 10,
             buffer: vec![10, 11, 12, 13, 14, 5, 6, 7, 8, 9],
+            ..Default::default()
         };
 
         assert_eq!(inflight, wantin4);
@@ -451,9 +668,85 @@ mod test {
         let wantin = Inflights {
             start: 1,
             count: 9,
+            size: 10,
             buffer: vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9],
+            ..Default::default()
         };
 
         assert_eq!(inflight, wantin);
     }
+
+    #[test]
+    fn test_inflight_max_bytes() {
+        let mut inflight = Inflights::with_max_bytes(10, 100);
+        for i in 0..3 {
+            inflight.add_with_bytes(i, 30);
+        }
+        assert!(!inflight.full());
+        inflight.add_with_bytes(3, 30);
+        assert_eq!(inflight.bytes, 120);
+        assert!(inflight.full());
+
+        inflight.free_to(1);
+        assert_eq!(inflight.bytes, 60);
+        assert!(!inflight.full());
+
+        // wrap around the buffer.
+        for i in 4..10 {
+            inflight.add_with_bytes(i, 1);
+        }
+        inflight.add_with_bytes(10, 1);
+        assert_eq!(inflight.bytes, 67);
+        inflight.free_to(10);
+        assert_eq!(inflight.bytes, 0);
+        assert_eq!(inflight.count, 0);
+
+        // a single message may exceed max_bytes.
+        assert!(!inflight.full());
+        inflight.add_with_bytes(11, 1000);
+        assert!(inflight.full());
+    }
+
+    #[test]
+    fn test_inflight_grow() {
+        let mut inflight = Inflights::new(10);
+        assert_eq!(inflight.cap(), 10);
+        assert_eq!(inflight.buffer.capacity(), 0);
+
+        // the buffer doubles as it fills up, and never exceeds size.
+        for (i, &wcap) in [1, 2, 4, 4, 8, 8, 8, 8, 10, 10].iter().enumerate() {
+            inflight.add(i as u64);
+            assert_eq!(inflight.buffer.capacity(), wcap, "#{}", i);
+        }
+        assert!(inflight.full());
+
+        // freed slots are reused without growing.
+        inflight.free_to(4);
+        for i in 10..15 {
+            inflight.add(i);
+        }
+        assert_eq!(inflight.buffer.capacity(), 10);
+        assert_eq!(inflight.buffer, vec![10, 11, 12, 13, 14, 5, 6, 7, 8, 9]);
+    }
+
+    #[test]
+    fn test_inflight_occupancy() {
+        let mut inflight = Inflights::new(4);
+        assert_eq!(inflight.cap(), 4);
+        assert_eq!(inflight.oldest(), None);
+
+        for i in 1..5 {
+            inflight.add(i * 10);
+        }
+        assert_eq!(inflight.count, 4);
+        assert_eq!(inflight.oldest(), Some(10));
+
+        inflight.free_to(25);
+        inflight.add(50);
+        assert_eq!(inflight.count, 3);
+        assert_eq!(inflight.oldest(), Some(30));
+
+        inflight.free_to(50);
+        assert_eq!(inflight.oldest(), None);
+    }
 }
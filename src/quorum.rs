@@ -0,0 +1,261 @@
+use std::collections::{HashMap, HashSet};
+
+/// QuorumPolicy decides whether a set of voters forms a quorum. voters is the
+/// current voter configuration; ids not in it must be ignored. Every election
+/// quorum must intersect every election and replication quorum, which raft
+/// can't check.
+pub trait QuorumPolicy: Send {
+    /// is_election_quorum returns true if the votes of granted, which
+    /// includes the candidate, win an election.
+    fn is_election_quorum(&self, voters: &[u64], granted: &[u64]) -> bool;
+
+    /// is_replication_quorum returns true if an entry stored by acked, which
+    /// includes the leader, is committed. It also decides whether the leader
+    /// is still confirmed by enough voters for check quorum and ReadIndex.
+    fn is_replication_quorum(&self, voters: &[u64], acked: &[u64]) -> bool;
+}
+
+fn count_voters(voters: &[u64], ids: &[u64]) -> usize {
+    ids.iter().filter(|id| voters.contains(id)).count()
+}
+
+fn majority(voters: &[u64]) -> usize {
+    voters.len() / 2 + 1
+}
+
+/// MajorityQuorum requires a simple majority of the voters for everything.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct MajorityQuorum;
+
+impl QuorumPolicy for MajorityQuorum {
+    fn is_election_quorum(&self, voters: &[u64], granted: &[u64]) -> bool {
+        count_voters(voters, granted) >= majority(voters)
+    }
+
+    fn is_replication_quorum(&self, voters: &[u64], acked: &[u64]) -> bool {
+        count_voters(voters, acked) >= majority(voters)
+    }
+}
+
+/// MajorityConfig is a set of voters deciding by simple majority.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct MajorityConfig {
+    voters: Vec<u64>,
+}
+
+impl MajorityConfig {
+    pub fn new(voters: Vec<u64>) -> MajorityConfig {
+        MajorityConfig { voters }
+    }
+
+    pub fn voters(&self) -> &[u64] {
+        &self.voters
+    }
+
+    /// committed_index returns the highest index acked by a majority of the
+    /// voters, given the acked index of each of them; the voters for which
+    /// acked returns None count as 0. An empty config returns u64::MAX, so that it
+    /// doesn't hold back the other half of a joint config.
+    pub fn committed_index<F>(&self, acked: F) -> u64
+    where
+        F: Fn(u64) -> Option<u64>,
+    {
+        if self.voters.is_empty() {
+            return u64::MAX;
+        }
+        let mut indexes: Vec<u64> = self
+            .voters
+            .iter()
+            .map(|&id| acked(id).unwrap_or(0))
+            .collect();
+        indexes.sort_by(|a, b| b.cmp(a));
+        indexes[majority(&self.voters) - 1]
+    }
+}
+
+/// JointConfig is the pair of majority configs of a joint config, the
+/// outgoing one being empty outside of a joint config. Decisions need a
+/// majority of both.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct JointConfig {
+    incoming: MajorityConfig,
+    outgoing: MajorityConfig,
+}
+
+impl JointConfig {
+    pub fn new(incoming: MajorityConfig, outgoing: MajorityConfig) -> JointConfig {
+        JointConfig { incoming, outgoing }
+    }
+
+    /// committed_index returns the highest index acked by a majority of both
+    /// configs, see MajorityConfig::committed_index.
+    pub fn committed_index<F>(&self, acked: F) -> u64
+    where
+        F: Fn(u64) -> Option<u64>,
+    {
+        let incoming = self.incoming.committed_index(&acked);
+        let outgoing = self.outgoing.committed_index(&acked);
+        incoming.min(outgoing)
+    }
+}
+
+/// FlexibleQuorum commits entries once `replication` voters store them, and
+/// in exchange requires enough votes for an election to intersect every such
+/// replication quorum: n - replication + 1 of n voters, and never less than a
+/// majority. Both sizes are clamped to the number of voters.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FlexibleQuorum {
+    replication: usize,
+}
+
+impl FlexibleQuorum {
+    pub fn new(replication: usize) -> FlexibleQuorum {
+        if replication == 0 {
+            panic!("replication quorum must be greater than 0");
+        }
+        FlexibleQuorum { replication }
+    }
+
+    fn replication_size(&self, voters: &[u64]) -> usize {
+        self.replication.min(voters.len())
+    }
+
+    fn election_size(&self, voters: &[u64]) -> usize {
+        let n = voters.len();
+        (n + 1 - self.replication_size(voters)).max(majority(voters))
+    }
+}
+
+impl QuorumPolicy for FlexibleQuorum {
+    fn is_election_quorum(&self, voters: &[u64], granted: &[u64]) -> bool {
+        count_voters(voters, granted) >= self.election_size(voters)
+    }
+
+    fn is_replication_quorum(&self, voters: &[u64], acked: &[u64]) -> bool {
+        count_voters(voters, acked) >= self.replication_size(voters)
+    }
+}
+
+/// ZoneQuorum tags voters with a failure domain, e.g. a rack or a zone, and
+/// commits entries once a majority of the voters stores them and that
+/// majority spans at least `min_domains` distinct domains, so that a single
+/// domain can never form the whole commit quorum. Elections need a simple
+/// majority, which intersects every such replication quorum.
+///
+/// Untagged voters count towards the majority but not towards the domains.
+/// min_domains is clamped to the number of domains of the current voters.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ZoneQuorum {
+    domains: HashMap<u64, String>,
+    min_domains: usize,
+}
+
+impl ZoneQuorum {
+    pub fn new(domains: HashMap<u64, String>, min_domains: usize) -> ZoneQuorum {
+        ZoneQuorum {
+            domains,
+            min_domains,
+        }
+    }
+
+    fn count_domains(&self, voters: &[u64], ids: &[u64]) -> usize {
+        ids.iter()
+            .filter(|id| voters.contains(id))
+            .filter_map(|id| self.domains.get(id))
+            .collect::<HashSet<_>>()
+            .len()
+    }
+}
+
+impl QuorumPolicy for ZoneQuorum {
+    fn is_election_quorum(&self, voters: &[u64], granted: &[u64]) -> bool {
+        count_voters(voters, granted) >= majority(voters)
+    }
+
+    fn is_replication_quorum(&self, voters: &[u64], acked: &[u64]) -> bool {
+        let min_domains = self.min_domains.min(self.count_domains(voters, voters));
+        count_voters(voters, acked) >= majority(voters)
+            && self.count_domains(voters, acked) >= min_domains
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_majority_quorum() {
+        let voters = vec![1, 2, 3, 4];
+        let q = MajorityQuorum;
+        assert!(!q.is_election_quorum(&voters, &[1, 2]));
+        assert!(q.is_election_quorum(&voters, &[1, 2, 3]));
+        // non voters don't count.
+        assert!(!q.is_replication_quorum(&voters, &[1, 2, 5]));
+        assert!(q.is_replication_quorum(&voters, &[1, 2, 4]));
+    }
+
+    #[test]
+    fn test_majority_config_committed_index() {
+        let acked: HashMap<u64, u64> = vec![(1, 5), (2, 3), (3, 9), (4, 1)].into_iter().collect();
+        let acked = |id| acked.get(&id).cloned();
+        let tests = vec![
+            (vec![], u64::MAX),
+            (vec![1], 5),
+            (vec![1, 2], 3),
+            (vec![1, 2, 3], 5),
+            (vec![1, 2, 3, 4], 3),
+            // voters which haven't acked count as 0.
+            (vec![1, 5, 6], 0),
+        ];
+        for (i, (voters, windex)) in tests.into_iter().enumerate() {
+            let c = MajorityConfig::new(voters);
+            assert_eq!(c.committed_index(acked), windex, "#{}", i);
+        }
+
+        let c = JointConfig::new(
+            MajorityConfig::new(vec![1, 3]),
+            MajorityConfig::new(vec![2, 3, 4]),
+        );
+        assert_eq!(c.committed_index(acked), 3);
+        let c = JointConfig::new(MajorityConfig::new(vec![1, 3]), MajorityConfig::default());
+        assert_eq!(c.committed_index(acked), 5);
+    }
+
+    #[test]
+    fn test_flexible_quorum() {
+        let voters = vec![1, 2, 3, 4, 5];
+        let q = FlexibleQuorum::new(2);
+        assert!(q.is_replication_quorum(&voters, &[1, 2]));
+        assert!(!q.is_replication_quorum(&voters, &[1]));
+        assert!(!q.is_election_quorum(&voters, &[1, 2, 3]));
+        assert!(q.is_election_quorum(&voters, &[1, 2, 3, 4]));
+
+        // the election quorum is never less than a majority.
+        let q = FlexibleQuorum::new(5);
+        assert!(q.is_election_quorum(&voters, &[1, 2, 3]));
+        assert!(!q.is_replication_quorum(&voters, &[1, 2, 3, 4]));
+
+        // sizes are clamped when voters are removed.
+        let q = FlexibleQuorum::new(4);
+        assert!(q.is_replication_quorum(&[1, 2, 3], &[1, 2, 3]));
+        assert!(q.is_election_quorum(&[1, 2, 3], &[1, 2]));
+    }
+
+    #[test]
+    fn test_zone_quorum() {
+        let voters = vec![1, 2, 3, 4, 5];
+        let domains = vec![(1, "a"), (2, "a"), (3, "a"), (4, "b"), (5, "c")]
+            .into_iter()
+            .map(|(id, d)| (id, d.to_string()))
+            .collect();
+        let q = ZoneQuorum::new(domains, 2);
+        // a majority within a single domain doesn't commit.
+        assert!(!q.is_replication_quorum(&voters, &[1, 2, 3]));
+        assert!(q.is_replication_quorum(&voters, &[1, 2, 4]));
+        assert!(!q.is_replication_quorum(&voters, &[1, 4]));
+        assert!(q.is_election_quorum(&voters, &[1, 2, 3]));
+
+        // min_domains is clamped when domains are removed.
+        assert!(q.is_replication_quorum(&[1, 2, 3], &[1, 2]));
+    }
+}
@@ -3,28 +3,62 @@ use std::collections::HashMap;
 use std::mem;
 
 use errors::{Error, Result, StorageError};
-use progress::{Progress, ProgressState};
-use protobuf::RepeatedField;
+use progress::{Inflights, PausedReason, Progress, ProgressState};
+use protobuf::Message as PbMessage;
+use protobuf::{RepeatedField, UnknownFields};
+use quorum::QuorumPolicy;
 use raft_log::RaftLog;
-use raftpb::{Entry, EntryType, HardState, Message, MessageType, Snapshot};
+use raftpb::{
+	ConfChangeSingle, ConfChangeType, ConfChangeV2, ConfState, Entry, EntryType, HardState,
+	HardStateExtension, Message, MessageType, Snapshot,
+};
 use raw_node::SoftState;
 use read_only::{ReadOnly, ReadOnlyOption, ReadState};
 use storage::Storage;
-use util::{num_of_pending_conf, vote_msg_resp_type, NO_LIMIT};
+use tracker::{ProgressTracker, VoteResult};
+use util::{
+	is_conf_change, is_leave_joint, num_of_pending_conf, set_entry_checksum, vote_msg_resp_type,
+	NO_LIMIT,
+};
 
 use rand::{self, Rng};
 
 // A constant represents invalid id of raft.
 pub const NONE: u64 = 0;
 
-// CAMPAIGN_PRE_ELECTION represents the first phase of a normal election when
-// Config.pre_vote is true.
-const CAMPAIGN_PRE_ELECTION: &[u8] = b"CampaignPreElection";
-// CAMPAIGN_ELECTION represents a normal (time-based) election (the second phase
-// of the election when Config.pre_vote is true).
-const CAMPAIGN_ELECTION: &[u8] = b"CampaignElection";
-// CAMPAIGN_TRANSFER represents the type of leader transfer.
-const CAMPAIGN_TRANSFER: &[u8] = b"CampaignTransfer";
+// PROBE_MAX_MSG_SIZE caps the entries sent to a peer in probe state, whose
+// log position is unknown and which is likely to reject them.
+const PROBE_MAX_MSG_SIZE: u64 = 4096;
+
+/// CampaignType is the kind of an election a node starts.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum CampaignType {
+	/// PreElection is the first phase of a normal election when
+	/// Config.pre_vote is true.
+	PreElection,
+	/// Election is a normal (time-based) election, the second phase of the
+	/// election when Config.pre_vote is true.
+	Election,
+	/// Transfer is an election started by a leadership transfer. It skips
+	/// pre-vote, and voters grant it despite check_quorum leases.
+	Transfer,
+}
+
+impl CampaignType {
+	// context is carried by the vote requests of the campaign.
+	fn context(self) -> &'static [u8] {
+		match self {
+			CampaignType::PreElection => b"CampaignPreElection",
+			CampaignType::Election => b"CampaignElection",
+			CampaignType::Transfer => b"CampaignTransfer",
+		}
+	}
+}
+
+// payloads_size returns the total size of the data of the entries.
+fn payloads_size(ents: &[Entry]) -> u64 {
+	ents.iter().map(|e| e.get_data().len() as u64).sum()
+}
 
 #[derive(Debug, Default, PartialEq)]
 pub struct Status {
@@ -33,6 +67,11 @@ pub struct Status {
 	pub soft_state: SoftState,
 	pub applied: u64,
 	pub progress: HashMap<u64, Progress>,
+	// paused holds why the leader is not sending entries to a peer, for every
+	// peer it is not.
+	pub paused: HashMap<u64, PausedReason>,
+	// lead_transferee is the target of the leadership transfer in progress,
+	// also reported in soft_state.
 	pub lead_transferee: u64,
 }
 
@@ -69,8 +108,9 @@ pub struct Config {
 
 	/// applied is the last applied index. It should only be set when restarting
 	/// raft. raft will not return entries to the application smaller or equal to
-	/// Applied. If Applied is unset when restarting, raft might return previous
-	/// applied entries. This is a very application dependent configuration.
+	/// Applied. If Applied is unset when restarting, raft uses the applied index
+	/// persisted in Storage, if any, and might otherwise return previous applied
+	/// entries. This is a very application dependent configuration.
 	pub applied: u64,
 
 	/// max_size_per_msg limits the max size of each append message. Smaller value
@@ -80,6 +120,13 @@ pub struct Config {
 	/// message.
 	pub max_size_per_msg: u64,
 
+	/// max_committed_size_per_ready limits the size in bytes of the committed
+	/// entries returned by a single Ready, so that a node restarting far
+	/// behind its commit index doesn't load them all at once; the rest is
+	/// returned by the following Readies. At least one entry is always
+	/// returned. 0 for no limit.
+	pub max_committed_size_per_ready: u64,
+
 	/// max_inflight_msgs limits the max number of in-flight append messages during
 	/// optimistic replication phase. The application transportation layer usually
 	/// has its own sending buffer over TCP/UDP. Setting MaxInflightMsgs to avoid
@@ -87,6 +134,78 @@ pub struct Config {
 	/// limit the proposal rate?
 	pub max_inflight_msgs: u64,
 
+	/// adaptive_inflight tunes the inflight limit of every peer in replicate
+	/// state: the limit is halved whenever the peer rejects an append or is
+	/// reported unreachable, and grows by one message after each window of
+	/// acknowledged appends, back up to max_inflight_msgs. This keeps a slow or
+	/// flaky peer from being flooded while fast ones can use the full window.
+	pub adaptive_inflight: bool,
+
+	/// max_inflight_bytes limits the total size in bytes of in-flight append
+	/// messages to a peer, in addition to their number. A single message is
+	/// always allowed, whatever its size. 0 for no limit.
+	pub max_inflight_bytes: u64,
+
+	/// max_probe_backoff enables an exponential backoff of the probing of peers
+	/// reported unreachable: after n consecutive MsgUnreachable reports without
+	/// any response from the peer, the leader skips the next
+	/// min(2^n - 1, max_probe_backoff) heartbeats to it. Any response resets the
+	/// backoff. 0 disables the backoff.
+	pub max_probe_backoff: u64,
+
+	/// replication_budget limits the bytes of append and snapshot messages the
+	/// leader sends per tick. Voters are always sent to and consume the budget
+	/// first, learners are only sent to while some budget is left, in
+	/// round-robin order, so that catching up several learners at once can not
+	/// delay the replication needed for commit. 0 for no limit.
+	pub replication_budget: u64,
+
+	/// ack_persisted_only makes the raft count and acknowledge only entries
+	/// that have been persisted, as reported by RawNode::advance: the leader's
+	/// own log contributes to commit only up to its persisted index, and
+	/// followers acknowledge appends only up to theirs, sending another
+	/// MsgAppResp once more is persisted. Commit then never outruns
+	/// durability, even when the messages of a Ready are sent before its
+	/// entries are persisted.
+	pub ack_persisted_only: bool,
+
+	/// max_proposal_size limits the size in bytes of the data of a single
+	/// proposed entry. Larger proposals are refused with ProposalTooLarge
+	/// before they reach the log. 0 for no limit.
+	pub max_proposal_size: u64,
+
+	/// max_proposals_per_tick and max_proposal_bytes_per_tick limit the rate
+	/// at which RawNode accepts proposals from its clients, see the rate_limit
+	/// module. Unused allowance accumulates for up to proposal_burst_ticks
+	/// ticks. Proposals over the limit are refused with ProposalRateLimited.
+	/// 0 for no limit.
+	pub max_proposals_per_tick: u64,
+	pub max_proposal_bytes_per_tick: u64,
+	pub proposal_burst_ticks: u64,
+
+	/// max_uncommitted_entries and max_uncommitted_bytes limit how far the
+	/// leader's log may run ahead of its commit index, in entries and in bytes
+	/// of entry data. While a limit is reached, the leader drops new proposals
+	/// with ProposalDropped until followers catch up. A proposal is always
+	/// accepted while nothing is uncommitted, whatever its size. 0 for no
+	/// limit.
+	pub max_uncommitted_entries: u64,
+	pub max_uncommitted_bytes: u64,
+
+	/// batch_read_index delays confirming ReadIndex requests with
+	/// ReadOnlyOption::Safe until the next heartbeat, instead of broadcasting a
+	/// heartbeat round for every request. All requests received in between are
+	/// then confirmed together by a single round, at the cost of up to a
+	/// heartbeat interval of extra latency.
+	pub batch_read_index: bool,
+
+	/// priority is the election priority of this node. A node does not grant
+	/// its (pre-)vote to a candidate with a lower priority whose log is not
+	/// ahead of its own, so that leadership stays with the higher priority
+	/// nodes, e.g. those in a preferred zone, whenever they are up to date.
+	/// Leader transfers ignore priorities.
+	pub priority: u64,
+
 	/// check_quorum specifies if the leader should check quorum activity. Leader
 	/// steps down when quorum is not active for an electionTimeout.
 	pub check_quorum: bool,
@@ -119,6 +238,14 @@ pub struct Config {
 	/// to the leader.
 	pub disable_proposal_forwarding: bool,
 
+	/// entry_checksum enables a CRC-32C checksum of every proposed entry
+	/// payload, computed when the proposal is stepped. Entries carrying a
+	/// checksum are verified before being handed to the application as
+	/// committed entries, so that corruption introduced by the transport or
+	/// storage is detected before it reaches the state machine. Only the data
+	/// is covered, see verify_entry_checksum for what goes undetected.
+	pub entry_checksum: bool,
+
 	/// tag used for logger.
 	pub tag: String,
 }
@@ -188,12 +315,18 @@ pub struct Raft<T: Storage> {
 	pub read_states: Vec<ReadState>,
 	pub raft_log: RaftLog<T>,
 	pub max_inflight: u64,
+	pub max_inflight_bytes: u64,
+	pub max_probe_backoff: u64,
+	pub replication_budget: u64,
+	// bytes sent to peers since the last tick, and the learner last sent to by
+	// bcast_append, when a replication budget is set.
+	replication_bytes: u64,
+	last_learner_sent: u64,
 	pub max_msg_size: u64,
-	pub prs: HashMap<u64, Progress>,
-	pub learner_prs: HashMap<u64, Progress>,
+	// tracker holds the Progress of the peers, the votes and the config.
+	pub tracker: ProgressTracker,
 	pub state: StateType,
 	pub is_learner: bool,
-	pub votes: HashMap<u64, bool>,
 	pub msgs: Vec<Message>,
 
 	// the leader id
@@ -208,9 +341,14 @@ pub struct Raft<T: Storage> {
 	// is set to a value >= the log index of the latest pending
 	// configuration change (if any). Config changes are only allowed to
 	// be proposed if the leader's applied index is greater than this
-	// value.
+	// value, and are dropped with ProposalDropped otherwise. While the
+	// config is joint, only the conf change leaving it may be proposed.
 	pub pending_conf_index: u64,
 
+	// pending_replace is the (old, new) pair of the node replacement in
+	// progress, see replace_node.
+	pub pending_replace: Option<(u64, u64)>,
+
 	pub read_only: ReadOnly,
 
 	// number of ticks since it reached last electionTimeout when it is leader
@@ -234,9 +372,35 @@ pub struct Raft<T: Storage> {
 	// when raft changes its state to follower or candidate.
 	pub randomized_election_timeout: u64,
 	pub disable_proposal_forwarding: bool,
+	pub entry_checksum: bool,
+	pub adaptive_inflight: bool,
+	pub ack_persisted_only: bool,
+	pub max_proposal_size: u64,
+	pub max_uncommitted_entries: u64,
+	pub max_uncommitted_bytes: u64,
+	pub batch_read_index: bool,
+	pub priority: u64,
+	// data bytes of the entries proposed to this leader and not yet committed.
+	uncommitted_size: u64,
+	// peers the leader failed to send a snapshot to because it was
+	// temporarily unavailable, retried by snapshot_ready.
+	snapshot_waiters: Vec<u64>,
+	// highest index known to match the current leader's log, which a follower
+	// acknowledges once it is persisted when ack_persisted_only is set.
+	verified_index: u64,
+	// append_budget is advertised to the leader in every MsgAppResp, see
+	// set_append_budget.
+	append_budget: u64,
+
+	// fields of the loaded HardState unknown to this version. They are kept
+	// and written back with every HardState, so that persisting state does
+	// not strip data written by a newer version during a rolling upgrade.
+	hard_state_unknown_fields: UnknownFields,
+	// extensions persisted along with HardState, sorted by id.
+	hard_state_extensions: Vec<HardStateExtension>,
 
 	/// tag only used for logger.
-	tag: String,
+	pub(crate) tag: String,
 }
 
 impl<T: Storage> Raft<T> {
@@ -247,7 +411,11 @@ impl<T: Storage> Raft<T> {
 	pub fn new(c: &mut Config, storage: T) -> Raft<T> {
 		c.validate().expect("configuration is invalid");
 		let (hard_state, conf_state) = storage.initial_state().unwrap();
-		let raft_log = RaftLog::new(storage, c.tag.clone());
+		let stored_applied = storage.applied_index().unwrap();
+		let mut raft_log = RaftLog::new(storage, c.tag.clone());
+		if c.max_committed_size_per_ready != 0 {
+			raft_log.max_next_ents_size = c.max_committed_size_per_ready;
+		}
 
 		let mut peers: &[u64] = &c.peers;
 		let mut learners: &[u64] = &c.learners;
@@ -260,6 +428,7 @@ impl<T: Storage> Raft<T> {
 			peers = &conf_state.get_nodes();
 			learners = &conf_state.get_learners();
 		}
+		let outgoing = conf_state.get_voters_outgoing();
 
 		let mut r = Raft {
 			id: c.id,
@@ -269,15 +438,19 @@ impl<T: Storage> Raft<T> {
 			raft_log,
 			max_msg_size: c.max_size_per_msg,
 			max_inflight: c.max_inflight_msgs,
-			prs: HashMap::new(),
-			learner_prs: HashMap::new(),
+			max_inflight_bytes: c.max_inflight_bytes,
+			max_probe_backoff: c.max_probe_backoff,
+			replication_budget: c.replication_budget,
+			replication_bytes: 0,
+			last_learner_sent: NONE,
+			tracker: ProgressTracker::new(),
 			state: Default::default(),
 			is_learner: false,
-			votes: HashMap::new(),
 			msgs: Default::default(),
 			lead: NONE,
 			lead_transferee: Default::default(),
 			pending_conf_index: Default::default(),
+			pending_replace: None,
 			read_only: ReadOnly::new(c.read_only_option),
 			election_elapsed: Default::default(),
 			heartbeat_elapsed: Default::default(),
@@ -286,20 +459,40 @@ impl<T: Storage> Raft<T> {
 			heartbeat_timeout: c.heartbeat_tick,
 			election_timeout: c.election_tick,
 			randomized_election_timeout: Default::default(),
+			hard_state_unknown_fields: Default::default(),
+			hard_state_extensions: vec![],
 			tag: c.tag.clone(),
 			disable_proposal_forwarding: c.disable_proposal_forwarding,
+			entry_checksum: c.entry_checksum,
+			adaptive_inflight: c.adaptive_inflight,
+			ack_persisted_only: c.ack_persisted_only,
+			max_proposal_size: c.max_proposal_size,
+			max_uncommitted_entries: c.max_uncommitted_entries,
+			max_uncommitted_bytes: c.max_uncommitted_bytes,
+			batch_read_index: c.batch_read_index,
+			priority: c.priority,
+			uncommitted_size: 0,
+			snapshot_waiters: vec![],
+			verified_index: 0,
+			append_budget: 0,
 		};
 
-		for &p in peers {
-			r.prs
-				.insert(p, Progress::new(1, r.max_inflight as usize, false));
+		for &p in peers.iter().chain(outgoing) {
+			let pr = r.new_progress(1, false);
+			r.tracker.prs.insert(p, pr);
 		}
+		r.tracker.set_joint(
+			peers,
+			outgoing,
+			conf_state.get_learners_next(),
+			conf_state.get_auto_leave(),
+		);
 		for &p in learners {
-			if r.prs.contains_key(&p) {
+			if r.tracker.prs.contains_key(&p) {
 				panic!("node {} in both learner and peer list", p);
 			}
-			r.learner_prs
-				.insert(p, Progress::new(1, r.max_inflight as usize, true));
+			let pr = r.new_progress(1, true);
+			r.tracker.learner_prs.insert(p, pr);
 			if r.id == p {
 				r.is_learner = true;
 			}
@@ -311,6 +504,8 @@ impl<T: Storage> Raft<T> {
 
 		if c.applied > 0 {
 			r.raft_log.applied_to(c.applied);
+		} else if stored_applied > r.raft_log.applied {
+			r.raft_log.applied_to(stored_applied);
 		}
 		let term = r.term;
 		r.become_follower(term, NONE);
@@ -341,13 +536,20 @@ impl<T: Storage> Raft<T> {
 
 		if s.soft_state.raft_state == StateType::Leader {
 			s.progress = HashMap::new();
-			for (&id, p) in &self.prs {
+			for (&id, p) in &self.tracker.prs {
 				s.progress.insert(id, p.clone());
 			}
 
-			for (&id, p) in &self.learner_prs {
+			for (&id, p) in &self.tracker.learner_prs {
 				s.progress.insert(id, p.clone());
 			}
+
+			s.paused = s
+				.progress
+				.iter()
+				.filter(|&(&id, _)| id != self.id)
+				.filter_map(|(&id, p)| p.paused_reason().map(|r| (id, r)))
+				.collect();
 		}
 
 		s
@@ -366,6 +568,9 @@ impl<T: Storage> Raft<T> {
 		self.raft_log.committed = state.commit;
 		self.term = state.term;
 		self.vote = state.vote;
+		self.hard_state_unknown_fields = state.get_unknown_fields().clone();
+		self.hard_state_extensions = state.get_extensions().to_vec();
+		self.hard_state_extensions.sort_by_key(|e| e.get_id());
 	}
 
 	pub fn append_entry(&mut self, ents: &mut [Entry]) {
@@ -379,22 +584,83 @@ impl<T: Storage> Raft<T> {
 		let id = self.id;
 
 		// use latest "last" index after truncate/append
+		let li = self.durable_index(li);
 		self.get_mut_progress(id).unwrap().maybe_update(li);
 		// Regardless of maybe_commit's return, our caller will call bcast_append.
 		self.maybe_commit();
 	}
 
+	// durable_index caps an index this node counts or acknowledges as stored
+	// by the persisted index, when ack_persisted_only is set.
+	fn durable_index(&self, index: u64) -> u64 {
+		if self.ack_persisted_only {
+			cmp::min(index, self.raft_log.persisted)
+		} else {
+			index
+		}
+	}
+
+	/// on_persisted is called after entries or a snapshot have been reported
+	/// persisted through raft_log.stable_to or stable_snap_to. When
+	/// ack_persisted_only is set, the leader counts the newly persisted
+	/// entries towards commit, and a follower acknowledges them to the leader.
+	pub fn on_persisted(&mut self) {
+		if !self.ack_persisted_only {
+			return;
+		}
+
+		let persisted = self.raft_log.persisted;
+		if self.state == StateType::Leader {
+			let id = self.id;
+			let updated = match self.get_mut_progress(id) {
+				Some(pr) => pr.maybe_update(persisted),
+				None => false,
+			};
+			if updated && self.maybe_commit() {
+				self.bcast_append();
+			}
+		} else if self.lead != NONE && self.verified_index != 0 {
+			let mut m = Message::new();
+			m.set_to(self.lead);
+			m.set_msg_type(MessageType::MsgAppResp);
+			m.set_index(cmp::min(self.verified_index, persisted));
+			self.send(m);
+		}
+	}
+
 	// maybe_commit attempts to advance the commit index. Returns true if
 	// the commit index changed (in which case the caller should call
 	// self.bcast_append).
 	pub fn maybe_commit(&mut self) -> bool {
-		let mut matched_indexs = Vec::with_capacity(self.prs.len());
-		for p in self.prs.values() {
-			matched_indexs.push(p.matched);
+		let max_matched_index = self.tracker.committed();
+		let committed = self.raft_log.committed;
+		if !self.raft_log.maybe_commit(max_matched_index, self.term) {
+			return false;
 		}
-		matched_indexs.sort_by(|a, b| b.cmp(a));
-		let max_matched_index = matched_indexs[self.quorum() - 1];
-		self.raft_log.maybe_commit(max_matched_index, self.term)
+		if self.uncommitted_size != 0 {
+			let ents = self
+				.raft_log
+				.slice(committed + 1, self.raft_log.committed + 1, NO_LIMIT)
+				.unwrap_or_default();
+			let size = payloads_size(&ents);
+			self.uncommitted_size = self.uncommitted_size.saturating_sub(size);
+		}
+		true
+	}
+
+	// within_uncommitted_limits returns true if the leader can append ents
+	// without exceeding max_uncommitted_entries or max_uncommitted_bytes.
+	fn within_uncommitted_limits(&self, ents: &[Entry]) -> bool {
+		let uncommitted = self.raft_log.last_index() - self.raft_log.committed;
+		if self.max_uncommitted_entries != 0
+			&& uncommitted > 0
+			&& uncommitted + ents.len() as u64 > self.max_uncommitted_entries
+		{
+			return false;
+		}
+		self.max_uncommitted_bytes == 0
+			|| self.uncommitted_size == 0
+			|| self.uncommitted_size + payloads_size(ents) <= self.max_uncommitted_bytes
 	}
 
 	pub fn become_follower(&mut self, term: u64, lead: u64) {
@@ -413,6 +679,7 @@ impl<T: Storage> Raft<T> {
 		self.reset(term);
 		self.lead = self.id;
 		self.state = StateType::Leader;
+		self.uncommitted_size = 0;
 
 		let ents = match self.raft_log.entries(self.raft_log.committed + 1, NO_LIMIT) {
 			Ok(ents) => ents,
@@ -453,7 +720,7 @@ impl<T: Storage> Raft<T> {
 		if self.state == StateType::Leader {
 			panic!("invalid transition [leader -> pre-candidate]")
 		}
-		self.votes = HashMap::new();
+		self.tracker.reset_votes();
 		self.state = StateType::PreCandidate;
 		info!(
 			"{} {} became pre-candidate at term {}",
@@ -471,26 +738,42 @@ impl<T: Storage> Raft<T> {
 		self.heartbeat_elapsed = 0;
 		self.reset_randomized_election_timeout();
 		self.abort_leader_transfer();
-		self.votes = HashMap::new();
+		self.tracker.reset_votes();
+		self.verified_index = 0;
+		self.snapshot_waiters.clear();
 
-		let (last_index, max_inflight) = (self.raft_log.last_index(), self.max_inflight);
+		let last_index = self.raft_log.last_index();
+		let self_matched = self.durable_index(last_index);
 		let self_id = self.id;
 
-		for (&id, pr) in &mut self.prs {
-			*pr = Progress::new(last_index + 1, max_inflight as usize, false);
+		let mut prs = self.take_prs();
+		for (&id, pr) in &mut prs {
+			let (replication_paused, send_queue_full) =
+				(pr.replication_paused, pr.send_queue_full);
+			*pr = self.new_progress(last_index + 1, false);
+			pr.replication_paused = replication_paused;
+			pr.send_queue_full = send_queue_full;
 			if id == self_id {
-				pr.matched = last_index;
+				pr.matched = self_matched;
 			}
 		}
-		for (&id, pr) in &mut self.learner_prs {
-			*pr = Progress::new(last_index + 1, max_inflight as usize, true);
+		self.set_prs(prs);
+		let mut learner_prs = self.take_learner_prs();
+		for (&id, pr) in &mut learner_prs {
+			let (replication_paused, send_queue_full) =
+				(pr.replication_paused, pr.send_queue_full);
+			*pr = self.new_progress(last_index + 1, true);
+			pr.replication_paused = replication_paused;
+			pr.send_queue_full = send_queue_full;
 			if id == self_id {
-				pr.matched = last_index;
+				pr.matched = self_matched;
 			}
 		}
+		self.set_learner_prs(learner_prs);
 
 		self.read_only = ReadOnly::new(self.read_only.option);
 		self.pending_conf_index = 0;
+		self.pending_replace = None;
 	}
 
 	pub fn reset_randomized_election_timeout(&mut self) {
@@ -505,20 +788,439 @@ impl<T: Storage> Raft<T> {
 		self.randomized_election_timeout = timeout;
 	}
 
+	/// transfer_leadership_auto transfers leadership to the recently active
+	/// voter with the most up-to-date log, e.g. before shutting the leader
+	/// down, and returns it. Nothing happens unless this node is the leader
+	/// and such a voter exists.
+	pub fn transfer_leadership_auto(&mut self) -> Option<u64> {
+		if self.state != StateType::Leader {
+			return None;
+		}
+		let self_id = self.id;
+		let transferee = self
+			.tracker
+			.prs
+			.iter()
+			.filter(|&(&id, pr)| id != self_id && pr.recent_active)
+			.max_by_key(|&(id, pr)| (pr.matched, cmp::Reverse(*id)))
+			.map(|(id, _)| *id)?;
+		let mut m = Message::new();
+		m.set_msg_type(MessageType::MsgTransferLeader);
+		m.set_from(transferee);
+		self.step(m).ok()?;
+		Some(transferee)
+	}
+
 	pub fn abort_leader_transfer(&mut self) {
 		self.lead_transferee = NONE;
 	}
 
+	/// nodes returns the voters, or the voters of the incoming config while
+	/// in a joint config.
 	pub fn nodes(&self) -> Vec<u64> {
-		let mut nodes: Vec<u64> = self.prs.iter().map(|(&id, _)| id).collect();
-		nodes.sort();
-		nodes
+		self.tracker.voters()
+	}
+
+	/// voters_outgoing returns the voters of the outgoing config while in a
+	/// joint config, and nothing otherwise.
+	pub fn voters_outgoing(&self) -> Vec<u64> {
+		self.tracker.voters_outgoing()
+	}
+
+	/// learners_next returns the outgoing voters which become learners once
+	/// the joint config is left.
+	pub fn learners_next(&self) -> Vec<u64> {
+		self.tracker.learners_next()
+	}
+
+	pub fn is_joint(&self) -> bool {
+		self.tracker.is_joint()
+	}
+
+	/// conf_state returns the current config.
+	pub fn conf_state(&self) -> ConfState {
+		self.tracker.conf_state()
+	}
+
+	/// check_conf_change returns an error if applying the given simple config
+	/// change would corrupt the peer set, so that it is rejected when proposed
+	/// or before the peer set is touched: adding an id already in the set it
+	/// is added to, or leaving the group without a voter, or with voters which
+	/// can't form a quorum under the quorum policy. Promoting a learner with
+	/// AddNode and demoting a voter with AddLearnerNode are allowed.
+	pub fn check_conf_change(&self, change_type: ConfChangeType, id: u64) -> Result<()> {
+		let is_voter = self.tracker.prs.contains_key(&id);
+		let mut voters = self.tracker.voter_ids();
+		match change_type {
+			ConfChangeType::ConfChangeAddNode => {
+				if is_voter {
+					return Err(Error::ConfChangeInvalid(format!(
+						"can't add {}, already a voter",
+						id
+					)));
+				}
+				voters.push(id);
+			}
+			ConfChangeType::ConfChangeAddLearnerNode => {
+				if self.tracker.learner_prs.contains_key(&id) {
+					return Err(Error::ConfChangeInvalid(format!(
+						"can't add {}, already a learner",
+						id
+					)));
+				}
+				if is_voter && voters.len() == 1 {
+					return Err(Error::ConfChangeInvalid(format!(
+						"can't demote {}, the only voter",
+						id
+					)));
+				}
+				voters.retain(|&v| v != id);
+			}
+			ConfChangeType::ConfChangeRemoveNode => {
+				if is_voter && voters.len() == 1 {
+					return Err(Error::ConfChangeInvalid(format!(
+						"can't remove {}, the last voter",
+						id
+					)));
+				}
+				voters.retain(|&v| v != id);
+			}
+			ConfChangeType::ConfChangeUpdateNode => return Ok(()),
+		}
+
+		// Every voter acking or voting must make a quorum, or the group could
+		// never commit nor elect a leader again.
+		voters.sort();
+		if !voters.is_empty()
+			&& (!self.tracker.is_election_quorum(&voters, &voters)
+				|| !self.tracker.is_replication_quorum(&voters, &voters))
+		{
+			return Err(Error::ConfChangeInvalid(format!(
+				"can't change {}, voters {:?} would have no quorum",
+				id, voters
+			)));
+		}
+		Ok(())
+	}
+
+	/// check_enter_joint returns an error if the given changes can't move the
+	/// current config to a joint one with enter_joint, so that they are
+	/// rejected when proposed rather than on every node once committed.
+	pub fn check_enter_joint(&self, changes: &[ConfChangeSingle]) -> Result<()> {
+		self.joint_config(changes).map(|_| ())
+	}
+
+	// joint_config returns the incoming voters, the learners and the
+	// learners_next of the joint config the given changes move the current
+	// config to, or an error if they can't.
+	fn joint_config(
+		&self,
+		changes: &[ConfChangeSingle],
+	) -> Result<(Vec<u64>, Vec<u64>, Vec<u64>)> {
+		if self.is_joint() {
+			return Err(Error::ConfChangeInvalid(
+				"config is already joint".to_string(),
+			));
+		}
+		let outgoing = self.nodes();
+		if outgoing.is_empty() {
+			return Err(Error::ConfChangeInvalid(
+				"can't make a zero-voter config joint".to_string(),
+			));
+		}
+
+		let mut incoming = outgoing.clone();
+		let mut learners = self.learner_nodes();
+		let mut learners_next = vec![];
+		for (i, c) in changes.iter().enumerate() {
+			let id = c.get_node_id();
+			if changes[..i].iter().any(|p| p.get_node_id() == id) {
+				return Err(Error::ConfChangeInvalid(format!(
+					"node {} is changed more than once",
+					id
+				)));
+			}
+			match c.get_change_type() {
+				ConfChangeType::ConfChangeAddNode => {
+					learners.retain(|&l| l != id);
+					learners_next.retain(|&l| l != id);
+					if !incoming.contains(&id) {
+						incoming.push(id);
+					}
+				}
+				ConfChangeType::ConfChangeAddLearnerNode => {
+					incoming.retain(|&v| v != id);
+					if outgoing.contains(&id) {
+						// the outgoing config still needs its vote.
+						if !learners_next.contains(&id) {
+							learners_next.push(id);
+						}
+					} else if !learners.contains(&id) {
+						learners.push(id);
+					}
+				}
+				ConfChangeType::ConfChangeRemoveNode => {
+					incoming.retain(|&v| v != id);
+					learners.retain(|&l| l != id);
+					learners_next.retain(|&l| l != id);
+				}
+				ConfChangeType::ConfChangeUpdateNode => {}
+			}
+		}
+		if incoming.is_empty() {
+			return Err(Error::ConfChangeInvalid(
+				"removed all voters".to_string(),
+			));
+		}
+		Ok((incoming, learners, learners_next))
+	}
+
+	/// enter_joint moves to a joint config, made of the current voters as the
+	/// outgoing config and the voters after the given changes as the incoming
+	/// one. Until the joint config is left, elections and commits need a
+	/// quorum of both. A voter demoted to learner stays a voter of the outgoing
+	/// config and only becomes a learner once the joint config is left.
+	pub fn enter_joint(&mut self, auto_leave: bool, changes: &[ConfChangeSingle]) -> Result<()> {
+		let (incoming, learners, learners_next) = self.joint_config(changes)?;
+		let outgoing = self.nodes();
+
+		let last_index = self.raft_log.last_index();
+		for id in self.learner_nodes() {
+			if incoming.contains(&id) {
+				self.promote_learner(id);
+			} else if !learners.contains(&id) {
+				self.tracker.learner_prs.remove(&id);
+			}
+		}
+		for &id in &incoming {
+			if !self.tracker.prs.contains_key(&id) {
+				self.set_progress(id, 0, last_index + 1, false);
+				self.tracker.prs.get_mut(&id).unwrap().recent_active = true;
+			}
+		}
+		for &id in &learners {
+			if !self.tracker.learner_prs.contains_key(&id) {
+				self.set_progress(id, 0, last_index + 1, true);
+				self.tracker.learner_prs.get_mut(&id).unwrap().recent_active = true;
+			}
+		}
+		self.is_learner = learners.contains(&self.id);
+		self.tracker.set_joint(&incoming, &outgoing, &learners_next, auto_leave);
+		info!(
+			"{} {} entered joint config [incoming: {:?}, outgoing: {:?}, learners: {:?}, \
+			 learners_next: {:?}]",
+			self.tag,
+			self.id,
+			self.tracker.voters(),
+			self.tracker.voters_outgoing(),
+			learners,
+			self.tracker.learners_next(),
+		);
+
+		if self.state == StateType::Leader {
+			if self.lead_transferee != NONE && !incoming.contains(&self.lead_transferee) {
+				self.abort_leader_transfer();
+			}
+			if self.maybe_commit() {
+				self.bcast_append();
+			}
+		}
+		Ok(())
+	}
+
+	/// on_applied is called once entries have been applied up to
+	/// raft_log.applied. A leader in a joint config to be left automatically
+	/// proposes to leave it once the conf change which entered it is applied,
+	/// and a leader replacing a node moves on once its learner is added.
+	pub fn on_applied(&mut self) {
+		self.maybe_finish_replace();
+		if self.state != StateType::Leader
+			|| !self.tracker.auto_leave
+			|| self.raft_log.applied < self.pending_conf_index
+		{
+			return;
+		}
+		let mut m = Message::new();
+		m.set_msg_type(MessageType::MsgProp);
+		let mut e = Entry::new();
+		e.set_entry_type(EntryType::EntryConfChangeV2);
+		m.set_entries(RepeatedField::from_vec(vec![e]));
+		match self.step(m) {
+			Ok(()) => info!(
+				"{} {} initiating automatic transition out of joint config [incoming: {:?}, outgoing: {:?}]",
+				self.tag, self.id, self.tracker.voters(), self.tracker.voters_outgoing(),
+			),
+			Err(e) => debug!(
+				"{} {} not leaving joint config automatically: {}",
+				self.tag, self.id, e,
+			),
+		}
+	}
+
+	/// replace_node replaces the voter old by new, the usual way to move a
+	/// member to another machine: new is first added as a learner, and once it
+	/// has caught up with the leader's commit index, a single joint conf change
+	/// adds it as a voter and removes old, leaving the joint config
+	/// automatically, so that the group never counts on a voter which has yet
+	/// to receive the log. Only the leader can replace a node, and the
+	/// replacement is abandoned if it loses leadership.
+	pub fn replace_node(&mut self, old: u64, new: u64) -> Result<()> {
+		if self.state != StateType::Leader {
+			return Err(Error::ProposalDropped);
+		}
+		if let Some((o, n)) = self.pending_replace {
+			return Err(Error::ConfChangeInvalid(format!(
+				"replacement of {} by {} is in progress",
+				o, n
+			)));
+		}
+		if self.is_joint() {
+			return Err(Error::ConfChangeInvalid(format!(
+				"can't replace {} in a joint config",
+				old
+			)));
+		}
+		if !self.tracker.prs.contains_key(&old) {
+			return Err(Error::ConfChangeInvalid(format!(
+				"can't replace {}, not a voter",
+				old
+			)));
+		}
+		if self.tracker.prs.contains_key(&new) {
+			return Err(Error::ConfChangeInvalid(format!(
+				"can't replace {} by {}, already a voter",
+				old, new
+			)));
+		}
+
+		if !self.tracker.learner_prs.contains_key(&new) {
+			self.propose_conf_change_v2(&[(ConfChangeType::ConfChangeAddLearnerNode, new)])?;
+		}
+		info!(
+			"{} {} replacing {} by {}, waiting for it to catch up as a learner",
+			self.tag, self.id, old, new
+		);
+		self.pending_replace = Some((old, new));
+		self.maybe_finish_replace();
+		Ok(())
+	}
+
+	// maybe_finish_replace proposes the joint conf change of the replacement
+	// in progress once its new node has caught up as a learner, and no other
+	// conf change is pending.
+	fn maybe_finish_replace(&mut self) {
+		let (old, new) = match self.pending_replace {
+			Some(p) => p,
+			None => return,
+		};
+		if self.state != StateType::Leader
+			|| self.is_joint()
+			|| self.raft_log.applied < self.pending_conf_index
+		{
+			return;
+		}
+		let matched = match self.tracker.learner_prs.get(&new) {
+			Some(pr) if self.tracker.prs.contains_key(&old) => pr.matched,
+			_ => {
+				warn!(
+					"{} {} abandoned replacing {} by {}, the config changed meanwhile",
+					self.tag, self.id, old, new
+				);
+				self.pending_replace = None;
+				return;
+			}
+		};
+		if matched < self.raft_log.committed {
+			return;
+		}
+
+		let changes = [
+			(ConfChangeType::ConfChangeAddNode, new),
+			(ConfChangeType::ConfChangeRemoveNode, old),
+		];
+		match self.propose_conf_change_v2(&changes) {
+			Ok(()) => {
+				info!(
+					"{} {} learner {} caught up, replacing {} by it",
+					self.tag, self.id, new, old
+				);
+				self.pending_replace = None;
+			}
+			Err(e) => debug!(
+				"{} {} not replacing {} by {} yet: {}",
+				self.tag, self.id, old, new, e,
+			),
+		}
+	}
+
+	// propose_conf_change_v2 proposes a ConfChangeV2 made of the given
+	// changes, entering a joint config left automatically when needed.
+	fn propose_conf_change_v2(&mut self, changes: &[(ConfChangeType, u64)]) -> Result<()> {
+		let mut cc = ConfChangeV2::new();
+		for &(change_type, id) in changes {
+			let mut c = ConfChangeSingle::new();
+			c.set_change_type(change_type);
+			c.set_node_id(id);
+			cc.mut_changes().push(c);
+		}
+		let mut e = Entry::new();
+		e.set_entry_type(EntryType::EntryConfChangeV2);
+		e.set_data(cc.write_to_bytes()?);
+		let mut m = Message::new();
+		m.set_msg_type(MessageType::MsgProp);
+		m.set_entries(RepeatedField::from_vec(vec![e]));
+		self.step(m)
+	}
+
+	/// leave_joint leaves the current joint config for its incoming config,
+	/// dropping the voters which are only part of the outgoing one, except for
+	/// learners_next which become learners.
+	pub fn leave_joint(&mut self) -> Result<()> {
+		if !self.is_joint() {
+			return Err(Error::ConfChangeInvalid(
+				"can't leave a non-joint config".to_string(),
+			));
+		}
+		let incoming = self.tracker.voters();
+		let demoted = self.tracker.learners_next();
+		let removed: Vec<u64> = self
+			.tracker
+			.voters_outgoing()
+			.into_iter()
+			.filter(|id| !incoming.contains(id) && !demoted.contains(id))
+			.collect();
+		for id in &removed {
+			self.tracker.prs.remove(id);
+		}
+		for &id in &demoted {
+			let mut pr = self.tracker.prs.remove(&id).unwrap();
+			pr.is_learner = true;
+			self.tracker.learner_prs.insert(id, pr);
+			if id == self.id {
+				self.is_learner = true;
+			}
+		}
+		self.tracker.set_joint(&[], &[], &[], false);
+		info!(
+			"{} {} left joint config [voters: {:?}, removed: {:?}, demoted: {:?}]",
+			self.tag, self.id, incoming, removed, demoted,
+		);
+
+		if self.state == StateType::Leader {
+			if !incoming.contains(&self.lead_transferee) {
+				self.abort_leader_transfer();
+			}
+			if !incoming.contains(&self.id) {
+				self.hand_over_leadership();
+			} else if self.maybe_commit() {
+				self.bcast_append();
+			}
+		}
+		Ok(())
 	}
 
 	pub fn learner_nodes(&self) -> Vec<u64> {
-		let mut nodes: Vec<u64> = self.learner_prs.iter().map(|(&id, _)| id).collect();
-		nodes.sort();
-		nodes
+		self.tracker.learners()
 	}
 
 	pub fn add_node(&mut self, id: u64) {
@@ -530,15 +1232,12 @@ impl<T: Storage> Raft<T> {
 	}
 
 	pub fn add_node_or_learner_node(&mut self, id: u64, is_learner: bool) {
-		if self.prs.contains_key(&id) {
+		if self.tracker.prs.contains_key(&id) {
 			if is_learner {
-				info!(
-					"{} ignored add learner: do not support changing {} from voter to learner",
-					self.tag, id
-				);
+				self.demote_voter(id);
 			}
 			return;
-		} else if self.learner_prs.contains_key(&id) {
+		} else if self.tracker.learner_prs.contains_key(&id) {
 			if is_learner {
 				// ignore redundant add learner.
 				return;
@@ -558,7 +1257,7 @@ impl<T: Storage> Raft<T> {
 		self.del_progress(id);
 
 		// do not try to commit or abort transferring if there is no nodes in the cluster.
-		if self.prs.is_empty() && self.learner_prs.is_empty() {
+		if self.tracker.prs.is_empty() && self.tracker.learner_prs.is_empty() {
 			return;
 		}
 
@@ -572,54 +1271,268 @@ impl<T: Storage> Raft<T> {
 		if self.state == StateType::Leader && self.lead_transferee == id {
 			self.abort_leader_transfer();
 		}
+
+		if self.state == StateType::Leader && id == self.id {
+			self.hand_over_leadership();
+		}
+	}
+
+	// demote_voter turns a voter into a learner, keeping its Progress so that
+	// replication to it goes on undisturbed.
+	fn demote_voter(&mut self, id: u64) {
+		let mut pr = self.tracker.prs.remove(&id).unwrap();
+		pr.is_learner = true;
+		self.tracker.learner_prs.insert(id, pr);
+		if id == self.id {
+			self.is_learner = true;
+		}
+		if self.state != StateType::Leader {
+			return;
+		}
+
+		// The quorum is now smaller, so see if any pending entries can be
+		// committed.
+		if !self.tracker.prs.is_empty() && self.maybe_commit() {
+			self.bcast_append();
+		}
+		if self.lead_transferee == id {
+			self.abort_leader_transfer();
+		}
+		if id == self.id {
+			self.hand_over_leadership();
+		}
+	}
+
+	// hand_over_leadership is called when the leader is no longer a voter. It
+	// hands its leadership over to the most up-to-date remaining voter and
+	// steps down, rather than leading a group it can not vote in. Like a
+	// leadership transfer, it waits for that voter to catch up first, so that
+	// its campaign can't fail for a lagging log; meanwhile proposals are
+	// dropped, and the handover is retried after an election timeout.
+	fn hand_over_leadership(&mut self) {
+		let transferee = self
+			.tracker
+			.prs
+			.iter()
+			.max_by_key(|&(id, pr)| (pr.matched, cmp::Reverse(*id)))
+			.map(|(id, pr)| (*id, pr.matched));
+		match transferee {
+			Some((to, matched)) if matched != self.raft_log.last_index() => {
+				info!(
+					"{} {} [term {}] is no longer a voter, transferring leadership to {} \
+					 once it catches up",
+					self.tag, self.id, self.term, to,
+				);
+				self.election_elapsed = 0;
+				self.lead_transferee = to;
+				self.send_append_to(to);
+			}
+			Some((to, _)) => {
+				info!(
+					"{} {} [term {}] is no longer a voter, transferring leadership to {}",
+					self.tag, self.id, self.term, to,
+				);
+				self.send_timeout_now(to);
+				let term = self.term;
+				self.become_follower(term, NONE);
+			}
+			None => {
+				let term = self.term;
+				self.become_follower(term, NONE);
+			}
+		}
 	}
 
 	pub fn del_progress(&mut self, id: u64) {
-		self.prs.remove(&id);
-		self.learner_prs.remove(&id);
+		self.tracker.prs.remove(&id);
+		self.tracker.learner_prs.remove(&id);
 	}
 
 	fn promote_learner(&mut self, id: u64) {
-		if let Some(mut pr) = self.learner_prs.remove(&id) {
+		if let Some(mut pr) = self.tracker.learner_prs.remove(&id) {
 			pr.is_learner = false;
-			self.prs.insert(id, pr);
+			self.tracker.prs.insert(id, pr);
 			return;
 		}
 		panic!("promote not exists learner: {}", id);
 	}
 
 	pub fn get_mut_progress(&mut self, id: u64) -> Option<&mut Progress> {
-		self.prs.get_mut(&id).or(self.learner_prs.get_mut(&id))
+		self.tracker.prs.get_mut(&id).or(self.tracker.learner_prs.get_mut(&id))
 	}
 
 	pub fn get_progress(&self, id: u64) -> Option<&Progress> {
-		self.prs.get(&id).or_else(|| self.learner_prs.get(&id))
+		self.tracker.prs.get(&id).or_else(|| self.tracker.learner_prs.get(&id))
+	}
+
+	/// pause_replication stops sending log entries and snapshots to the given
+	/// peer, e.g. while it is under maintenance, without removing it from the
+	/// configuration. Heartbeats are still sent so that it doesn't campaign. A
+	/// paused voter still counts towards the quorum, so pausing leaves the group
+	/// less tolerant to failures. The pause is local to this node and survives
+	/// its elections, but not a leadership change to another node. Returns false
+	/// if the peer is unknown.
+	pub fn pause_replication(&mut self, id: u64) -> bool {
+		match self.get_mut_progress(id) {
+			Some(pr) => pr.replication_paused = true,
+			None => return false,
+		}
+		info!("{} {} paused replication to {}", self.tag, self.id, id);
+		true
+	}
+
+	/// resume_replication undoes pause_replication, and lets the leader catch
+	/// the peer up right away. Returns false if the peer is unknown.
+	pub fn resume_replication(&mut self, id: u64) -> bool {
+		match self.get_mut_progress(id) {
+			Some(pr) => pr.replication_paused = false,
+			None => return false,
+		}
+		info!("{} {} resumed replication to {}", self.tag, self.id, id);
+		self.send_append_to(id);
+		true
+	}
+
+	/// set_append_budget sets the max size in bytes of the entries this node
+	/// wants to receive in a single MsgApp, e.g. from the size of its apply
+	/// backlog and its available memory. It is advertised to the leader with
+	/// every MsgAppResp, the leader then sizes its appends to the smaller of
+	/// the budget and its own max_size_per_msg; a single entry bigger than the
+	/// budget is still sent. 0 for no limit.
+	pub fn set_append_budget(&mut self, bytes: u64) {
+		self.append_budget = bytes;
+	}
+
+	/// report_send_queue lets the transport report whether its send queue to
+	/// the given peer is full. While it is, the leader stops sending log entries
+	/// and snapshots to the peer, as if its inflights were full, instead of
+	/// queueing them without bound; heartbeats are still sent. Once the queue
+	/// drains the leader resumes replicating right away. Returns false if the
+	/// peer is unknown.
+	pub fn report_send_queue(&mut self, id: u64, full: bool) -> bool {
+		match self.get_mut_progress(id) {
+			Some(pr) if pr.send_queue_full == full => return true,
+			Some(pr) => pr.send_queue_full = full,
+			None => return false,
+		}
+		debug!(
+			"{} {} send queue to {} is {}",
+			self.tag,
+			self.id,
+			id,
+			if full { "full" } else { "drained" }
+		);
+		if !full {
+			self.send_append_to(id);
+		}
+		true
+	}
+
+	/// force_probe makes the leader forget what it knows about the log of the
+	/// given peer and probe it again as a newly elected leader would, e.g. after
+	/// the peer's storage was restored from a backup. Returns false if this
+	/// node is not the leader or the peer is unknown.
+	pub fn force_probe(&mut self, id: u64) -> bool {
+		if self.state != StateType::Leader || id == self.id {
+			return false;
+		}
+		let last_index = self.raft_log.last_index();
+		match self.get_mut_progress(id) {
+			Some(pr) => {
+				pr.matched = 0;
+				pr.become_probe();
+				pr.next = last_index + 1;
+			}
+			None => return false,
+		}
+		info!("{} {} forced probing {}", self.tag, self.id, id);
+		self.send_append_to(id);
+		true
+	}
+
+	/// force_snapshot sends the latest snapshot to the given peer on demand,
+	/// whatever its progress, e.g. when its log is suspected to be
+	/// inconsistent. Returns false if this node is not the leader, the peer is
+	/// unknown or no snapshot is available.
+	pub fn force_snapshot(&mut self, id: u64) -> bool {
+		if self.state != StateType::Leader || id == self.id || self.get_progress(id).is_none() {
+			return false;
+		}
+		let snap = match self.raft_log.snapshot() {
+			Ok(ref s) if s.get_metadata().get_index() == 0 => {
+				warn!(
+					"{} {} can not force a snapshot to {}, no snapshot available",
+					self.tag, self.id, id
+				);
+				return false;
+			}
+			snap => snap,
+		};
+
+		let mut prs = self.take_prs();
+		let mut learner_prs = self.take_learner_prs();
+		let mut sent = false;
+		if let Some(pr) = prs.get_mut(&id).or(learner_prs.get_mut(&id)) {
+			pr.matched = 0;
+			let mut m = Message::new();
+			m.set_to(id);
+			if self.prepare_send_snapshot(&mut m, pr, id, snap) {
+				info!("{} {} forced a snapshot to {}", self.tag, self.id, id);
+				self.send(m);
+				sent = true;
+			}
+		}
+		self.set_prs(prs);
+		self.set_learner_prs(learner_prs);
+		sent
+	}
+
+	// send_append_to sends an append to the given peer if this node is the
+	// leader, used when a peer which was held back can be caught up again.
+	fn send_append_to(&mut self, id: u64) {
+		if self.state != StateType::Leader || id == self.id {
+			return;
+		}
+		let mut prs = self.take_prs();
+		let mut learner_prs = self.take_learner_prs();
+		if let Some(pr) = prs.get_mut(&id).or(learner_prs.get_mut(&id)) {
+			self.send_append(id, pr);
+		}
+		self.set_prs(prs);
+		self.set_learner_prs(learner_prs);
+	}
+
+	fn new_progress(&self, next: u64, is_learner: bool) -> Progress {
+		let mut pr = Progress::new(next, self.max_inflight as usize, is_learner);
+		pr.ins = Inflights::with_max_bytes(self.max_inflight as usize, self.max_inflight_bytes);
+		pr
 	}
 
 	pub fn set_progress(&mut self, id: u64, matched: u64, next: u64, is_learner: bool) {
 		if !is_learner {
-			self.learner_prs.remove(&id);
-			let mut pr = Progress::new(next, self.max_inflight as usize, is_learner);
+			self.tracker.learner_prs.remove(&id);
+			let mut pr = self.new_progress(next, is_learner);
 			pr.matched = matched;
-			self.prs.insert(id, pr);
+			self.tracker.prs.insert(id, pr);
 			return;
 		}
 
-		if self.prs.contains_key(&id) {
+		if self.tracker.prs.contains_key(&id) {
 			panic!(
 				"{} unexpected changing from voter to learner for {}",
 				self.id, id
 			);
 		}
-		let mut pr = Progress::new(next, self.max_inflight as usize, is_learner);
+		let mut pr = self.new_progress(next, is_learner);
 		pr.matched = matched;
-		self.learner_prs.insert(id, pr);
+		self.tracker.learner_prs.insert(id, pr);
 	}
 
 	pub fn soft_state(&self) -> SoftState {
 		SoftState {
 			lead: self.lead,
 			raft_state: self.state,
+			lead_transferee: self.lead_transferee,
 		}
 	}
 
@@ -628,9 +1541,39 @@ impl<T: Storage> Raft<T> {
 		hs.set_term(self.term);
 		hs.set_vote(self.vote);
 		hs.set_commit(self.raft_log.committed);
+		hs.set_extensions(RepeatedField::from_vec(self.hard_state_extensions.clone()));
+		*hs.mut_unknown_fields() = self.hard_state_unknown_fields.clone();
 		hs
 	}
 
+	/// hard_state_extension returns the data of the HardState extension with the
+	/// given id, if any.
+	pub fn hard_state_extension(&self, id: u32) -> Option<&[u8]> {
+		self.hard_state_extensions
+			.binary_search_by_key(&id, |e| e.get_id())
+			.ok()
+			.map(|i| self.hard_state_extensions[i].get_data())
+	}
+
+	/// set_hard_state_extension stores data as the HardState extension with the
+	/// given id, it will be part of the next HardState handed to the application
+	/// for persisting. Empty data removes the extension.
+	pub fn set_hard_state_extension(&mut self, id: u32, data: Vec<u8>) {
+		match self.hard_state_extensions.binary_search_by_key(&id, |e| e.get_id()) {
+			Ok(i) if data.is_empty() => {
+				self.hard_state_extensions.remove(i);
+			}
+			Ok(i) => self.hard_state_extensions[i].set_data(data),
+			Err(_) if data.is_empty() => {}
+			Err(i) => {
+				let mut e = HardStateExtension::new();
+				e.set_id(id);
+				e.set_data(data);
+				self.hard_state_extensions.insert(i, e);
+			}
+		}
+	}
+
 	pub fn tick(&mut self) {
 		match self.state {
 			StateType::Follower | StateType::PreCandidate | StateType::Candidate => {
@@ -640,6 +1583,43 @@ impl<T: Storage> Raft<T> {
 		}
 	}
 
+	/// next_timeout returns the number of ticks until the next election or
+	/// heartbeat timeout fires, or None if there is none to wait for. A driver
+	/// can sleep that long and call on_timeout rather than calling tick
+	/// periodically. Stepping a message may move the deadline, so it should
+	/// be asked again after every Ready.
+	pub fn next_timeout(&self) -> Option<u64> {
+		let election = self.election_timeout.saturating_sub(self.election_elapsed);
+		let ticks = match self.state {
+			StateType::Leader => {
+				let heartbeat = self.heartbeat_timeout.saturating_sub(self.heartbeat_elapsed);
+				if self.check_quorum || self.lead_transferee != NONE {
+					cmp::min(heartbeat, election)
+				} else {
+					heartbeat
+				}
+			}
+			_ if self.promotable() => self
+				.randomized_election_timeout
+				.saturating_sub(self.election_elapsed),
+			// a node which can't campaign still has to let its leader lease
+			// expire to grant votes again.
+			_ if self.check_quorum && election > 0 => election,
+			_ => return None,
+		};
+		Some(cmp::max(ticks, 1))
+	}
+
+	/// on_timeout advances the logical clock up to the deadline returned by
+	/// next_timeout. It is equivalent to calling tick that many times.
+	pub fn on_timeout(&mut self) {
+		if let Some(ticks) = self.next_timeout() {
+			for _ in 0..ticks {
+				self.tick();
+			}
+		}
+	}
+
 	/// tick_election is run by followers and candidates after election_timeout.
 	fn tick_election(&mut self) {
 		self.election_elapsed += 1;
@@ -653,6 +1633,7 @@ impl<T: Storage> Raft<T> {
 	}
 
 	fn tick_heartbeat(&mut self) {
+		self.replication_bytes = 0;
 		self.heartbeat_elapsed += 1;
 		self.election_elapsed += 1;
 
@@ -669,6 +1650,9 @@ impl<T: Storage> Raft<T> {
 			// If current leader cannot transfer leadership in electionTimeout, it becomes leader again.
 			if self.state == StateType::Leader && self.lead_transferee != NONE {
 				self.abort_leader_transfer();
+				if !self.tracker.prs.contains_key(&self.id) {
+					self.hand_over_leadership();
+				}
 			}
 		}
 
@@ -688,7 +1672,7 @@ impl<T: Storage> Raft<T> {
 	/// promotable indicates whether state machine can be promoted to leader,
 	/// which is true when its own id is in progress list.
 	pub fn promotable(&self) -> bool {
-		self.prs.contains_key(&self.id)
+		self.tracker.prs.contains_key(&self.id)
 	}
 
 	/// past_election_timeout returns true if election_elapsed is greater
@@ -698,7 +1682,7 @@ impl<T: Storage> Raft<T> {
 		self.election_elapsed >= self.randomized_election_timeout
 	}
 
-	pub fn step(&mut self, msg: Message) -> Result<()> {
+	pub fn step(&mut self, mut msg: Message) -> Result<()> {
 		// Handle the message term, which may result in our stepping down to a follower.
 		if msg.get_term() == 0 {
 			// local message
@@ -706,7 +1690,7 @@ impl<T: Storage> Raft<T> {
 			if msg.get_msg_type() == MessageType::MsgVote
 				|| msg.get_msg_type() == MessageType::MsgPreVote
 			{
-				let force = msg.get_context() == CAMPAIGN_TRANSFER;
+				let force = msg.get_context() == CampaignType::Transfer.context();
 				let in_lease = self.check_quorum
 					&& self.lead != NONE
 					&& self.election_elapsed < self.election_timeout;
@@ -824,44 +1808,15 @@ impl<T: Storage> Raft<T> {
 			return Ok(());
 		}
 
-		if msg.get_msg_type() == MessageType::MsgHup {
-			if self.state != StateType::Leader {
-				let ents = match self.raft_log.slice(
-					self.raft_log.applied + 1,
-					self.raft_log.committed + 1,
-					NO_LIMIT,
-				) {
-					Ok(ents) => ents,
-					Err(e) => panic!(e),
-				};
-
-				let n = num_of_pending_conf(&ents);
-				if n > 0 && self.raft_log.committed > self.raft_log.applied {
-					warn!(
-						"{} {} cannot campaign at term {} since there are still {} pending configuration changes to apply", 
-						self.tag,
-						self.id,
-						self.term,
-						n,
-					);
-					return Ok(());
-				}
-
-				info!(
-					"{} {} is starting a new election at term {}",
-					self.tag, self.id, self.term
-				);
+		if msg.get_msg_type() == MessageType::MsgProp && self.entry_checksum {
+			msg.mut_entries().iter_mut().for_each(set_entry_checksum);
+		}
 
-				if self.pre_vote {
-					self.campaign(CAMPAIGN_PRE_ELECTION);
-				} else {
-					self.campaign(CAMPAIGN_ELECTION);
-				}
+		if msg.get_msg_type() == MessageType::MsgHup {
+			if self.pre_vote {
+				self.hup(CampaignType::PreElection);
 			} else {
-				debug!(
-					"{} {} ignoring MsgHup because already leader",
-					self.tag, self.term
-				);
+				self.hup(CampaignType::Election);
 			}
 		} else if msg.get_msg_type() == MessageType::MsgPreVote
 			|| msg.get_msg_type() == MessageType::MsgVote
@@ -890,8 +1845,14 @@ impl<T: Storage> Raft<T> {
 				|| (self.vote == NONE && self.lead == NONE)
 				|| (msg.get_msg_type() == MessageType::MsgPreVote && msg.get_term() > self.term);
 
-			// ...and we believe the candidate is up to date.
+			// ...and we believe the candidate is up to date, and it does not
+			// have a lower priority than us while not being ahead of us.
+			let outranked = msg.get_priority() < self.priority
+				&& msg.get_context() != CampaignType::Transfer.context()
+				&& msg.get_log_term() == self.raft_log.last_term()
+				&& msg.get_index() == self.raft_log.last_index();
 			if can_vote
+				&& !outranked
 				&& self
 					.raft_log
 					.is_up_to_date(msg.get_index(), msg.get_log_term())
@@ -952,6 +1913,9 @@ impl<T: Storage> Raft<T> {
 				self.send(m);
 			}
 		} else {
+			if msg.get_msg_type() == MessageType::MsgProp {
+				self.check_proposal_size(&msg)?;
+			}
 			match self.state {
 				StateType::PreCandidate | StateType::Candidate => self.step_candidate(msg)?,
 				StateType::Follower => self.step_follower(msg)?,
@@ -962,6 +1926,25 @@ impl<T: Storage> Raft<T> {
 		Ok(())
 	}
 
+	// check_proposal_size refuses proposals with an entry larger than
+	// max_proposal_size.
+	fn check_proposal_size(&self, msg: &Message) -> Result<()> {
+		if self.max_proposal_size == 0 {
+			return Ok(());
+		}
+		for e in msg.get_entries() {
+			let size = e.get_data().len() as u64;
+			if size > self.max_proposal_size {
+				debug!(
+					"{} {} dropping proposal of {} bytes, limit is {} bytes",
+					self.tag, self.id, size, self.max_proposal_size
+				);
+				return Err(Error::ProposalTooLarge(size, self.max_proposal_size));
+			}
+		}
+		Ok(())
+	}
+
 	fn step_follower(&mut self, mut msg: Message) -> Result<()> {
 		match msg.get_msg_type() {
 			MessageType::MsgProp => {
@@ -1021,7 +2004,7 @@ impl<T: Storage> Raft<T> {
 					// Leadership transfers never use pre-vote even if r.preVote is true; we
 					// know we are not recovering from a partition so there is no need for the
 					// extra round trip.
-					self.campaign(CAMPAIGN_TRANSFER);
+					self.campaign(CampaignType::Transfer);
 				} else {
 					info!(
 						"{} {} received MsgTimeoutNow from {} but is not promotable",
@@ -1031,6 +2014,22 @@ impl<T: Storage> Raft<T> {
 					);
 				}
 			}
+			MessageType::MsgForgetLeader => {
+				if self.read_only.option == ReadOnlyOption::LeaseBased {
+					error!(
+						"{} {} ignoring MsgForgetLeader due to ReadOnlyOption::LeaseBased",
+						self.tag, self.id,
+					);
+					return Ok(());
+				}
+				if self.lead != NONE {
+					info!(
+						"{} {} forgetting leader {} at term {}",
+						self.tag, self.id, self.lead, self.term,
+					);
+					self.lead = NONE;
+				}
+			}
 			MessageType::MsgReadIndex => {
 				if self.lead == NONE {
 					info!(
@@ -1085,7 +2084,7 @@ impl<T: Storage> Raft<T> {
 				if msg.get_entries().is_empty() {
 					panic!("{} stepped empty MsgProp", self.id);
 				}
-				if !self.prs.contains_key(&self.id) {
+				if !self.tracker.prs.contains_key(&self.id) {
 					// If we are not currently a member of the range (i.e. this node
 					// was removed from the configuration while serving as leader),
 					// drop any new proposals.
@@ -1102,32 +2101,64 @@ impl<T: Storage> Raft<T> {
 					return Err(Error::ProposalDropped);
 				}
 
-				for (i, e) in msg.mut_entries().iter_mut().enumerate() {
-					if e.get_entry_type() == EntryType::EntryConfChange {
-						if self.pending_conf_index > self.raft_log.applied {
-							info!(
-								"{} propose conf {:?} ignored since pending unapplied configuration [index {}, applied {}]",
-								self.tag,
-								e,
-								self.pending_conf_index,
-								self.raft_log.applied,
-							);
-							*e = Entry::new();
-							e.set_entry_type(EntryType::EntryNormal);
-						} else {
-							self.pending_conf_index = self.raft_log.last_index() + i as u64 + 1;
-						}
+				if !self.within_uncommitted_limits(msg.get_entries()) {
+					debug!(
+						"{} {} [term {}] too many uncommitted entries [last {}, commit {}, \
+						 size {}]; dropping proposal",
+						self.tag,
+						self.id,
+						self.term,
+						self.raft_log.last_index(),
+						self.raft_log.committed,
+						self.uncommitted_size,
+					);
+					return Err(Error::ProposalDropped);
+				}
+
+				// only one conf change may be in flight at a time, and a joint
+				// config only accepts the conf change which leaves it.
+				let mut pending_conf_index = self.pending_conf_index;
+				for (i, e) in msg.get_entries().iter().enumerate() {
+					if !is_conf_change(e) {
+						continue;
 					}
+					let refused = if pending_conf_index > self.raft_log.applied {
+						Some("pending unapplied configuration")
+					} else if self.is_joint() && !is_leave_joint(e) {
+						Some("must leave the joint configuration first")
+					} else if !self.is_joint() && is_leave_joint(e) {
+						Some("not in a joint configuration")
+					} else {
+						None
+					};
+					if let Some(reason) = refused {
+						info!(
+							"{} {} propose conf {:?} dropped: {} [index {}, applied {}]",
+							self.tag,
+							self.id,
+							e,
+							reason,
+							pending_conf_index,
+							self.raft_log.applied,
+						);
+						return Err(Error::ProposalDropped);
+					}
+					pending_conf_index = self.raft_log.last_index() + i as u64 + 1;
 				}
+				self.pending_conf_index = pending_conf_index;
+				if self.max_uncommitted_bytes != 0 {
+					self.uncommitted_size += payloads_size(msg.get_entries());
+				}
+
 				self.append_entry(msg.mut_entries());
 				self.bcast_append();
 				return Ok(());
 			}
 			MessageType::MsgReadIndex => {
-				// if (and only if) there is only one voting member (i.e. the leader) in the 
-				// current configuration. the local vote is needed, go through a full broadcast,
-				// quorum = 1, means only one member can vote, optimize.
-				if self.quorum() > 1 {
+				// unless the leader alone is a quorum (i.e. it is the only voting member),
+				// leadership has to be confirmed by a round of heartbeats.
+				let voters = self.get_prs_ids();
+				if !self.is_replication_quorum(&voters, &[self.id]) {
 					if self
 						.raft_log
 						.zero_term_on_err_compacted(self.raft_log.term(self.raft_log.committed))
@@ -1144,7 +2175,11 @@ impl<T: Storage> Raft<T> {
 						ReadOnlyOption::Safe => {
 							let ctx = msg.get_entries()[0].get_data().to_vec();
 							self.read_only.add_request(self.raft_log.committed, msg);
-							self.bcast_heartbeat_with_ctx(&Some(ctx));
+							// batched requests are confirmed by the next heartbeat,
+							// which carries the context of the latest pending one.
+							if !self.batch_read_index {
+								self.bcast_heartbeat_with_ctx(&Some(ctx), false);
+							}
 						}
 						ReadOnlyOption::LeaseBased => {
 							let ri = self.raft_log.committed;
@@ -1184,8 +2219,8 @@ impl<T: Storage> Raft<T> {
 			}
 			_ => {
 				// All other message types require a progress for msg.from (pr).
-				if !self.prs.contains_key(&msg.get_from())
-					&& !self.learner_prs.contains_key(&msg.get_from())
+				if !self.tracker.prs.contains_key(&msg.get_from())
+					&& !self.tracker.learner_prs.contains_key(&msg.get_from())
 				{
 					debug!(
 						"{} {} no progress available for {}",
@@ -1203,7 +2238,7 @@ impl<T: Storage> Raft<T> {
 				let mut maybe_commit = false;
 				let mut send_append = false;
 				let mut more_to_send = None;
-				let quorum = quorum(prs.len()) as u64;
+				let voters: Vec<u64> = prs.keys().cloned().collect();
 
 				if let Some(pr) = prs
 					.get_mut(&msg.get_from())
@@ -1223,7 +2258,7 @@ impl<T: Storage> Raft<T> {
 							self.handle_heartbeat_resp(
 								pr,
 								&msg,
-								quorum,
+								&voters,
 								&mut send_append,
 								&mut more_to_send,
 							);
@@ -1252,16 +2287,17 @@ impl<T: Storage> Raft<T> {
 					}
 				}
 
-				if send_append {
+				if send_append || maybe_commit {
 					let from = msg.get_from();
 					let mut prs = self.take_prs();
 					let mut learner_prs = self.take_learner_prs();
-					self.send_append(
-						from,
-						prs.get_mut(&from)
-							.or_else(|| learner_prs.get_mut(&from))
-							.unwrap(),
-					);
+					let pr = prs.get_mut(&from)
+						.or_else(|| learner_prs.get_mut(&from))
+						.unwrap();
+					if send_append {
+						self.send_append(from, pr);
+					}
+					self.send_append_all(from, pr);
 					self.set_prs(prs);
 					self.set_learner_prs(learner_prs);
 				}
@@ -1269,6 +2305,9 @@ impl<T: Storage> Raft<T> {
 				if let Some(m) = more_to_send {
 					self.send(m);
 				}
+				if msg.get_msg_type() == MessageType::MsgAppResp {
+					self.maybe_finish_replace();
+				}
 
 				Ok(())
 			}
@@ -1337,8 +2376,12 @@ impl<T: Storage> Raft<T> {
 		// During optimistic replication, if the remote becomes unreachable,
 		// there is huge probability that a MsgApp is lost.
 		if pr.state == ProgressState::Replicate {
+			if self.adaptive_inflight {
+				pr.shrink_inflight_window();
+			}
 			pr.become_probe();
 		}
+		pr.back_off_probe(self.max_probe_backoff);
 
 		debug!(
 			"{} failed to send message to {} because it is unreachable [{:?}]",
@@ -1363,6 +2406,7 @@ impl<T: Storage> Raft<T> {
 			);
 		} else {
 			pr.snapshot_failure();
+			pr.snapshots_failed += 1;
 			pr.become_probe();
 			debug!(
 				"{} {} snapshot failed, resumed sending replication messages to {} [{:?}]",
@@ -1383,16 +2427,31 @@ impl<T: Storage> Raft<T> {
 		&mut self,
 		pr: &mut Progress,
 		msg: &Message,
-		quorum: u64,
+		voters: &[u64],
 		send_append: &mut bool,
 		more_to_send: &mut Option<Message>,
 	) {
 		pr.recent_active = true;
+		pr.reset_probe_backoff();
 		pr.resume();
 
 		if pr.state == ProgressState::Replicate && pr.ins.full() {
 			pr.ins.free_first_one();
 		}
+		// The follower reports its last index, which lets a probing leader skip
+		// straight to where the follower's log ends. An index of zero carries no
+		// information (empty log or an older peer), the rejection path covers it.
+		if msg.get_index() != 0 && pr.maybe_probe_from(msg.get_index()) {
+			debug!(
+				"{} {} follower {} lags behind [last index: {}, leader last index: {}], probing from {}",
+				self.tag,
+				self.id,
+				msg.get_from(),
+				msg.get_index(),
+				self.raft_log.last_index(),
+				pr.next,
+			);
+		}
 		if pr.matched < self.raft_log.last_index() {
 			*send_append = true;
 		}
@@ -1400,8 +2459,9 @@ impl<T: Storage> Raft<T> {
 			return;
 		}
 
-		let ack_count = self.read_only.recv_ack(&msg) as u64;
-		if ack_count < quorum {
+		let mut acked = self.read_only.recv_ack(&msg);
+		acked.push(self.id);
+		if !self.is_replication_quorum(voters, &acked) {
 			return;
 		}
 
@@ -1435,7 +2495,10 @@ impl<T: Storage> Raft<T> {
 		send_append: &mut bool,
 	) {
 		pr.recent_active = true;
+		pr.reset_probe_backoff();
+		pr.append_budget = msg.get_append_budget();
 		if msg.get_reject() {
+			pr.rejects_received += 1;
 			debug!(
 				"{} {} received msgApp rejection(lastindex: {}) from {} for index {}",
 				self.tag,
@@ -1445,7 +2508,18 @@ impl<T: Storage> Raft<T> {
 				msg.get_index(),
 			);
 
-			if pr.maybe_decr_to(msg.get_index(), msg.get_reject_hint()) {
+			// The follower's entries after the hint can't match ours. If it
+			// returned its term there, also skip our entries with a greater
+			// term, as they can't match its log either: the next probe starts
+			// at the last index where both logs may agree.
+			let mut next_probe_index = msg.get_reject_hint();
+			if msg.get_log_term() > 0 {
+				next_probe_index = self
+					.raft_log
+					.find_conflict_by_term(next_probe_index, msg.get_log_term())
+					.0;
+			}
+			if pr.maybe_decr_to(msg.get_index(), next_probe_index) {
 				debug!(
 					"{} {} decreased progress of {} to [{:?}]",
 					self.tag,
@@ -1454,6 +2528,9 @@ impl<T: Storage> Raft<T> {
 					pr,
 				);
 				if pr.state == ProgressState::Replicate {
+					if self.adaptive_inflight {
+						pr.shrink_inflight_window();
+					}
 					pr.become_probe();
 				}
 				*send_append = true;
@@ -1474,10 +2551,13 @@ impl<T: Storage> Raft<T> {
 			pr.become_probe();
 		} else {
 			pr.ins.free_to(msg.get_index());
+			if self.adaptive_inflight {
+				pr.grow_inflight_window();
+			}
 		}
 
 		// Transfer leadership is in progress.
-		if msg.get_from() == self.lead_transferee {
+		if msg.get_from() == self.lead_transferee && pr.matched == self.raft_log.last_index() {
 			info!(
 				"{} {} sent MsgTimeoutNow to {} after received MsgAppResp",
 				self.tag,
@@ -1522,7 +2602,8 @@ impl<T: Storage> Raft<T> {
 				{
 					return Ok(());
 				}
-				let granted = self.poll(msg.get_from(), msg.get_msg_type(), !msg.get_reject());
+				let (granted, rejected) =
+					self.poll(msg.get_from(), msg.get_msg_type(), !msg.get_reject());
 				info!(
 					"{} {} [quorum:{}] has received {} {:?} votes and {} vote rejections",
 					self.tag,
@@ -1530,21 +2611,25 @@ impl<T: Storage> Raft<T> {
 					self.quorum(),
 					granted,
 					msg.get_msg_type(),
-					self.votes.len() - granted,
+					rejected,
 				);
 
-				if self.quorum() == granted {
-					if self.state == StateType::PreCandidate {
-						self.campaign(CAMPAIGN_ELECTION);
-					} else {
-						self.become_leader();
-						self.bcast_append();
+				match self.tracker.vote_result() {
+					VoteResult::Won => {
+						if self.state == StateType::PreCandidate {
+							self.campaign(CampaignType::Election);
+						} else {
+							self.become_leader();
+							self.bcast_append();
+						}
 					}
-				} else if self.votes.len() - granted == self.quorum() {
-					// MsgPreVoteResp contains future term of pre-candidate
-					// msg.term > self.term; reuse self.term
-					let term = self.term;
-					self.become_follower(term, NONE);
+					VoteResult::Lost => {
+						// MsgPreVoteResp contains future term of pre-candidate
+						// msg.term > self.term; reuse self.term
+						let term = self.term;
+						self.become_follower(term, NONE);
+					}
+					VoteResult::Pending => {}
 				}
 			}
 			MessageType::MsgTimeoutNow => {
@@ -1564,25 +2649,46 @@ impl<T: Storage> Raft<T> {
 	}
 
 	// bcast_append sends RPC, with entries to all peers that are not up-to-date
-	// according to the progress recorded in r.prs.
+	// according to the progress recorded in r.tracker.prs.
 	pub fn bcast_append(&mut self) {
 		let self_id = self.id;
-		let mut prs = self.take_prs();
-		prs.iter_mut()
-			.filter(|&(id, _)| *id != self_id)
-			.for_each(|(&id, mut pr)| {
-				self.send_append(id, &mut pr);
-			});
-		self.set_prs(prs);
+		let budgeted = self.replication_budget != 0;
+		let mut tracker = mem::replace(&mut self.tracker, ProgressTracker::new());
+		tracker.visit(|id, pr| {
+			if id != self_id && !(budgeted && pr.is_learner) {
+				self.send_append(id, pr);
+			}
+		});
 
-		let mut learner_prs = self.take_learner_prs();
-		learner_prs
-			.iter_mut()
-			.filter(|&(id, _)| *id != self_id)
-			.for_each(|(&id, mut pr)| {
-				self.send_append(id, &mut pr);
-			});
-		self.set_learner_prs(learner_prs);
+		if budgeted {
+			// serve the learners round-robin, starting after the last one sent to,
+			// until the budget is used up.
+			let mut ids: Vec<u64> = tracker
+				.learners()
+				.into_iter()
+				.filter(|&id| id != self_id)
+				.collect();
+			let start = ids
+				.iter()
+				.position(|&id| id > self.last_learner_sent)
+				.unwrap_or(0);
+			ids.rotate_left(start);
+			for id in ids {
+				if self.replication_budget_exhausted() {
+					break;
+				}
+				let sent = self.msgs.len();
+				self.send_append(id, tracker.learner_prs.get_mut(&id).unwrap());
+				if self.msgs.len() != sent {
+					self.last_learner_sent = id;
+				}
+			}
+		}
+		self.tracker = tracker;
+	}
+
+	fn replication_budget_exhausted(&self) -> bool {
+		self.replication_budget != 0 && self.replication_bytes >= self.replication_budget
 	}
 
 	fn send_timeout_now(&mut self, to: u64) {
@@ -1593,60 +2699,27 @@ impl<T: Storage> Raft<T> {
 	}
 
 	fn check_quorum_active(&mut self) -> bool {
-		let mut act = 0;
-		let self_id = self.id;
-		let mut prs = self.take_prs();
-		prs.iter_mut().for_each(|(&id, pr)| {
-			if id == self_id {
-				act += 1;
-			}
-			if pr.recent_active {
-				act += 1;
-			}
-
-			pr.recent_active = false;
-		});
-		self.set_prs(prs);
-
-		let mut learner_prs = self.take_learner_prs();
-		learner_prs.iter_mut().for_each(|(&id, pr)| {
-			if id == self_id {
-				act += 1;
-			}
-			if pr.recent_active {
-				act += 1;
-			}
-
-			pr.recent_active = false;
-		});
-		self.set_learner_prs(learner_prs);
-		act >= self.quorum()
+		self.tracker.quorum_active(self.id)
 	}
 
 	// bcast_heartbeat sends RPC, without entries to all the peers.
 	fn bcast_heartbeat(&mut self) {
 		let last_ctx = self.read_only.last_pending_request_ctx();
-		self.bcast_heartbeat_with_ctx(&last_ctx);
+		self.bcast_heartbeat_with_ctx(&last_ctx, true);
 	}
 
-	fn bcast_heartbeat_with_ctx(&mut self, ctx: &Option<Vec<u8>>) {
+	// bcast_heartbeat_with_ctx sends heartbeats carrying the given context. The
+	// periodic ones skip the peers whose probing is backed off, see
+	// Config::max_probe_backoff.
+	fn bcast_heartbeat_with_ctx(&mut self, ctx: &Option<Vec<u8>>, periodic: bool) {
 		let self_id = self.id;
-		let prs = self.take_prs();
-		prs.iter()
-			.filter(|&(id, _)| *id != self_id)
-			.for_each(|(&id, pr)| {
+		let mut tracker = mem::replace(&mut self.tracker, ProgressTracker::new());
+		tracker.visit(|id, pr| {
+			if id != self_id && !(periodic && pr.skip_probe()) {
 				self.send_heartbeat(id, ctx.clone(), pr);
-			});
-		self.set_prs(prs);
-
-		let learner_prs = self.take_learner_prs();
-		learner_prs
-			.iter()
-			.filter(|&(id, _)| *id != self_id)
-			.for_each(|(&id, pr)| {
-				self.send_heartbeat(id, ctx.clone(), pr);
-			});
-		self.set_learner_prs(learner_prs);
+			}
+		});
+		self.tracker = tracker;
 	}
 
 	fn send_heartbeat(&mut self, to: u64, ctx: Option<Vec<u8>>, pr: &Progress) {
@@ -1669,31 +2742,47 @@ impl<T: Storage> Raft<T> {
 	}
 
 	pub fn set_prs(&mut self, prs: HashMap<u64, Progress>) {
-		mem::replace(&mut self.prs, prs);
+		mem::replace(&mut self.tracker.prs, prs);
 	}
 
 	pub fn take_prs(&mut self) -> HashMap<u64, Progress> {
-		mem::replace(&mut self.prs, HashMap::new())
+		mem::replace(&mut self.tracker.prs, HashMap::new())
 	}
 
 	pub fn take_learner_prs(&mut self) -> HashMap<u64, Progress> {
-		mem::replace(&mut self.learner_prs, HashMap::new())
+		mem::replace(&mut self.tracker.learner_prs, HashMap::new())
 	}
 
 	pub fn set_learner_prs(&mut self, learner_prs: HashMap<u64, Progress>) {
-		mem::replace(&mut self.learner_prs, learner_prs);
+		mem::replace(&mut self.tracker.learner_prs, learner_prs);
 	}
 
-	// send_append sends RPC, with entries to the given peer.
-	fn send_append(&mut self, to: u64, pr: &mut Progress) {
+	// send_append sends an append RPC with new entries (if any) and the
+	// current commit index to the given peer. Returns true if a MsgApp
+	// carrying entries was sent.
+	fn send_append(&mut self, to: u64, pr: &mut Progress) -> bool {
 		if pr.is_paused() {
-			return;
+			return false;
+		}
+		if pr.is_learner && self.replication_budget_exhausted() {
+			debug!(
+				"{} {} delayed sending to learner {}, replication budget used up",
+				self.tag, self.id, to
+			);
+			return false;
 		}
 
 		let mut m = Message::new();
 		m.set_to(to);
 		let term = self.raft_log.term(pr.next - 1);
-		let ents = self.raft_log.entries(pr.next, self.max_msg_size);
+		let max_size = if pr.state == ProgressState::Probe {
+			cmp::min(PROBE_MAX_MSG_SIZE, self.max_msg_size)
+		} else if pr.append_budget != 0 {
+			cmp::min(pr.append_budget, self.max_msg_size)
+		} else {
+			self.max_msg_size
+		};
+		let ents = self.raft_log.entries(pr.next, max_size);
 
 		// send snapshot if we failed to get term or entries
 		if term.is_err() || ents.is_err() {
@@ -1702,49 +2791,12 @@ impl<T: Storage> Raft<T> {
 					"{} ignore sending snapshot to {} since it is not recently active",
 					self.tag, to
 				);
-				return;
+				return false;
 			}
 
-			m.set_msg_type(MessageType::MsgSnap);
-			match self.raft_log.snapshot() {
-				Ok(s) => {
-					if s.get_metadata().get_index() == 0 {
-						panic!("need non-empty snapshot");
-					}
-					let (sindex, sterm) =
-						(s.get_metadata().get_index(), s.get_metadata().get_term());
-
-					m.set_snapshot(s);
-					debug!(
-						"{} {} [firstindex: {}, commit: {}] sent snapshot[index: {}, term: {}] to {} [{:?}]",
-						self.tag,
-						self.id, 
-						self.raft_log.first_index(), 
-						self.raft_log.committed, 
-						sindex, 
-						sterm, 
-						to, 
-						pr
-					);
-
-					pr.become_snapshot(sindex);
-					debug!(
-						"{} {} paused sending replication messages to {} [{:?}]",
-						self.tag, self.id, to, pr,
-					);
-				}
-				Err(e) => {
-					if e == Error::Storage(StorageError::SnapshotTemporarilyUnavailable) {
-						debug!(
-							"{} {} failed to send snapshot to {} because snapshot is temporarily unavailable", 
-							self.tag,
-							self.id,
-							to,
-						);
-						return;
-					}
-					panic!(e)
-				}
+			let snap = self.raft_log.snapshot();
+			if !self.prepare_send_snapshot(&mut m, pr, to, snap) {
+				return false;
 			}
 		} else {
 			let term = term.unwrap();
@@ -1757,11 +2809,14 @@ impl<T: Storage> Raft<T> {
 
 			let n = m.get_entries().len();
 			if n != 0 {
+				let bytes = m.get_entries().iter().map(|e| u64::from(e.compute_size())).sum();
+				pr.entries_sent += n as u64;
+				pr.bytes_sent += bytes;
 				// optimistically increase the next when in ProgressState::Replicate
 				if pr.state == ProgressState::Replicate {
 					let last = m.get_entries()[n - 1].get_index();
 					pr.optimistic_update(last);
-					pr.ins.add(last);
+					pr.ins.add_with_bytes(last, bytes);
 				} else if pr.state == ProgressState::Probe {
 					pr.pause();
 				} else {
@@ -1773,7 +2828,104 @@ impl<T: Storage> Raft<T> {
 			}
 		}
 
+		let sent_entries = !m.get_entries().is_empty();
 		self.send(m);
+		sent_entries
+	}
+
+	// send_append_all keeps sending appends to a replicating peer until its
+	// log is caught up or its inflight window is full, so that a backlog
+	// larger than max_size_per_msg goes out in several messages at once. A
+	// peer advertising an append budget only gets one message per response.
+	fn send_append_all(&mut self, to: u64, pr: &mut Progress) {
+		while pr.state == ProgressState::Replicate
+			&& pr.append_budget == 0
+			&& pr.next <= self.raft_log.last_index()
+			&& self.send_append(to, pr)
+		{}
+	}
+
+	// prepare_send_snapshot turns m into a MsgSnap carrying the given snapshot
+	// and moves pr into snapshot state. Returns false if the snapshot is
+	// temporarily unavailable.
+	fn prepare_send_snapshot(
+		&mut self,
+		m: &mut Message,
+		pr: &mut Progress,
+		to: u64,
+		snap: Result<Snapshot>,
+	) -> bool {
+		m.set_msg_type(MessageType::MsgSnap);
+		match snap {
+			Ok(s) => {
+				if s.get_metadata().get_index() == 0 {
+					panic!("need non-empty snapshot");
+				}
+				let (sindex, sterm) =
+					(s.get_metadata().get_index(), s.get_metadata().get_term());
+
+				m.set_snapshot(s);
+				pr.snapshots_sent += 1;
+				debug!(
+					"{} {} [firstindex: {}, commit: {}] sent snapshot[index: {}, term: {}] to {} [{:?}]",
+					self.tag,
+					self.id, 
+					self.raft_log.first_index(), 
+					self.raft_log.committed, 
+					sindex, 
+					sterm, 
+					to, 
+					pr
+				);
+
+				pr.become_snapshot(sindex);
+				debug!(
+					"{} {} paused sending replication messages to {} [{:?}]",
+					self.tag, self.id, to, pr,
+				);
+				true
+			}
+			Err(e) => {
+				if e == Error::Storage(StorageError::SnapshotTemporarilyUnavailable) {
+					debug!(
+						"{} {} failed to send snapshot to {} because snapshot is temporarily unavailable", 
+						self.tag,
+						self.id,
+						to,
+					);
+					if !self.snapshot_waiters.contains(&to) {
+						self.snapshot_waiters.push(to);
+					}
+					return false;
+				}
+				panic!(e)
+			}
+		}
+	}
+
+	/// snapshot_ready tells the leader that the application finished generating
+	/// a snapshot at the given index after Storage::snapshot reported it as
+	/// temporarily unavailable. The snapshot is then sent right away to every
+	/// peer which was waiting for it, instead of at the next heartbeat
+	/// response.
+	pub fn snapshot_ready(&mut self, index: u64) {
+		if self.state != StateType::Leader {
+			return;
+		}
+		if index + 1 < self.raft_log.first_index() {
+			debug!(
+				"{} {} ignored snapshot ready at {}, older than first index {}",
+				self.tag,
+				self.id,
+				index,
+				self.raft_log.first_index()
+			);
+			return;
+		}
+		let waiters = mem::take(&mut self.snapshot_waiters);
+		for id in waiters {
+			self.send_append_to(id);
+		}
 	}
 
 	fn handle_snapshot(&mut self, mut msg: Message) {
@@ -1788,10 +2940,12 @@ impl<T: Storage> Raft<T> {
 				self.tag, self.id, self.raft_log.committed, sindex, sterm
 			);
 
+			let last_index = self.raft_log.last_index();
+			self.verified_index = last_index;
 			let mut m = Message::new();
 			m.set_to(msg.get_from());
 			m.set_msg_type(MessageType::MsgAppResp);
-			m.set_index(self.raft_log.last_index());
+			m.set_index(self.durable_index(last_index));
 			self.send(m);
 		} else {
 			info!(
@@ -1799,10 +2953,12 @@ impl<T: Storage> Raft<T> {
 				self.tag, self.id, self.raft_log.committed, sindex, sterm
 			);
 
+			let committed = self.raft_log.committed;
+			self.verified_index = cmp::max(self.verified_index, committed);
 			let mut m = Message::new();
 			m.set_to(msg.get_from());
 			m.set_msg_type(MessageType::MsgAppResp);
-			m.set_index(self.raft_log.committed);
+			m.set_index(self.durable_index(committed));
 			self.send(m);
 		}
 	}
@@ -1862,11 +3018,26 @@ impl<T: Storage> Raft<T> {
 			s.get_metadata().get_term()
 		);
 
-		self.prs.clear();
-		self.learner_prs.clear();
-		self.restore_node(s.get_metadata().get_conf_state().get_nodes(), false);
-		self.restore_node(s.get_metadata().get_conf_state().get_learners(), true);
+		// restore the log first, so that every peer probes from the snapshot.
+		let cs = s.get_metadata().get_conf_state().clone();
 		self.raft_log.restore(s);
+		self.tracker.prs.clear();
+		self.tracker.learner_prs.clear();
+		self.restore_node(cs.get_nodes(), false);
+		let outgoing: Vec<u64> = cs
+			.get_voters_outgoing()
+			.iter()
+			.filter(|id| !cs.get_nodes().contains(id))
+			.cloned()
+			.collect();
+		self.restore_node(&outgoing, false);
+		self.restore_node(cs.get_learners(), true);
+		self.tracker.set_joint(
+			cs.get_nodes(),
+			cs.get_voters_outgoing(),
+			cs.get_learners_next(),
+			cs.get_auto_leave(),
+		);
 
 		true
 	}
@@ -1892,6 +3063,7 @@ impl<T: Storage> Raft<T> {
 		let mut m = Message::new();
 		m.set_to(msg.get_from());
 		m.set_msg_type(MessageType::MsgHeartbeatResp);
+		m.set_index(self.raft_log.last_index());
 		m.set_context(msg.take_context());
 		self.send(m);
 	}
@@ -1899,10 +3071,12 @@ impl<T: Storage> Raft<T> {
 	pub fn handle_append_entries(&mut self, msg: &Message) {
 		// msg index < self.committed, we should tell leader the committed index. 
 		if msg.get_index() < self.raft_log.committed {
+			let committed = self.raft_log.committed;
+			self.verified_index = cmp::max(self.verified_index, committed);
 			let mut m = Message::new();
 			m.set_to(msg.get_from());
 			m.set_msg_type(MessageType::MsgAppResp);
-			m.set_index(self.raft_log.committed);
+			m.set_index(self.durable_index(committed));
 			self.send(m);
 			return;
 		}
@@ -1913,10 +3087,11 @@ impl<T: Storage> Raft<T> {
 			msg.get_commit(),
 			msg.get_entries(),
 		) {
+			self.verified_index = mlast_index;
 			let mut m = Message::new();
 			m.set_to(msg.get_from());
 			m.set_msg_type(MessageType::MsgAppResp);
-			m.set_index(mlast_index);
+			m.set_index(self.durable_index(mlast_index));
 			self.send(m);
 		} else {
 			debug!(
@@ -1931,18 +3106,73 @@ impl<T: Storage> Raft<T> {
 				msg.get_from(),
 			);
 
+			// Return a hint to the leader about the maximum index and term that
+			// the two logs could be divergent at. Every entry of ours with a
+			// term above the leader's one at msg.index can't match, so they are
+			// skipped at once, and the leader does the same on its side with
+			// the returned term, see handle_append_resp.
+			let (hint_index, hint_term) = self
+				.raft_log
+				.find_conflict_by_term(msg.get_index(), msg.get_log_term());
 			let mut m = Message::new();
 			m.set_to(msg.get_from());
 			m.set_msg_type(MessageType::MsgAppResp);
 			m.set_reject(true);
 			m.set_index(msg.get_index());
-			m.set_reject_hint(self.raft_log.last_index());
+			m.set_reject_hint(hint_index);
+			m.set_log_term(hint_term);
 			self.send(m);
 		}
 	}
 
-	pub fn campaign(&mut self, campaign_type: &[u8]) {
-		let (term, vote_msg) = if campaign_type == CAMPAIGN_PRE_ELECTION {
+	/// hup starts an election of the given type, unless the node is already
+	/// leader or has committed conf changes left to apply.
+	pub fn hup(&mut self, campaign_type: CampaignType) {
+		if self.state == StateType::Leader {
+			debug!(
+				"{} {} ignoring MsgHup because already leader",
+				self.tag, self.term
+			);
+			return;
+		}
+		if !self.promotable() {
+			warn!(
+				"{} {} is unpromotable and can not campaign",
+				self.tag, self.id
+			);
+			return;
+		}
+
+		let ents = match self.raft_log.slice(
+			self.raft_log.applied + 1,
+			self.raft_log.committed + 1,
+			NO_LIMIT,
+		) {
+			Ok(ents) => ents,
+			Err(e) => panic!(e),
+		};
+
+		let n = num_of_pending_conf(&ents);
+		if n > 0 && self.raft_log.committed > self.raft_log.applied {
+			warn!(
+				"{} {} cannot campaign at term {} since there are still {} pending configuration changes to apply", 
+				self.tag,
+				self.id,
+				self.term,
+				n,
+			);
+			return;
+		}
+
+		info!(
+			"{} {} is starting a new election at term {}",
+			self.tag, self.id, self.term
+		);
+		self.campaign(campaign_type);
+	}
+
+	pub fn campaign(&mut self, campaign_type: CampaignType) {
+		let (term, vote_msg) = if campaign_type == CampaignType::PreElection {
 			self.become_pre_candidate();
 			(self.term + 1, MessageType::MsgPreVote)
 		} else {
@@ -1951,9 +3181,10 @@ impl<T: Storage> Raft<T> {
 		};
 
 		let id = self.id;
-		if self.quorum() == self.poll(id, vote_msg_resp_type(vote_msg), true) {
-			if campaign_type == CAMPAIGN_PRE_ELECTION {
-				self.campaign(CAMPAIGN_ELECTION);
+		self.poll(id, vote_msg_resp_type(vote_msg), true);
+		if self.tracker.vote_result() == VoteResult::Won {
+			if campaign_type == CampaignType::PreElection {
+				self.campaign(CampaignType::Election);
 			} else {
 				self.become_leader();
 			}
@@ -1982,19 +3213,20 @@ impl<T: Storage> Raft<T> {
 				msg.set_msg_type(vote_msg);
 				msg.set_index(self.raft_log.last_index());
 				msg.set_log_term(self.raft_log.last_term());
+				msg.set_priority(self.priority);
 
-				if campaign_type == CAMPAIGN_TRANSFER {
-					msg.set_context(campaign_type.to_vec());
+				if campaign_type == CampaignType::Transfer {
+					msg.set_context(campaign_type.context().to_vec());
 				}
 				self.send(msg);
 			});
 	}
 
 	fn get_prs_ids(&self) -> Vec<u64> {
-		self.prs.keys().cloned().collect()
+		self.tracker.voter_ids()
 	}
 
-	fn poll(&mut self, id: u64, t: MessageType, v: bool) -> usize {
+	fn poll(&mut self, id: u64, t: MessageType, v: bool) -> (usize, usize) {
 		if v {
 			info!(
 				"{} {} received {:?} from {} at term {}",
@@ -2007,13 +3239,25 @@ impl<T: Storage> Raft<T> {
 			);
 		}
 
-		self.votes.entry(id).or_insert(v);
-		let count = self.votes.values().filter(|v| **v).count();
-		count
+		self.tracker.record_vote(id, v);
+		self.tracker.tally_votes()
 	}
 
 	fn quorum(&self) -> usize {
-		self.prs.len() / 2 + 1
+		self.tracker.prs.len() / 2 + 1
+	}
+
+	/// set_quorum_policy replaces the simple majority quorum used to elect a
+	/// leader and to commit entries, see QuorumPolicy.
+	pub fn set_quorum_policy<P>(&mut self, policy: P)
+	where
+		P: QuorumPolicy + 'static,
+	{
+		self.tracker.set_quorum_policy(policy);
+	}
+
+	fn is_replication_quorum(&self, voters: &[u64], acked: &[u64]) -> bool {
+		self.tracker.is_replication_quorum(voters, acked)
 	}
 
 	// send persists state to stable storage and then sends to its mailbox.
@@ -2058,6 +3302,15 @@ impl<T: Storage> Raft<T> {
 			{
 				msg.set_term(self.term);
 			}
+			if msg.get_msg_type() == MessageType::MsgAppResp {
+				msg.set_append_budget(self.append_budget);
+			}
+		}
+		if self.replication_budget != 0
+			&& (msg.get_msg_type() == MessageType::MsgApp
+				|| msg.get_msg_type() == MessageType::MsgSnap)
+		{
+			self.replication_bytes += u64::from(msg.compute_size());
 		}
 		self.msgs.push(msg);
 	}
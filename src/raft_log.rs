@@ -4,7 +4,7 @@ use errors::{Error, Result, StorageError};
 use log_unstable::Unstable;
 use raftpb::{Entry, Snapshot};
 use storage::Storage;
-use util::{limit_size, NO_LIMIT};
+use util::{entries_size, limit_size, verify_entry_checksum, NO_LIMIT};
 
 #[derive(Debug, Default)]
 pub struct RaftLog<T: Storage> {
@@ -24,6 +24,17 @@ pub struct RaftLog<T: Storage> {
     /// Invariant: applied <= committed
     pub applied: u64,
 
+    /// persisted is the highest log position known to be in stable storage,
+    /// as reported through stable_to and stable_snap_to. Unlike
+    /// unstable.offset it never covers entries only received but not yet
+    /// written, so it bounds what this node may count or acknowledge as
+    /// durable.
+    pub persisted: u64,
+
+    /// max_next_ents_size is the max size in bytes of the entries returned
+    /// by next_ents.
+    pub max_next_ents_size: u64,
+
     /// tag only used for logger.
     pub tag: String,
 }
@@ -48,6 +59,8 @@ impl<T: Storage> RaftLog<T> {
             storage,
             committed: first_index - 1,
             applied: first_index - 1,
+            persisted: last_index,
+            max_next_ents_size: NO_LIMIT,
             unstable: Unstable::new(last_index + 1, tag.clone()),
             tag,
         }
@@ -134,6 +147,8 @@ impl<T: Storage> RaftLog<T> {
                 after, self.committed
             );
         }
+        // entries from after on are replaced, they are not durable any more.
+        self.persisted = cmp::min(self.persisted, after);
         self.unstable.truncate_and_append(ents);
         self.last_index()
     }
@@ -211,12 +226,29 @@ impl<T: Storage> RaftLog<T> {
                         return Err(e);
                     }
                     Error::Storage(StorageError::Unavailable) => {
+                        if self.storage_compacted_past(lo) {
+                            return Err(Error::Storage(StorageError::Compacted));
+                        }
                         panic!("entries[{}:{}) is unavailable from storage", lo, hi);
                     }
                     _ => panic!(e),
                 },
             };
 
+            // storage may have been compacted after the bound check above, in
+            // which case it can't serve the beginning of the range any more.
+            if sorted_ents.first().map(|e| e.get_index()) != Some(lo) {
+                if self.storage_compacted_past(lo) {
+                    return Err(Error::Storage(StorageError::Compacted));
+                }
+                panic!(
+                    "{} storage returned entries starting at {:?}, want {}",
+                    self.tag,
+                    sorted_ents.first().map(|e| e.get_index()),
+                    lo
+                );
+            }
+
             // check if has reached the size limitation
             if (sorted_ents.len() as u64) < cmp::min(hi, self.unstable.offset) - lo {
                 return Ok(sorted_ents);
@@ -233,6 +265,13 @@ impl<T: Storage> RaftLog<T> {
         Ok(ents)
     }
 
+    fn storage_compacted_past(&self, lo: u64) -> bool {
+        match self.storage.first_index() {
+            Ok(fi) => lo < fi,
+            Err(_) => false,
+        }
+    }
+
     pub fn entries(&self, i: u64, max_size: u64) -> Result<Vec<Entry>> {
         if i > self.last_index() {
             Ok(vec![])
@@ -288,6 +327,24 @@ impl<T: Storage> RaftLog<T> {
         0
     }
 
+    /// find_conflict_by_term takes an (index, term) pair, indicating a
+    /// conflicting log entry on a leader/follower during an append, and finds
+    /// the largest index in this log with a term <= the given term, along
+    /// with that term. The index is at most the given one.
+    /// When the term at an index is unknown, e.g. compacted, that index is
+    /// returned with a zero term, as it may match.
+    pub fn find_conflict_by_term(&self, index: u64, term: u64) -> (u64, u64) {
+        let mut index = cmp::min(index, self.last_index());
+        while index > 0 {
+            match self.term(index) {
+                Ok(t) if t <= term => return (index, t),
+                Ok(_) => index -= 1,
+                Err(_) => return (index, 0),
+            }
+        }
+        (0, 0)
+    }
+
     // maybe_append returns None if the entries cannot be appended. Otherwise,
     // it returns Some(last index of new entries).
     pub fn maybe_append(
@@ -325,6 +382,9 @@ impl<T: Storage> RaftLog<T> {
             s.get_metadata().get_term()
         );
 
+        // the snapshot replaces the whole log, only the committed prefix is
+        // known to be the same and durable until the snapshot is persisted.
+        self.persisted = cmp::min(self.persisted, self.committed);
         self.committed = s.get_metadata().get_index();
         self.unstable.restore(s);
     }
@@ -337,6 +397,13 @@ impl<T: Storage> RaftLog<T> {
         self.storage.snapshot()
     }
 
+    /// log_size returns the total size in bytes of the entries in the log,
+    /// as tracked by storage plus the unstable entries not yet persisted.
+    pub fn log_size(&self) -> u64 {
+        let stable = self.storage.log_size().unwrap_or(0);
+        stable + entries_size(&self.unstable.entries)
+    }
+
     pub fn unstable_entries(&self) -> Vec<Entry> {
         self.unstable.entries.to_vec()
     }
@@ -344,10 +411,21 @@ impl<T: Storage> RaftLog<T> {
     pub fn next_ents(&self) -> Vec<Entry> {
         let off = cmp::max(self.applied + 1, self.first_index());
         if self.committed + 1 > off {
-            match self.slice(off, self.committed + 1, NO_LIMIT) {
-                Ok(ents) => return ents,
+            let ents = match self.slice(off, self.committed + 1, self.max_next_ents_size) {
+                Ok(ents) => ents,
                 Err(e) => panic!("unexpected error when getting unapplied entries ({})", e),
+            };
+            for e in &ents {
+                if !verify_entry_checksum(e) {
+                    panic!(
+                        "{} entry [index: {}, term: {}] is corrupted: checksum mismatch",
+                        self.tag,
+                        e.get_index(),
+                        e.get_term(),
+                    );
+                }
             }
+            return ents;
         }
         vec![]
     }
@@ -358,11 +436,15 @@ impl<T: Storage> RaftLog<T> {
     }
 
     pub fn stable_to(&mut self, index: u64, term: u64) {
-        self.unstable.stable_to(index, term);
+        if self.unstable.stable_to(index, term) {
+            self.persisted = cmp::max(self.persisted, index);
+        }
     }
 
     pub fn stable_snap_to(&mut self, index: u64) {
-        self.unstable.stable_snap_to(index);
+        if self.unstable.stable_snap_to(index) {
+            self.persisted = cmp::max(self.persisted, index);
+        }
     }
 
     pub fn all_entries(&self) -> Vec<Entry> {
@@ -381,7 +463,7 @@ impl<T: Storage> RaftLog<T> {
 mod test {
     use super::*;
     use protobuf::Message;
-    use raftpb::SnapshotMetadata;
+    use raftpb::{ConfState, HardState, SnapshotMetadata};
     use storage::MemStorage;
 
     fn new_entry(index: u64, term: u64) -> Entry {
@@ -453,6 +535,43 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_find_conflict_by_term() {
+        let ents = vec![
+            new_entry(1, 1),
+            new_entry(2, 2),
+            new_entry(3, 2),
+            new_entry(4, 4),
+            new_entry(5, 4),
+        ];
+        let tests = vec![
+            // the term at index matches.
+            (5, 4, (5, 4)),
+            (3, 2, (3, 2)),
+            // skips the entries with a greater term.
+            (5, 3, (3, 2)),
+            (5, 1, (1, 1)),
+            (5, 0, (0, 0)),
+            // the index is capped to the last index.
+            (10, 4, (5, 4)),
+            (10, 3, (3, 2)),
+        ];
+
+        for (index, term, w) in tests {
+            let mut log = new_raft_log(MemStorage::new(), String::default());
+            log.append(&ents);
+            assert_eq!(log.find_conflict_by_term(index, term), w, "{}/{}", index, term);
+        }
+
+        // the term of compacted entries is unknown.
+        let mut storage = MemStorage::new();
+        storage.apply_snapshot(new_snapshot(3, 2)).unwrap();
+        let mut log = new_raft_log(storage, String::default());
+        log.append(&[new_entry(4, 4), new_entry(5, 4)]);
+        assert_eq!(log.find_conflict_by_term(5, 3), (3, 2));
+        assert_eq!(log.find_conflict_by_term(2, 1), (2, 0));
+    }
+
     #[test]
     fn test_is_up_to_date() {
         let previous_ents = vec![new_entry(1, 1), new_entry(2, 2), new_entry(3, 3)];
@@ -671,6 +790,10 @@ mod test {
         assert_eq!(log.unstable_entries().len(), 250);
         assert_eq!(log.unstable_entries()[0].get_index(), 751);
 
+        let stable = log.storage.log_size().unwrap();
+        let unstable = entries_size(&log.unstable_entries());
+        assert_eq!(log.log_size(), stable + unstable);
+
         let prev = log.last_index();
         log.append(&vec![new_entry(prev + 1, prev + 1)]);
         assert_eq!(log.last_index(), prev + 1);
@@ -723,6 +846,27 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_next_ents_with_limit() {
+        let ents = vec![new_entry(1, 1), new_entry(2, 1), new_entry(3, 1)];
+        let size = u64::from(ents[0].compute_size());
+        let tests = vec![
+            (0, &ents[0..1]),
+            (size, &ents[0..1]),
+            (size * 2, &ents[0..2]),
+            (NO_LIMIT, &ents[..]),
+        ];
+
+        for (max, wents) in tests {
+            let mut log = new_raft_log(MemStorage::new(), String::default());
+            log.append(&ents);
+            log.maybe_commit(3, 1);
+            log.max_next_ents_size = max;
+
+            assert_eq!(wents.to_vec(), log.next_ents(), "max {}", max);
+        }
+    }
+
     #[test]
     fn test_term() {
         let offset = 100;
@@ -877,4 +1021,71 @@ mod test {
             assert_eq!(log.slice(from, to, limit), wents);
         }
     }
+
+    // CompactingStorage compacts the shared storage right before serving a
+    // read, as a concurrent compaction would. If lenient is set, it serves
+    // whatever part of the range is left instead of returning Compacted.
+    struct CompactingStorage {
+        storage: MemStorage,
+        compact_index: u64,
+        lenient: bool,
+    }
+
+    impl Storage for CompactingStorage {
+        fn initial_state(&self) -> Result<(HardState, ConfState)> {
+            self.storage.initial_state()
+        }
+
+        fn entries(&self, low: u64, high: u64, max_size: u64) -> Result<Vec<Entry>> {
+            // a repeated compaction to the same index is a no-op.
+            let _ = self.storage.clone().compact(self.compact_index);
+            let fi = self.storage.first_index().unwrap();
+            if self.lenient && low < fi {
+                return self.storage.entries(fi, high, max_size);
+            }
+            self.storage.entries(low, high, max_size)
+        }
+
+        fn term(&self, idx: u64) -> Result<u64> {
+            self.storage.term(idx)
+        }
+
+        fn first_index(&self) -> Result<u64> {
+            self.storage.first_index()
+        }
+
+        fn last_index(&self) -> Result<u64> {
+            self.storage.last_index()
+        }
+
+        fn snapshot(&self) -> Result<Snapshot> {
+            self.storage.snapshot()
+        }
+    }
+
+    #[test]
+    fn test_slice_concurrent_compaction() {
+        for &lenient in &[false, true] {
+            let mut storage = MemStorage::new();
+            for i in 1..11 {
+                storage.append(&[new_entry(i, i)]).unwrap();
+            }
+            let log = new_raft_log(
+                CompactingStorage {
+                    storage: storage.clone(),
+                    compact_index: 5,
+                    lenient,
+                },
+                String::default(),
+            );
+
+            assert_eq!(
+                log.slice(3, 8, NO_LIMIT),
+                Err(Error::Storage(StorageError::Compacted))
+            );
+            // the rest of the range is still served.
+            let wents: Vec<Entry> = (6..8).map(|i| new_entry(i, i)).collect();
+            assert_eq!(log.slice(6, 8, NO_LIMIT), Ok(wents));
+        }
+    }
 }
@@ -28,6 +28,8 @@ pub struct Entry {
     pub index: u64,
     pub entry_type: EntryType,
     pub data: ::std::vec::Vec<u8>,
+    pub checksum: u32,
+    pub context: ::std::vec::Vec<u8>,
     // special fields
     unknown_fields: ::protobuf::UnknownFields,
     cached_size: ::protobuf::CachedSize,
@@ -153,6 +155,63 @@ impl Entry {
     fn mut_data_for_reflect(&mut self) -> &mut ::std::vec::Vec<u8> {
         &mut self.data
     }
+
+    // uint32 checksum = 5;
+
+    pub fn clear_checksum(&mut self) {
+        self.checksum = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_checksum(&mut self, v: u32) {
+        self.checksum = v;
+    }
+
+    pub fn get_checksum(&self) -> u32 {
+        self.checksum
+    }
+
+    fn get_checksum_for_reflect(&self) -> &u32 {
+        &self.checksum
+    }
+
+    fn mut_checksum_for_reflect(&mut self) -> &mut u32 {
+        &mut self.checksum
+    }
+
+    // bytes context = 6;
+
+    pub fn clear_context(&mut self) {
+        self.context.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_context(&mut self, v: ::std::vec::Vec<u8>) {
+        self.context = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_context(&mut self) -> &mut ::std::vec::Vec<u8> {
+        &mut self.context
+    }
+
+    // Take field
+    pub fn take_context(&mut self) -> ::std::vec::Vec<u8> {
+        ::std::mem::replace(&mut self.context, ::std::vec::Vec::new())
+    }
+
+    pub fn get_context(&self) -> &[u8] {
+        &self.context
+    }
+
+    fn get_context_for_reflect(&self) -> &::std::vec::Vec<u8> {
+        &self.context
+    }
+
+    fn mut_context_for_reflect(&mut self) -> &mut ::std::vec::Vec<u8> {
+        &mut self.context
+    }
 }
 
 impl ::protobuf::Message for Entry {
@@ -184,6 +243,16 @@ impl ::protobuf::Message for Entry {
                 4 => {
                     ::protobuf::rt::read_singular_proto3_bytes_into(wire_type, is, &mut self.data)?;
                 },
+                5 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint32()?;
+                    self.checksum = tmp;
+                },
+                6 => {
+                    ::protobuf::rt::read_singular_proto3_bytes_into(wire_type, is, &mut self.context)?;
+                },
                 _ => {
                     ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
                 },
@@ -208,6 +277,12 @@ impl ::protobuf::Message for Entry {
         if !self.data.is_empty() {
             my_size += ::protobuf::rt::bytes_size(4, &self.data);
         }
+        if self.checksum != 0 {
+            my_size += ::protobuf::rt::value_size(5, self.checksum, ::protobuf::wire_format::WireTypeVarint);
+        }
+        if !self.context.is_empty() {
+            my_size += ::protobuf::rt::bytes_size(6, &self.context);
+        }
         my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
         self.cached_size.set(my_size);
         my_size
@@ -226,6 +301,12 @@ impl ::protobuf::Message for Entry {
         if !self.data.is_empty() {
             os.write_bytes(4, &self.data)?;
         }
+        if self.checksum != 0 {
+            os.write_uint32(5, self.checksum)?;
+        }
+        if !self.context.is_empty() {
+            os.write_bytes(6, &self.context)?;
+        }
         os.write_unknown_fields(self.get_unknown_fields())?;
         ::std::result::Result::Ok(())
     }
@@ -290,6 +371,16 @@ impl ::protobuf::MessageStatic for Entry {
                     Entry::get_data_for_reflect,
                     Entry::mut_data_for_reflect,
                 ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint32>(
+                    "checksum",
+                    Entry::get_checksum_for_reflect,
+                    Entry::mut_checksum_for_reflect,
+                ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeBytes>(
+                    "context",
+                    Entry::get_context_for_reflect,
+                    Entry::mut_context_for_reflect,
+                ));
                 ::protobuf::reflect::MessageDescriptor::new::<Entry>(
                     "Entry",
                     fields,
@@ -306,6 +397,8 @@ impl ::protobuf::Clear for Entry {
         self.clear_index();
         self.clear_entry_type();
         self.clear_data();
+        self.clear_checksum();
+        self.clear_context();
         self.unknown_fields.clear();
     }
 }
@@ -845,6 +938,9 @@ pub struct ConfState {
     // message fields
     pub nodes: ::std::vec::Vec<u64>,
     pub learners: ::std::vec::Vec<u64>,
+    pub voters_outgoing: ::std::vec::Vec<u64>,
+    pub auto_leave: bool,
+    pub learners_next: ::std::vec::Vec<u64>,
     // special fields
     unknown_fields: ::protobuf::UnknownFields,
     cached_size: ::protobuf::CachedSize,
@@ -933,6 +1029,95 @@ impl ConfState {
     fn mut_learners_for_reflect(&mut self) -> &mut ::std::vec::Vec<u64> {
         &mut self.learners
     }
+
+    // repeated uint64 voters_outgoing = 3;
+
+    pub fn clear_voters_outgoing(&mut self) {
+        self.voters_outgoing.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_voters_outgoing(&mut self, v: ::std::vec::Vec<u64>) {
+        self.voters_outgoing = v;
+    }
+
+    // Mutable pointer to the field.
+    pub fn mut_voters_outgoing(&mut self) -> &mut ::std::vec::Vec<u64> {
+        &mut self.voters_outgoing
+    }
+
+    // Take field
+    pub fn take_voters_outgoing(&mut self) -> ::std::vec::Vec<u64> {
+        ::std::mem::replace(&mut self.voters_outgoing, ::std::vec::Vec::new())
+    }
+
+    pub fn get_voters_outgoing(&self) -> &[u64] {
+        &self.voters_outgoing
+    }
+
+    fn get_voters_outgoing_for_reflect(&self) -> &::std::vec::Vec<u64> {
+        &self.voters_outgoing
+    }
+
+    fn mut_voters_outgoing_for_reflect(&mut self) -> &mut ::std::vec::Vec<u64> {
+        &mut self.voters_outgoing
+    }
+
+    // bool auto_leave = 4;
+
+    pub fn clear_auto_leave(&mut self) {
+        self.auto_leave = false;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_auto_leave(&mut self, v: bool) {
+        self.auto_leave = v;
+    }
+
+    pub fn get_auto_leave(&self) -> bool {
+        self.auto_leave
+    }
+
+    fn get_auto_leave_for_reflect(&self) -> &bool {
+        &self.auto_leave
+    }
+
+    fn mut_auto_leave_for_reflect(&mut self) -> &mut bool {
+        &mut self.auto_leave
+    }
+
+    // repeated uint64 learners_next = 5;
+
+    pub fn clear_learners_next(&mut self) {
+        self.learners_next.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_learners_next(&mut self, v: ::std::vec::Vec<u64>) {
+        self.learners_next = v;
+    }
+
+    // Mutable pointer to the field.
+    pub fn mut_learners_next(&mut self) -> &mut ::std::vec::Vec<u64> {
+        &mut self.learners_next
+    }
+
+    // Take field
+    pub fn take_learners_next(&mut self) -> ::std::vec::Vec<u64> {
+        ::std::mem::replace(&mut self.learners_next, ::std::vec::Vec::new())
+    }
+
+    pub fn get_learners_next(&self) -> &[u64] {
+        &self.learners_next
+    }
+
+    fn get_learners_next_for_reflect(&self) -> &::std::vec::Vec<u64> {
+        &self.learners_next
+    }
+
+    fn mut_learners_next_for_reflect(&mut self) -> &mut ::std::vec::Vec<u64> {
+        &mut self.learners_next
+    }
 }
 
 impl ::protobuf::Message for ConfState {
@@ -950,6 +1135,19 @@ impl ::protobuf::Message for ConfState {
                 2 => {
                     ::protobuf::rt::read_repeated_uint64_into(wire_type, is, &mut self.learners)?;
                 },
+                3 => {
+                    ::protobuf::rt::read_repeated_uint64_into(wire_type, is, &mut self.voters_outgoing)?;
+                },
+                4 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_bool()?;
+                    self.auto_leave = tmp;
+                },
+                5 => {
+                    ::protobuf::rt::read_repeated_uint64_into(wire_type, is, &mut self.learners_next)?;
+                },
                 _ => {
                     ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
                 },
@@ -968,6 +1166,15 @@ impl ::protobuf::Message for ConfState {
         for value in &self.learners {
             my_size += ::protobuf::rt::value_size(2, *value, ::protobuf::wire_format::WireTypeVarint);
         };
+        for value in &self.voters_outgoing {
+            my_size += ::protobuf::rt::value_size(3, *value, ::protobuf::wire_format::WireTypeVarint);
+        };
+        if self.auto_leave != false {
+            my_size += 2;
+        }
+        for value in &self.learners_next {
+            my_size += ::protobuf::rt::value_size(5, *value, ::protobuf::wire_format::WireTypeVarint);
+        };
         my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
         self.cached_size.set(my_size);
         my_size
@@ -980,6 +1187,15 @@ impl ::protobuf::Message for ConfState {
         for v in &self.learners {
             os.write_uint64(2, *v)?;
         };
+        for v in &self.voters_outgoing {
+            os.write_uint64(3, *v)?;
+        };
+        if self.auto_leave != false {
+            os.write_bool(4, self.auto_leave)?;
+        }
+        for v in &self.learners_next {
+            os.write_uint64(5, *v)?;
+        };
         os.write_unknown_fields(self.get_unknown_fields())?;
         ::std::result::Result::Ok(())
     }
@@ -1034,6 +1250,21 @@ impl ::protobuf::MessageStatic for ConfState {
                     ConfState::get_learners_for_reflect,
                     ConfState::mut_learners_for_reflect,
                 ));
+                fields.push(::protobuf::reflect::accessor::make_vec_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
+                    "voters_outgoing",
+                    ConfState::get_voters_outgoing_for_reflect,
+                    ConfState::mut_voters_outgoing_for_reflect,
+                ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeBool>(
+                    "auto_leave",
+                    ConfState::get_auto_leave_for_reflect,
+                    ConfState::mut_auto_leave_for_reflect,
+                ));
+                fields.push(::protobuf::reflect::accessor::make_vec_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
+                    "learners_next",
+                    ConfState::get_learners_next_for_reflect,
+                    ConfState::mut_learners_next_for_reflect,
+                ));
                 ::protobuf::reflect::MessageDescriptor::new::<ConfState>(
                     "ConfState",
                     fields,
@@ -1048,6 +1279,9 @@ impl ::protobuf::Clear for ConfState {
     fn clear(&mut self) {
         self.clear_nodes();
         self.clear_learners();
+        self.clear_voters_outgoing();
+        self.clear_auto_leave();
+        self.clear_learners_next();
         self.unknown_fields.clear();
     }
 }
@@ -1070,6 +1304,7 @@ pub struct HardState {
     pub term: u64,
     pub vote: u64,
     pub commit: u64,
+    pub extensions: ::protobuf::RepeatedField<HardStateExtension>,
     // special fields
     unknown_fields: ::protobuf::UnknownFields,
     cached_size: ::protobuf::CachedSize,
@@ -1161,10 +1396,48 @@ impl HardState {
     fn mut_commit_for_reflect(&mut self) -> &mut u64 {
         &mut self.commit
     }
+
+    // repeated .raftpb.HardStateExtension extensions = 4;
+
+    pub fn clear_extensions(&mut self) {
+        self.extensions.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_extensions(&mut self, v: ::protobuf::RepeatedField<HardStateExtension>) {
+        self.extensions = v;
+    }
+
+    // Mutable pointer to the field.
+    pub fn mut_extensions(&mut self) -> &mut ::protobuf::RepeatedField<HardStateExtension> {
+        &mut self.extensions
+    }
+
+    // Take field
+    pub fn take_extensions(&mut self) -> ::protobuf::RepeatedField<HardStateExtension> {
+        ::std::mem::replace(&mut self.extensions, ::protobuf::RepeatedField::new())
+    }
+
+    pub fn get_extensions(&self) -> &[HardStateExtension] {
+        &self.extensions
+    }
+
+    fn get_extensions_for_reflect(&self) -> &::protobuf::RepeatedField<HardStateExtension> {
+        &self.extensions
+    }
+
+    fn mut_extensions_for_reflect(&mut self) -> &mut ::protobuf::RepeatedField<HardStateExtension> {
+        &mut self.extensions
+    }
 }
 
 impl ::protobuf::Message for HardState {
     fn is_initialized(&self) -> bool {
+        for v in &self.extensions {
+            if !v.is_initialized() {
+                return false;
+            }
+        };
         true
     }
 
@@ -1193,6 +1466,9 @@ impl ::protobuf::Message for HardState {
                     let tmp = is.read_uint64()?;
                     self.commit = tmp;
                 },
+                4 => {
+                    ::protobuf::rt::read_repeated_message_into(wire_type, is, &mut self.extensions)?;
+                },
                 _ => {
                     ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
                 },
@@ -1214,6 +1490,10 @@ impl ::protobuf::Message for HardState {
         if self.commit != 0 {
             my_size += ::protobuf::rt::value_size(3, self.commit, ::protobuf::wire_format::WireTypeVarint);
         }
+        for value in &self.extensions {
+            let len = value.compute_size();
+            my_size += 1 + ::protobuf::rt::compute_raw_varint32_size(len) + len;
+        };
         my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
         self.cached_size.set(my_size);
         my_size
@@ -1229,6 +1509,11 @@ impl ::protobuf::Message for HardState {
         if self.commit != 0 {
             os.write_uint64(3, self.commit)?;
         }
+        for v in &self.extensions {
+            os.write_tag(4, ::protobuf::wire_format::WireTypeLengthDelimited)?;
+            os.write_raw_varint32(v.get_cached_size())?;
+            v.write_to_with_cached_sizes(os)?;
+        };
         os.write_unknown_fields(self.get_unknown_fields())?;
         ::std::result::Result::Ok(())
     }
@@ -1288,6 +1573,11 @@ impl ::protobuf::MessageStatic for HardState {
                     HardState::get_commit_for_reflect,
                     HardState::mut_commit_for_reflect,
                 ));
+                fields.push(::protobuf::reflect::accessor::make_repeated_field_accessor::<_, ::protobuf::types::ProtobufTypeMessage<HardStateExtension>>(
+                    "extensions",
+                    HardState::get_extensions_for_reflect,
+                    HardState::mut_extensions_for_reflect,
+                ));
                 ::protobuf::reflect::MessageDescriptor::new::<HardState>(
                     "HardState",
                     fields,
@@ -1303,6 +1593,7 @@ impl ::protobuf::Clear for HardState {
         self.clear_term();
         self.clear_vote();
         self.clear_commit();
+        self.clear_extensions();
         self.unknown_fields.clear();
     }
 }
@@ -1320,115 +1611,336 @@ impl ::protobuf::reflect::ProtobufValue for HardState {
 }
 
 #[derive(PartialEq,Clone,Default)]
-pub struct Message {
+pub struct HardStateExtension {
     // message fields
-    pub msg_type: MessageType,
-    pub to: u64,
-    pub from: u64,
-    pub term: u64,
-    pub log_term: u64,
-    pub index: u64,
-    pub entries: ::protobuf::RepeatedField<Entry>,
-    pub commit: u64,
-    pub snapshot: ::protobuf::SingularPtrField<Snapshot>,
-    pub reject: bool,
-    pub reject_hint: u64,
-    pub context: ::std::vec::Vec<u8>,
+    pub id: u32,
+    pub data: ::std::vec::Vec<u8>,
     // special fields
     unknown_fields: ::protobuf::UnknownFields,
     cached_size: ::protobuf::CachedSize,
 }
 
 // see codegen.rs for the explanation why impl Sync explicitly
-unsafe impl ::std::marker::Sync for Message {}
+unsafe impl ::std::marker::Sync for HardStateExtension {}
 
-impl Message {
-    pub fn new() -> Message {
+impl HardStateExtension {
+    pub fn new() -> HardStateExtension {
         ::std::default::Default::default()
     }
 
-    pub fn default_instance() -> &'static Message {
-        static mut instance: ::protobuf::lazy::Lazy<Message> = ::protobuf::lazy::Lazy {
+    pub fn default_instance() -> &'static HardStateExtension {
+        static mut instance: ::protobuf::lazy::Lazy<HardStateExtension> = ::protobuf::lazy::Lazy {
             lock: ::protobuf::lazy::ONCE_INIT,
-            ptr: 0 as *const Message,
+            ptr: 0 as *const HardStateExtension,
         };
         unsafe {
-            instance.get(Message::new)
+            instance.get(HardStateExtension::new)
         }
     }
 
-    // .raftpb.MessageType msg_type = 1;
+    // uint32 id = 1;
 
-    pub fn clear_msg_type(&mut self) {
-        self.msg_type = MessageType::MsgHup;
+    pub fn clear_id(&mut self) {
+        self.id = 0;
     }
 
     // Param is passed by value, moved
-    pub fn set_msg_type(&mut self, v: MessageType) {
-        self.msg_type = v;
+    pub fn set_id(&mut self, v: u32) {
+        self.id = v;
     }
 
-    pub fn get_msg_type(&self) -> MessageType {
-        self.msg_type
+    pub fn get_id(&self) -> u32 {
+        self.id
     }
 
-    fn get_msg_type_for_reflect(&self) -> &MessageType {
-        &self.msg_type
+    fn get_id_for_reflect(&self) -> &u32 {
+        &self.id
     }
 
-    fn mut_msg_type_for_reflect(&mut self) -> &mut MessageType {
-        &mut self.msg_type
+    fn mut_id_for_reflect(&mut self) -> &mut u32 {
+        &mut self.id
     }
 
-    // uint64 to = 2;
+    // bytes data = 2;
 
-    pub fn clear_to(&mut self) {
-        self.to = 0;
+    pub fn clear_data(&mut self) {
+        self.data.clear();
     }
 
     // Param is passed by value, moved
-    pub fn set_to(&mut self, v: u64) {
-        self.to = v;
-    }
-
-    pub fn get_to(&self) -> u64 {
-        self.to
-    }
-
-    fn get_to_for_reflect(&self) -> &u64 {
-        &self.to
+    pub fn set_data(&mut self, v: ::std::vec::Vec<u8>) {
+        self.data = v;
     }
 
-    fn mut_to_for_reflect(&mut self) -> &mut u64 {
-        &mut self.to
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_data(&mut self) -> &mut ::std::vec::Vec<u8> {
+        &mut self.data
     }
 
-    // uint64 from = 3;
-
-    pub fn clear_from(&mut self) {
-        self.from = 0;
+    // Take field
+    pub fn take_data(&mut self) -> ::std::vec::Vec<u8> {
+        ::std::mem::replace(&mut self.data, ::std::vec::Vec::new())
     }
 
-    // Param is passed by value, moved
-    pub fn set_from(&mut self, v: u64) {
-        self.from = v;
+    pub fn get_data(&self) -> &[u8] {
+        &self.data
     }
 
-    pub fn get_from(&self) -> u64 {
-        self.from
+    fn get_data_for_reflect(&self) -> &::std::vec::Vec<u8> {
+        &self.data
     }
 
-    fn get_from_for_reflect(&self) -> &u64 {
-        &self.from
+    fn mut_data_for_reflect(&mut self) -> &mut ::std::vec::Vec<u8> {
+        &mut self.data
     }
+}
 
-    fn mut_from_for_reflect(&mut self) -> &mut u64 {
-        &mut self.from
+impl ::protobuf::Message for HardStateExtension {
+    fn is_initialized(&self) -> bool {
+        true
     }
 
-    // uint64 term = 4;
-
-    pub fn clear_term(&mut self) {
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint32()?;
+                    self.id = tmp;
+                },
+                2 => {
+                    ::protobuf::rt::read_singular_proto3_bytes_into(wire_type, is, &mut self.data)?;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if self.id != 0 {
+            my_size += ::protobuf::rt::value_size(1, self.id, ::protobuf::wire_format::WireTypeVarint);
+        }
+        if !self.data.is_empty() {
+            my_size += ::protobuf::rt::bytes_size(2, &self.data);
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream) -> ::protobuf::ProtobufResult<()> {
+        if self.id != 0 {
+            os.write_uint32(1, self.id)?;
+        }
+        if !self.data.is_empty() {
+            os.write_bytes(2, &self.data)?;
+        }
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &::std::any::Any {
+        self as &::std::any::Any
+    }
+    fn as_any_mut(&mut self) -> &mut ::std::any::Any {
+        self as &mut ::std::any::Any
+    }
+    fn into_any(self: Box<Self>) -> ::std::boxed::Box<::std::any::Any> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        ::protobuf::MessageStatic::descriptor_static(None::<Self>)
+    }
+}
+
+impl ::protobuf::MessageStatic for HardStateExtension {
+    fn new() -> HardStateExtension {
+        HardStateExtension::new()
+    }
+
+    fn descriptor_static(_: ::std::option::Option<HardStateExtension>) -> &'static ::protobuf::reflect::MessageDescriptor {
+        static mut descriptor: ::protobuf::lazy::Lazy<::protobuf::reflect::MessageDescriptor> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const ::protobuf::reflect::MessageDescriptor,
+        };
+        unsafe {
+            descriptor.get(|| {
+                let mut fields = ::std::vec::Vec::new();
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint32>(
+                    "id",
+                    HardStateExtension::get_id_for_reflect,
+                    HardStateExtension::mut_id_for_reflect,
+                ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeBytes>(
+                    "data",
+                    HardStateExtension::get_data_for_reflect,
+                    HardStateExtension::mut_data_for_reflect,
+                ));
+                ::protobuf::reflect::MessageDescriptor::new::<HardStateExtension>(
+                    "HardStateExtension",
+                    fields,
+                    file_descriptor_proto()
+                )
+            })
+        }
+    }
+}
+
+impl ::protobuf::Clear for HardStateExtension {
+    fn clear(&mut self) {
+        self.clear_id();
+        self.clear_data();
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for HardStateExtension {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for HardStateExtension {
+    fn as_ref(&self) -> ::protobuf::reflect::ProtobufValueRef {
+        ::protobuf::reflect::ProtobufValueRef::Message(self)
+    }
+}
+
+#[derive(PartialEq,Clone,Default)]
+pub struct Message {
+    // message fields
+    pub msg_type: MessageType,
+    pub to: u64,
+    pub from: u64,
+    pub term: u64,
+    pub log_term: u64,
+    pub index: u64,
+    pub entries: ::protobuf::RepeatedField<Entry>,
+    pub commit: u64,
+    pub snapshot: ::protobuf::SingularPtrField<Snapshot>,
+    pub reject: bool,
+    pub reject_hint: u64,
+    pub context: ::std::vec::Vec<u8>,
+    pub append_budget: u64,
+    pub priority: u64,
+    // special fields
+    unknown_fields: ::protobuf::UnknownFields,
+    cached_size: ::protobuf::CachedSize,
+}
+
+// see codegen.rs for the explanation why impl Sync explicitly
+unsafe impl ::std::marker::Sync for Message {}
+
+impl Message {
+    pub fn new() -> Message {
+        ::std::default::Default::default()
+    }
+
+    pub fn default_instance() -> &'static Message {
+        static mut instance: ::protobuf::lazy::Lazy<Message> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const Message,
+        };
+        unsafe {
+            instance.get(Message::new)
+        }
+    }
+
+    // .raftpb.MessageType msg_type = 1;
+
+    pub fn clear_msg_type(&mut self) {
+        self.msg_type = MessageType::MsgHup;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_msg_type(&mut self, v: MessageType) {
+        self.msg_type = v;
+    }
+
+    pub fn get_msg_type(&self) -> MessageType {
+        self.msg_type
+    }
+
+    fn get_msg_type_for_reflect(&self) -> &MessageType {
+        &self.msg_type
+    }
+
+    fn mut_msg_type_for_reflect(&mut self) -> &mut MessageType {
+        &mut self.msg_type
+    }
+
+    // uint64 to = 2;
+
+    pub fn clear_to(&mut self) {
+        self.to = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_to(&mut self, v: u64) {
+        self.to = v;
+    }
+
+    pub fn get_to(&self) -> u64 {
+        self.to
+    }
+
+    fn get_to_for_reflect(&self) -> &u64 {
+        &self.to
+    }
+
+    fn mut_to_for_reflect(&mut self) -> &mut u64 {
+        &mut self.to
+    }
+
+    // uint64 from = 3;
+
+    pub fn clear_from(&mut self) {
+        self.from = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_from(&mut self, v: u64) {
+        self.from = v;
+    }
+
+    pub fn get_from(&self) -> u64 {
+        self.from
+    }
+
+    fn get_from_for_reflect(&self) -> &u64 {
+        &self.from
+    }
+
+    fn mut_from_for_reflect(&mut self) -> &mut u64 {
+        &mut self.from
+    }
+
+    // uint64 term = 4;
+
+    pub fn clear_term(&mut self) {
         self.term = 0;
     }
 
@@ -1671,6 +2183,52 @@ impl Message {
     fn mut_context_for_reflect(&mut self) -> &mut ::std::vec::Vec<u8> {
         &mut self.context
     }
+
+    // uint64 append_budget = 13;
+
+    pub fn clear_append_budget(&mut self) {
+        self.append_budget = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_append_budget(&mut self, v: u64) {
+        self.append_budget = v;
+    }
+
+    pub fn get_append_budget(&self) -> u64 {
+        self.append_budget
+    }
+
+    fn get_append_budget_for_reflect(&self) -> &u64 {
+        &self.append_budget
+    }
+
+    fn mut_append_budget_for_reflect(&mut self) -> &mut u64 {
+        &mut self.append_budget
+    }
+
+    // uint64 priority = 14;
+
+    pub fn clear_priority(&mut self) {
+        self.priority = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_priority(&mut self, v: u64) {
+        self.priority = v;
+    }
+
+    pub fn get_priority(&self) -> u64 {
+        self.priority
+    }
+
+    fn get_priority_for_reflect(&self) -> &u64 {
+        &self.priority
+    }
+
+    fn mut_priority_for_reflect(&mut self) -> &mut u64 {
+        &mut self.priority
+    }
 }
 
 impl ::protobuf::Message for Message {
@@ -1760,6 +2318,20 @@ impl ::protobuf::Message for Message {
                 12 => {
                     ::protobuf::rt::read_singular_proto3_bytes_into(wire_type, is, &mut self.context)?;
                 },
+                13 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint64()?;
+                    self.append_budget = tmp;
+                },
+                14 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint64()?;
+                    self.priority = tmp;
+                },
                 _ => {
                     ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
                 },
@@ -1810,6 +2382,12 @@ impl ::protobuf::Message for Message {
         if !self.context.is_empty() {
             my_size += ::protobuf::rt::bytes_size(12, &self.context);
         }
+        if self.append_budget != 0 {
+            my_size += ::protobuf::rt::value_size(13, self.append_budget, ::protobuf::wire_format::WireTypeVarint);
+        }
+        if self.priority != 0 {
+            my_size += ::protobuf::rt::value_size(14, self.priority, ::protobuf::wire_format::WireTypeVarint);
+        }
         my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
         self.cached_size.set(my_size);
         my_size
@@ -1853,8 +2431,595 @@ impl ::protobuf::Message for Message {
         if self.reject_hint != 0 {
             os.write_uint64(11, self.reject_hint)?;
         }
-        if !self.context.is_empty() {
-            os.write_bytes(12, &self.context)?;
+        if !self.context.is_empty() {
+            os.write_bytes(12, &self.context)?;
+        }
+        if self.append_budget != 0 {
+            os.write_uint64(13, self.append_budget)?;
+        }
+        if self.priority != 0 {
+            os.write_uint64(14, self.priority)?;
+        }
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &::std::any::Any {
+        self as &::std::any::Any
+    }
+    fn as_any_mut(&mut self) -> &mut ::std::any::Any {
+        self as &mut ::std::any::Any
+    }
+    fn into_any(self: Box<Self>) -> ::std::boxed::Box<::std::any::Any> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        ::protobuf::MessageStatic::descriptor_static(None::<Self>)
+    }
+}
+
+impl ::protobuf::MessageStatic for Message {
+    fn new() -> Message {
+        Message::new()
+    }
+
+    fn descriptor_static(_: ::std::option::Option<Message>) -> &'static ::protobuf::reflect::MessageDescriptor {
+        static mut descriptor: ::protobuf::lazy::Lazy<::protobuf::reflect::MessageDescriptor> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const ::protobuf::reflect::MessageDescriptor,
+        };
+        unsafe {
+            descriptor.get(|| {
+                let mut fields = ::std::vec::Vec::new();
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeEnum<MessageType>>(
+                    "msg_type",
+                    Message::get_msg_type_for_reflect,
+                    Message::mut_msg_type_for_reflect,
+                ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
+                    "to",
+                    Message::get_to_for_reflect,
+                    Message::mut_to_for_reflect,
+                ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
+                    "from",
+                    Message::get_from_for_reflect,
+                    Message::mut_from_for_reflect,
+                ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
+                    "term",
+                    Message::get_term_for_reflect,
+                    Message::mut_term_for_reflect,
+                ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
+                    "log_term",
+                    Message::get_log_term_for_reflect,
+                    Message::mut_log_term_for_reflect,
+                ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
+                    "index",
+                    Message::get_index_for_reflect,
+                    Message::mut_index_for_reflect,
+                ));
+                fields.push(::protobuf::reflect::accessor::make_repeated_field_accessor::<_, ::protobuf::types::ProtobufTypeMessage<Entry>>(
+                    "entries",
+                    Message::get_entries_for_reflect,
+                    Message::mut_entries_for_reflect,
+                ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
+                    "commit",
+                    Message::get_commit_for_reflect,
+                    Message::mut_commit_for_reflect,
+                ));
+                fields.push(::protobuf::reflect::accessor::make_singular_ptr_field_accessor::<_, ::protobuf::types::ProtobufTypeMessage<Snapshot>>(
+                    "snapshot",
+                    Message::get_snapshot_for_reflect,
+                    Message::mut_snapshot_for_reflect,
+                ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeBool>(
+                    "reject",
+                    Message::get_reject_for_reflect,
+                    Message::mut_reject_for_reflect,
+                ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
+                    "reject_hint",
+                    Message::get_reject_hint_for_reflect,
+                    Message::mut_reject_hint_for_reflect,
+                ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeBytes>(
+                    "context",
+                    Message::get_context_for_reflect,
+                    Message::mut_context_for_reflect,
+                ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
+                    "append_budget",
+                    Message::get_append_budget_for_reflect,
+                    Message::mut_append_budget_for_reflect,
+                ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
+                    "priority",
+                    Message::get_priority_for_reflect,
+                    Message::mut_priority_for_reflect,
+                ));
+                ::protobuf::reflect::MessageDescriptor::new::<Message>(
+                    "Message",
+                    fields,
+                    file_descriptor_proto()
+                )
+            })
+        }
+    }
+}
+
+impl ::protobuf::Clear for Message {
+    fn clear(&mut self) {
+        self.clear_msg_type();
+        self.clear_to();
+        self.clear_from();
+        self.clear_term();
+        self.clear_log_term();
+        self.clear_index();
+        self.clear_entries();
+        self.clear_commit();
+        self.clear_snapshot();
+        self.clear_reject();
+        self.clear_reject_hint();
+        self.clear_context();
+        self.clear_append_budget();
+        self.clear_priority();
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for Message {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for Message {
+    fn as_ref(&self) -> ::protobuf::reflect::ProtobufValueRef {
+        ::protobuf::reflect::ProtobufValueRef::Message(self)
+    }
+}
+
+#[derive(PartialEq,Clone,Default)]
+pub struct ConfChange {
+    // message fields
+    pub id: u64,
+    pub change_type: ConfChangeType,
+    pub node_id: u64,
+    pub context: ::std::vec::Vec<u8>,
+    // special fields
+    unknown_fields: ::protobuf::UnknownFields,
+    cached_size: ::protobuf::CachedSize,
+}
+
+// see codegen.rs for the explanation why impl Sync explicitly
+unsafe impl ::std::marker::Sync for ConfChange {}
+
+impl ConfChange {
+    pub fn new() -> ConfChange {
+        ::std::default::Default::default()
+    }
+
+    pub fn default_instance() -> &'static ConfChange {
+        static mut instance: ::protobuf::lazy::Lazy<ConfChange> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const ConfChange,
+        };
+        unsafe {
+            instance.get(ConfChange::new)
+        }
+    }
+
+    // uint64 id = 1;
+
+    pub fn clear_id(&mut self) {
+        self.id = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_id(&mut self, v: u64) {
+        self.id = v;
+    }
+
+    pub fn get_id(&self) -> u64 {
+        self.id
+    }
+
+    fn get_id_for_reflect(&self) -> &u64 {
+        &self.id
+    }
+
+    fn mut_id_for_reflect(&mut self) -> &mut u64 {
+        &mut self.id
+    }
+
+    // .raftpb.ConfChangeType change_type = 2;
+
+    pub fn clear_change_type(&mut self) {
+        self.change_type = ConfChangeType::ConfChangeAddNode;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_change_type(&mut self, v: ConfChangeType) {
+        self.change_type = v;
+    }
+
+    pub fn get_change_type(&self) -> ConfChangeType {
+        self.change_type
+    }
+
+    fn get_change_type_for_reflect(&self) -> &ConfChangeType {
+        &self.change_type
+    }
+
+    fn mut_change_type_for_reflect(&mut self) -> &mut ConfChangeType {
+        &mut self.change_type
+    }
+
+    // uint64 node_id = 3;
+
+    pub fn clear_node_id(&mut self) {
+        self.node_id = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_node_id(&mut self, v: u64) {
+        self.node_id = v;
+    }
+
+    pub fn get_node_id(&self) -> u64 {
+        self.node_id
+    }
+
+    fn get_node_id_for_reflect(&self) -> &u64 {
+        &self.node_id
+    }
+
+    fn mut_node_id_for_reflect(&mut self) -> &mut u64 {
+        &mut self.node_id
+    }
+
+    // bytes context = 4;
+
+    pub fn clear_context(&mut self) {
+        self.context.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_context(&mut self, v: ::std::vec::Vec<u8>) {
+        self.context = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_context(&mut self) -> &mut ::std::vec::Vec<u8> {
+        &mut self.context
+    }
+
+    // Take field
+    pub fn take_context(&mut self) -> ::std::vec::Vec<u8> {
+        ::std::mem::replace(&mut self.context, ::std::vec::Vec::new())
+    }
+
+    pub fn get_context(&self) -> &[u8] {
+        &self.context
+    }
+
+    fn get_context_for_reflect(&self) -> &::std::vec::Vec<u8> {
+        &self.context
+    }
+
+    fn mut_context_for_reflect(&mut self) -> &mut ::std::vec::Vec<u8> {
+        &mut self.context
+    }
+}
+
+impl ::protobuf::Message for ConfChange {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint64()?;
+                    self.id = tmp;
+                },
+                2 => {
+                    ::protobuf::rt::read_proto3_enum_with_unknown_fields_into(wire_type, is, &mut self.change_type, 2, &mut self.unknown_fields)?
+                },
+                3 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint64()?;
+                    self.node_id = tmp;
+                },
+                4 => {
+                    ::protobuf::rt::read_singular_proto3_bytes_into(wire_type, is, &mut self.context)?;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if self.id != 0 {
+            my_size += ::protobuf::rt::value_size(1, self.id, ::protobuf::wire_format::WireTypeVarint);
+        }
+        if self.change_type != ConfChangeType::ConfChangeAddNode {
+            my_size += ::protobuf::rt::enum_size(2, self.change_type);
+        }
+        if self.node_id != 0 {
+            my_size += ::protobuf::rt::value_size(3, self.node_id, ::protobuf::wire_format::WireTypeVarint);
+        }
+        if !self.context.is_empty() {
+            my_size += ::protobuf::rt::bytes_size(4, &self.context);
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream) -> ::protobuf::ProtobufResult<()> {
+        if self.id != 0 {
+            os.write_uint64(1, self.id)?;
+        }
+        if self.change_type != ConfChangeType::ConfChangeAddNode {
+            os.write_enum(2, self.change_type.value())?;
+        }
+        if self.node_id != 0 {
+            os.write_uint64(3, self.node_id)?;
+        }
+        if !self.context.is_empty() {
+            os.write_bytes(4, &self.context)?;
+        }
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &::std::any::Any {
+        self as &::std::any::Any
+    }
+    fn as_any_mut(&mut self) -> &mut ::std::any::Any {
+        self as &mut ::std::any::Any
+    }
+    fn into_any(self: Box<Self>) -> ::std::boxed::Box<::std::any::Any> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        ::protobuf::MessageStatic::descriptor_static(None::<Self>)
+    }
+}
+
+impl ::protobuf::MessageStatic for ConfChange {
+    fn new() -> ConfChange {
+        ConfChange::new()
+    }
+
+    fn descriptor_static(_: ::std::option::Option<ConfChange>) -> &'static ::protobuf::reflect::MessageDescriptor {
+        static mut descriptor: ::protobuf::lazy::Lazy<::protobuf::reflect::MessageDescriptor> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const ::protobuf::reflect::MessageDescriptor,
+        };
+        unsafe {
+            descriptor.get(|| {
+                let mut fields = ::std::vec::Vec::new();
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
+                    "id",
+                    ConfChange::get_id_for_reflect,
+                    ConfChange::mut_id_for_reflect,
+                ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeEnum<ConfChangeType>>(
+                    "change_type",
+                    ConfChange::get_change_type_for_reflect,
+                    ConfChange::mut_change_type_for_reflect,
+                ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
+                    "node_id",
+                    ConfChange::get_node_id_for_reflect,
+                    ConfChange::mut_node_id_for_reflect,
+                ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeBytes>(
+                    "context",
+                    ConfChange::get_context_for_reflect,
+                    ConfChange::mut_context_for_reflect,
+                ));
+                ::protobuf::reflect::MessageDescriptor::new::<ConfChange>(
+                    "ConfChange",
+                    fields,
+                    file_descriptor_proto()
+                )
+            })
+        }
+    }
+}
+
+impl ::protobuf::Clear for ConfChange {
+    fn clear(&mut self) {
+        self.clear_id();
+        self.clear_change_type();
+        self.clear_node_id();
+        self.clear_context();
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for ConfChange {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for ConfChange {
+    fn as_ref(&self) -> ::protobuf::reflect::ProtobufValueRef {
+        ::protobuf::reflect::ProtobufValueRef::Message(self)
+    }
+}
+
+#[derive(PartialEq,Clone,Default)]
+pub struct ConfChangeSingle {
+    // message fields
+    pub change_type: ConfChangeType,
+    pub node_id: u64,
+    // special fields
+    unknown_fields: ::protobuf::UnknownFields,
+    cached_size: ::protobuf::CachedSize,
+}
+
+// see codegen.rs for the explanation why impl Sync explicitly
+unsafe impl ::std::marker::Sync for ConfChangeSingle {}
+
+impl ConfChangeSingle {
+    pub fn new() -> ConfChangeSingle {
+        ::std::default::Default::default()
+    }
+
+    pub fn default_instance() -> &'static ConfChangeSingle {
+        static mut instance: ::protobuf::lazy::Lazy<ConfChangeSingle> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const ConfChangeSingle,
+        };
+        unsafe {
+            instance.get(ConfChangeSingle::new)
+        }
+    }
+
+    // .raftpb.ConfChangeType change_type = 1;
+
+    pub fn clear_change_type(&mut self) {
+        self.change_type = ConfChangeType::ConfChangeAddNode;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_change_type(&mut self, v: ConfChangeType) {
+        self.change_type = v;
+    }
+
+    pub fn get_change_type(&self) -> ConfChangeType {
+        self.change_type
+    }
+
+    fn get_change_type_for_reflect(&self) -> &ConfChangeType {
+        &self.change_type
+    }
+
+    fn mut_change_type_for_reflect(&mut self) -> &mut ConfChangeType {
+        &mut self.change_type
+    }
+
+    // uint64 node_id = 2;
+
+    pub fn clear_node_id(&mut self) {
+        self.node_id = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_node_id(&mut self, v: u64) {
+        self.node_id = v;
+    }
+
+    pub fn get_node_id(&self) -> u64 {
+        self.node_id
+    }
+
+    fn get_node_id_for_reflect(&self) -> &u64 {
+        &self.node_id
+    }
+
+    fn mut_node_id_for_reflect(&mut self) -> &mut u64 {
+        &mut self.node_id
+    }
+}
+
+impl ::protobuf::Message for ConfChangeSingle {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    ::protobuf::rt::read_proto3_enum_with_unknown_fields_into(wire_type, is, &mut self.change_type, 1, &mut self.unknown_fields)?
+                },
+                2 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint64()?;
+                    self.node_id = tmp;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if self.change_type != ConfChangeType::ConfChangeAddNode {
+            my_size += ::protobuf::rt::enum_size(1, self.change_type);
+        }
+        if self.node_id != 0 {
+            my_size += ::protobuf::rt::value_size(2, self.node_id, ::protobuf::wire_format::WireTypeVarint);
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream) -> ::protobuf::ProtobufResult<()> {
+        if self.change_type != ConfChangeType::ConfChangeAddNode {
+            os.write_enum(1, self.change_type.value())?;
+        }
+        if self.node_id != 0 {
+            os.write_uint64(2, self.node_id)?;
         }
         os.write_unknown_fields(self.get_unknown_fields())?;
         ::std::result::Result::Ok(())
@@ -1887,12 +3052,12 @@ impl ::protobuf::Message for Message {
     }
 }
 
-impl ::protobuf::MessageStatic for Message {
-    fn new() -> Message {
-        Message::new()
+impl ::protobuf::MessageStatic for ConfChangeSingle {
+    fn new() -> ConfChangeSingle {
+        ConfChangeSingle::new()
     }
 
-    fn descriptor_static(_: ::std::option::Option<Message>) -> &'static ::protobuf::reflect::MessageDescriptor {
+    fn descriptor_static(_: ::std::option::Option<ConfChangeSingle>) -> &'static ::protobuf::reflect::MessageDescriptor {
         static mut descriptor: ::protobuf::lazy::Lazy<::protobuf::reflect::MessageDescriptor> = ::protobuf::lazy::Lazy {
             lock: ::protobuf::lazy::ONCE_INIT,
             ptr: 0 as *const ::protobuf::reflect::MessageDescriptor,
@@ -1900,68 +3065,18 @@ impl ::protobuf::MessageStatic for Message {
         unsafe {
             descriptor.get(|| {
                 let mut fields = ::std::vec::Vec::new();
-                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeEnum<MessageType>>(
-                    "msg_type",
-                    Message::get_msg_type_for_reflect,
-                    Message::mut_msg_type_for_reflect,
-                ));
-                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
-                    "to",
-                    Message::get_to_for_reflect,
-                    Message::mut_to_for_reflect,
-                ));
-                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
-                    "from",
-                    Message::get_from_for_reflect,
-                    Message::mut_from_for_reflect,
-                ));
-                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
-                    "term",
-                    Message::get_term_for_reflect,
-                    Message::mut_term_for_reflect,
-                ));
-                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
-                    "log_term",
-                    Message::get_log_term_for_reflect,
-                    Message::mut_log_term_for_reflect,
-                ));
-                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
-                    "index",
-                    Message::get_index_for_reflect,
-                    Message::mut_index_for_reflect,
-                ));
-                fields.push(::protobuf::reflect::accessor::make_repeated_field_accessor::<_, ::protobuf::types::ProtobufTypeMessage<Entry>>(
-                    "entries",
-                    Message::get_entries_for_reflect,
-                    Message::mut_entries_for_reflect,
-                ));
-                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
-                    "commit",
-                    Message::get_commit_for_reflect,
-                    Message::mut_commit_for_reflect,
-                ));
-                fields.push(::protobuf::reflect::accessor::make_singular_ptr_field_accessor::<_, ::protobuf::types::ProtobufTypeMessage<Snapshot>>(
-                    "snapshot",
-                    Message::get_snapshot_for_reflect,
-                    Message::mut_snapshot_for_reflect,
-                ));
-                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeBool>(
-                    "reject",
-                    Message::get_reject_for_reflect,
-                    Message::mut_reject_for_reflect,
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeEnum<ConfChangeType>>(
+                    "change_type",
+                    ConfChangeSingle::get_change_type_for_reflect,
+                    ConfChangeSingle::mut_change_type_for_reflect,
                 ));
                 fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
-                    "reject_hint",
-                    Message::get_reject_hint_for_reflect,
-                    Message::mut_reject_hint_for_reflect,
-                ));
-                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeBytes>(
-                    "context",
-                    Message::get_context_for_reflect,
-                    Message::mut_context_for_reflect,
+                    "node_id",
+                    ConfChangeSingle::get_node_id_for_reflect,
+                    ConfChangeSingle::mut_node_id_for_reflect,
                 ));
-                ::protobuf::reflect::MessageDescriptor::new::<Message>(
-                    "Message",
+                ::protobuf::reflect::MessageDescriptor::new::<ConfChangeSingle>(
+                    "ConfChangeSingle",
                     fields,
                     file_descriptor_proto()
                 )
@@ -1970,42 +3085,31 @@ impl ::protobuf::MessageStatic for Message {
     }
 }
 
-impl ::protobuf::Clear for Message {
+impl ::protobuf::Clear for ConfChangeSingle {
     fn clear(&mut self) {
-        self.clear_msg_type();
-        self.clear_to();
-        self.clear_from();
-        self.clear_term();
-        self.clear_log_term();
-        self.clear_index();
-        self.clear_entries();
-        self.clear_commit();
-        self.clear_snapshot();
-        self.clear_reject();
-        self.clear_reject_hint();
-        self.clear_context();
+        self.clear_change_type();
+        self.clear_node_id();
         self.unknown_fields.clear();
     }
 }
 
-impl ::std::fmt::Debug for Message {
+impl ::std::fmt::Debug for ConfChangeSingle {
     fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
         ::protobuf::text_format::fmt(self, f)
     }
 }
 
-impl ::protobuf::reflect::ProtobufValue for Message {
+impl ::protobuf::reflect::ProtobufValue for ConfChangeSingle {
     fn as_ref(&self) -> ::protobuf::reflect::ProtobufValueRef {
         ::protobuf::reflect::ProtobufValueRef::Message(self)
     }
 }
 
 #[derive(PartialEq,Clone,Default)]
-pub struct ConfChange {
+pub struct ConfChangeV2 {
     // message fields
-    pub id: u64,
-    pub change_type: ConfChangeType,
-    pub node_id: u64,
+    pub transition: ConfChangeTransition,
+    pub changes: ::protobuf::RepeatedField<ConfChangeSingle>,
     pub context: ::std::vec::Vec<u8>,
     // special fields
     unknown_fields: ::protobuf::UnknownFields,
@@ -2013,93 +3117,80 @@ pub struct ConfChange {
 }
 
 // see codegen.rs for the explanation why impl Sync explicitly
-unsafe impl ::std::marker::Sync for ConfChange {}
+unsafe impl ::std::marker::Sync for ConfChangeV2 {}
 
-impl ConfChange {
-    pub fn new() -> ConfChange {
+impl ConfChangeV2 {
+    pub fn new() -> ConfChangeV2 {
         ::std::default::Default::default()
     }
 
-    pub fn default_instance() -> &'static ConfChange {
-        static mut instance: ::protobuf::lazy::Lazy<ConfChange> = ::protobuf::lazy::Lazy {
+    pub fn default_instance() -> &'static ConfChangeV2 {
+        static mut instance: ::protobuf::lazy::Lazy<ConfChangeV2> = ::protobuf::lazy::Lazy {
             lock: ::protobuf::lazy::ONCE_INIT,
-            ptr: 0 as *const ConfChange,
+            ptr: 0 as *const ConfChangeV2,
         };
         unsafe {
-            instance.get(ConfChange::new)
+            instance.get(ConfChangeV2::new)
         }
     }
 
-    // uint64 id = 1;
+    // .raftpb.ConfChangeTransition transition = 1;
 
-    pub fn clear_id(&mut self) {
-        self.id = 0;
+    pub fn clear_transition(&mut self) {
+        self.transition = ConfChangeTransition::ConfChangeTransitionAuto;
     }
 
     // Param is passed by value, moved
-    pub fn set_id(&mut self, v: u64) {
-        self.id = v;
+    pub fn set_transition(&mut self, v: ConfChangeTransition) {
+        self.transition = v;
     }
 
-    pub fn get_id(&self) -> u64 {
-        self.id
+    pub fn get_transition(&self) -> ConfChangeTransition {
+        self.transition
     }
 
-    fn get_id_for_reflect(&self) -> &u64 {
-        &self.id
+    fn get_transition_for_reflect(&self) -> &ConfChangeTransition {
+        &self.transition
     }
 
-    fn mut_id_for_reflect(&mut self) -> &mut u64 {
-        &mut self.id
+    fn mut_transition_for_reflect(&mut self) -> &mut ConfChangeTransition {
+        &mut self.transition
     }
 
-    // .raftpb.ConfChangeType change_type = 2;
+    // repeated .raftpb.ConfChangeSingle changes = 2;
 
-    pub fn clear_change_type(&mut self) {
-        self.change_type = ConfChangeType::ConfChangeAddNode;
+    pub fn clear_changes(&mut self) {
+        self.changes.clear();
     }
 
     // Param is passed by value, moved
-    pub fn set_change_type(&mut self, v: ConfChangeType) {
-        self.change_type = v;
-    }
-
-    pub fn get_change_type(&self) -> ConfChangeType {
-        self.change_type
-    }
-
-    fn get_change_type_for_reflect(&self) -> &ConfChangeType {
-        &self.change_type
-    }
-
-    fn mut_change_type_for_reflect(&mut self) -> &mut ConfChangeType {
-        &mut self.change_type
+    pub fn set_changes(&mut self, v: ::protobuf::RepeatedField<ConfChangeSingle>) {
+        self.changes = v;
     }
 
-    // uint64 node_id = 3;
-
-    pub fn clear_node_id(&mut self) {
-        self.node_id = 0;
+    // Mutable pointer to the field.
+    pub fn mut_changes(&mut self) -> &mut ::protobuf::RepeatedField<ConfChangeSingle> {
+        &mut self.changes
     }
 
-    // Param is passed by value, moved
-    pub fn set_node_id(&mut self, v: u64) {
-        self.node_id = v;
+    // Take field
+    pub fn take_changes(&mut self) -> ::protobuf::RepeatedField<ConfChangeSingle> {
+        ::std::mem::replace(&mut self.changes, ::protobuf::RepeatedField::new())
     }
 
-    pub fn get_node_id(&self) -> u64 {
-        self.node_id
+    pub fn get_changes(&self) -> &[ConfChangeSingle] {
+        &self.changes
     }
 
-    fn get_node_id_for_reflect(&self) -> &u64 {
-        &self.node_id
+    fn get_changes_for_reflect(&self) -> &::protobuf::RepeatedField<ConfChangeSingle> {
+        &self.changes
     }
 
-    fn mut_node_id_for_reflect(&mut self) -> &mut u64 {
-        &mut self.node_id
+    fn mut_changes_for_reflect(&mut self) -> &mut ::protobuf::RepeatedField<ConfChangeSingle> {
+        &mut self.changes
     }
 
-    // bytes context = 4;
+    // bytes context = 3;
 
     pub fn clear_context(&mut self) {
         self.context.clear();
@@ -2134,8 +3225,13 @@ impl ConfChange {
     }
 }
 
-impl ::protobuf::Message for ConfChange {
+impl ::protobuf::Message for ConfChangeV2 {
     fn is_initialized(&self) -> bool {
+        for v in &self.changes {
+            if !v.is_initialized() {
+                return false;
+            }
+        };
         true
     }
 
@@ -2144,23 +3240,12 @@ impl ::protobuf::Message for ConfChange {
             let (field_number, wire_type) = is.read_tag_unpack()?;
             match field_number {
                 1 => {
-                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
-                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
-                    }
-                    let tmp = is.read_uint64()?;
-                    self.id = tmp;
+                    ::protobuf::rt::read_proto3_enum_with_unknown_fields_into(wire_type, is, &mut self.transition, 1, &mut self.unknown_fields)?
                 },
                 2 => {
-                    ::protobuf::rt::read_proto3_enum_with_unknown_fields_into(wire_type, is, &mut self.change_type, 2, &mut self.unknown_fields)?
+                    ::protobuf::rt::read_repeated_message_into(wire_type, is, &mut self.changes)?;
                 },
                 3 => {
-                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
-                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
-                    }
-                    let tmp = is.read_uint64()?;
-                    self.node_id = tmp;
-                },
-                4 => {
                     ::protobuf::rt::read_singular_proto3_bytes_into(wire_type, is, &mut self.context)?;
                 },
                 _ => {
@@ -2175,17 +3260,15 @@ impl ::protobuf::Message for ConfChange {
     #[allow(unused_variables)]
     fn compute_size(&self) -> u32 {
         let mut my_size = 0;
-        if self.id != 0 {
-            my_size += ::protobuf::rt::value_size(1, self.id, ::protobuf::wire_format::WireTypeVarint);
-        }
-        if self.change_type != ConfChangeType::ConfChangeAddNode {
-            my_size += ::protobuf::rt::enum_size(2, self.change_type);
-        }
-        if self.node_id != 0 {
-            my_size += ::protobuf::rt::value_size(3, self.node_id, ::protobuf::wire_format::WireTypeVarint);
+        if self.transition != ConfChangeTransition::ConfChangeTransitionAuto {
+            my_size += ::protobuf::rt::enum_size(1, self.transition);
         }
+        for value in &self.changes {
+            let len = value.compute_size();
+            my_size += 1 + ::protobuf::rt::compute_raw_varint32_size(len) + len;
+        };
         if !self.context.is_empty() {
-            my_size += ::protobuf::rt::bytes_size(4, &self.context);
+            my_size += ::protobuf::rt::bytes_size(3, &self.context);
         }
         my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
         self.cached_size.set(my_size);
@@ -2193,17 +3276,16 @@ impl ::protobuf::Message for ConfChange {
     }
 
     fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream) -> ::protobuf::ProtobufResult<()> {
-        if self.id != 0 {
-            os.write_uint64(1, self.id)?;
-        }
-        if self.change_type != ConfChangeType::ConfChangeAddNode {
-            os.write_enum(2, self.change_type.value())?;
-        }
-        if self.node_id != 0 {
-            os.write_uint64(3, self.node_id)?;
+        if self.transition != ConfChangeTransition::ConfChangeTransitionAuto {
+            os.write_enum(1, self.transition.value())?;
         }
+        for v in &self.changes {
+            os.write_tag(2, ::protobuf::wire_format::WireTypeLengthDelimited)?;
+            os.write_raw_varint32(v.get_cached_size())?;
+            v.write_to_with_cached_sizes(os)?;
+        };
         if !self.context.is_empty() {
-            os.write_bytes(4, &self.context)?;
+            os.write_bytes(3, &self.context)?;
         }
         os.write_unknown_fields(self.get_unknown_fields())?;
         ::std::result::Result::Ok(())
@@ -2236,12 +3318,12 @@ impl ::protobuf::Message for ConfChange {
     }
 }
 
-impl ::protobuf::MessageStatic for ConfChange {
-    fn new() -> ConfChange {
-        ConfChange::new()
+impl ::protobuf::MessageStatic for ConfChangeV2 {
+    fn new() -> ConfChangeV2 {
+        ConfChangeV2::new()
     }
 
-    fn descriptor_static(_: ::std::option::Option<ConfChange>) -> &'static ::protobuf::reflect::MessageDescriptor {
+    fn descriptor_static(_: ::std::option::Option<ConfChangeV2>) -> &'static ::protobuf::reflect::MessageDescriptor {
         static mut descriptor: ::protobuf::lazy::Lazy<::protobuf::reflect::MessageDescriptor> = ::protobuf::lazy::Lazy {
             lock: ::protobuf::lazy::ONCE_INIT,
             ptr: 0 as *const ::protobuf::reflect::MessageDescriptor,
@@ -2249,28 +3331,23 @@ impl ::protobuf::MessageStatic for ConfChange {
         unsafe {
             descriptor.get(|| {
                 let mut fields = ::std::vec::Vec::new();
-                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
-                    "id",
-                    ConfChange::get_id_for_reflect,
-                    ConfChange::mut_id_for_reflect,
-                ));
-                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeEnum<ConfChangeType>>(
-                    "change_type",
-                    ConfChange::get_change_type_for_reflect,
-                    ConfChange::mut_change_type_for_reflect,
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeEnum<ConfChangeTransition>>(
+                    "transition",
+                    ConfChangeV2::get_transition_for_reflect,
+                    ConfChangeV2::mut_transition_for_reflect,
                 ));
-                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
-                    "node_id",
-                    ConfChange::get_node_id_for_reflect,
-                    ConfChange::mut_node_id_for_reflect,
+                fields.push(::protobuf::reflect::accessor::make_repeated_field_accessor::<_, ::protobuf::types::ProtobufTypeMessage<ConfChangeSingle>>(
+                    "changes",
+                    ConfChangeV2::get_changes_for_reflect,
+                    ConfChangeV2::mut_changes_for_reflect,
                 ));
                 fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeBytes>(
                     "context",
-                    ConfChange::get_context_for_reflect,
-                    ConfChange::mut_context_for_reflect,
+                    ConfChangeV2::get_context_for_reflect,
+                    ConfChangeV2::mut_context_for_reflect,
                 ));
-                ::protobuf::reflect::MessageDescriptor::new::<ConfChange>(
-                    "ConfChange",
+                ::protobuf::reflect::MessageDescriptor::new::<ConfChangeV2>(
+                    "ConfChangeV2",
                     fields,
                     file_descriptor_proto()
                 )
@@ -2279,23 +3356,22 @@ impl ::protobuf::MessageStatic for ConfChange {
     }
 }
 
-impl ::protobuf::Clear for ConfChange {
+impl ::protobuf::Clear for ConfChangeV2 {
     fn clear(&mut self) {
-        self.clear_id();
-        self.clear_change_type();
-        self.clear_node_id();
+        self.clear_transition();
+        self.clear_changes();
         self.clear_context();
         self.unknown_fields.clear();
     }
 }
 
-impl ::std::fmt::Debug for ConfChange {
+impl ::std::fmt::Debug for ConfChangeV2 {
     fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
         ::protobuf::text_format::fmt(self, f)
     }
 }
 
-impl ::protobuf::reflect::ProtobufValue for ConfChange {
+impl ::protobuf::reflect::ProtobufValue for ConfChangeV2 {
     fn as_ref(&self) -> ::protobuf::reflect::ProtobufValueRef {
         ::protobuf::reflect::ProtobufValueRef::Message(self)
     }
@@ -2305,6 +3381,7 @@ impl ::protobuf::reflect::ProtobufValue for ConfChange {
 pub enum EntryType {
     EntryNormal = 0,
     EntryConfChange = 1,
+    EntryConfChangeV2 = 2,
 }
 
 impl ::protobuf::ProtobufEnum for EntryType {
@@ -2316,6 +3393,7 @@ impl ::protobuf::ProtobufEnum for EntryType {
         match value {
             0 => ::std::option::Option::Some(EntryType::EntryNormal),
             1 => ::std::option::Option::Some(EntryType::EntryConfChange),
+            2 => ::std::option::Option::Some(EntryType::EntryConfChangeV2),
             _ => ::std::option::Option::None
         }
     }
@@ -2324,6 +3402,7 @@ impl ::protobuf::ProtobufEnum for EntryType {
         static values: &'static [EntryType] = &[
             EntryType::EntryNormal,
             EntryType::EntryConfChange,
+            EntryType::EntryConfChangeV2,
         ];
         values
     }
@@ -2377,6 +3456,7 @@ pub enum MessageType {
     MsgReadIndexResp = 16,
     MsgPreVote = 17,
     MsgPreVoteResp = 18,
+    MsgForgetLeader = 19,
 }
 
 impl ::protobuf::ProtobufEnum for MessageType {
@@ -2405,6 +3485,7 @@ impl ::protobuf::ProtobufEnum for MessageType {
             16 => ::std::option::Option::Some(MessageType::MsgReadIndexResp),
             17 => ::std::option::Option::Some(MessageType::MsgPreVote),
             18 => ::std::option::Option::Some(MessageType::MsgPreVoteResp),
+            19 => ::std::option::Option::Some(MessageType::MsgForgetLeader),
             _ => ::std::option::Option::None
         }
     }
@@ -2430,6 +3511,7 @@ impl ::protobuf::ProtobufEnum for MessageType {
             MessageType::MsgReadIndexResp,
             MessageType::MsgPreVote,
             MessageType::MsgPreVoteResp,
+            MessageType::MsgForgetLeader,
         ];
         values
     }
@@ -2523,215 +3605,353 @@ impl ::protobuf::reflect::ProtobufValue for ConfChangeType {
     }
 }
 
+#[derive(Clone,PartialEq,Eq,Debug,Hash)]
+pub enum ConfChangeTransition {
+    ConfChangeTransitionAuto = 0,
+    ConfChangeTransitionJointImplicit = 1,
+    ConfChangeTransitionJointExplicit = 2,
+}
+
+impl ::protobuf::ProtobufEnum for ConfChangeTransition {
+    fn value(&self) -> i32 {
+        *self as i32
+    }
+
+    fn from_i32(value: i32) -> ::std::option::Option<ConfChangeTransition> {
+        match value {
+            0 => ::std::option::Option::Some(ConfChangeTransition::ConfChangeTransitionAuto),
+            1 => ::std::option::Option::Some(ConfChangeTransition::ConfChangeTransitionJointImplicit),
+            2 => ::std::option::Option::Some(ConfChangeTransition::ConfChangeTransitionJointExplicit),
+            _ => ::std::option::Option::None
+        }
+    }
+
+    fn values() -> &'static [Self] {
+        static values: &'static [ConfChangeTransition] = &[
+            ConfChangeTransition::ConfChangeTransitionAuto,
+            ConfChangeTransition::ConfChangeTransitionJointImplicit,
+            ConfChangeTransition::ConfChangeTransitionJointExplicit,
+        ];
+        values
+    }
+
+    fn enum_descriptor_static(_: ::std::option::Option<ConfChangeTransition>) -> &'static ::protobuf::reflect::EnumDescriptor {
+        static mut descriptor: ::protobuf::lazy::Lazy<::protobuf::reflect::EnumDescriptor> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const ::protobuf::reflect::EnumDescriptor,
+        };
+        unsafe {
+            descriptor.get(|| {
+                ::protobuf::reflect::EnumDescriptor::new("ConfChangeTransition", file_descriptor_proto())
+            })
+        }
+    }
+}
+
+impl ::std::marker::Copy for ConfChangeTransition {
+}
+
+impl ::std::default::Default for ConfChangeTransition {
+    fn default() -> Self {
+        ConfChangeTransition::ConfChangeTransitionAuto
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for ConfChangeTransition {
+    fn as_ref(&self) -> ::protobuf::reflect::ProtobufValueRef {
+        ::protobuf::reflect::ProtobufValueRef::Enum(self.descriptor())
+    }
+}
+
 static file_descriptor_proto_data: &'static [u8] = b"\
-    \n\x13raftpb/raftpb.proto\x12\x06raftpb\"w\n\x05Entry\x12\x12\n\x04term\
-    \x18\x01\x20\x01(\x04R\x04term\x12\x14\n\x05index\x18\x02\x20\x01(\x04R\
-    \x05index\x120\n\nentry_type\x18\x03\x20\x01(\x0e2\x11.raftpb.EntryTypeR\
-    \tentryType\x12\x12\n\x04data\x18\x04\x20\x01(\x0cR\x04data\"n\n\x10Snap\
-    shotMetadata\x120\n\nconf_state\x18\x01\x20\x01(\x0b2\x11.raftpb.ConfSta\
-    teR\tconfState\x12\x14\n\x05index\x18\x02\x20\x01(\x04R\x05index\x12\x12\
-    \n\x04term\x18\x03\x20\x01(\x04R\x04term\"T\n\x08Snapshot\x12\x12\n\x04d\
-    ata\x18\x01\x20\x01(\x0cR\x04data\x124\n\x08metadata\x18\x02\x20\x01(\
-    \x0b2\x18.raftpb.SnapshotMetadataR\x08metadata\"=\n\tConfState\x12\x14\n\
-    \x05nodes\x18\x01\x20\x03(\x04R\x05nodes\x12\x1a\n\x08learners\x18\x02\
-    \x20\x03(\x04R\x08learners\"K\n\tHardState\x12\x12\n\x04term\x18\x01\x20\
-    \x01(\x04R\x04term\x12\x12\n\x04vote\x18\x02\x20\x01(\x04R\x04vote\x12\
-    \x16\n\x06commit\x18\x03\x20\x01(\x04R\x06commit\"\xe4\x02\n\x07Message\
-    \x12.\n\x08msg_type\x18\x01\x20\x01(\x0e2\x13.raftpb.MessageTypeR\x07msg\
-    Type\x12\x0e\n\x02to\x18\x02\x20\x01(\x04R\x02to\x12\x12\n\x04from\x18\
-    \x03\x20\x01(\x04R\x04from\x12\x12\n\x04term\x18\x04\x20\x01(\x04R\x04te\
-    rm\x12\x19\n\x08log_term\x18\x05\x20\x01(\x04R\x07logTerm\x12\x14\n\x05i\
-    ndex\x18\x06\x20\x01(\x04R\x05index\x12'\n\x07entries\x18\x07\x20\x03(\
-    \x0b2\r.raftpb.EntryR\x07entries\x12\x16\n\x06commit\x18\x08\x20\x01(\
-    \x04R\x06commit\x12,\n\x08snapshot\x18\t\x20\x01(\x0b2\x10.raftpb.Snapsh\
-    otR\x08snapshot\x12\x16\n\x06reject\x18\n\x20\x01(\x08R\x06reject\x12\
-    \x1f\n\x0breject_hint\x18\x0b\x20\x01(\x04R\nrejectHint\x12\x18\n\x07con\
-    text\x18\x0c\x20\x01(\x0cR\x07context\"\x88\x01\n\nConfChange\x12\x0e\n\
-    \x02id\x18\x01\x20\x01(\x04R\x02id\x127\n\x0bchange_type\x18\x02\x20\x01\
-    (\x0e2\x16.raftpb.ConfChangeTypeR\nchangeType\x12\x17\n\x07node_id\x18\
-    \x03\x20\x01(\x04R\x06nodeId\x12\x18\n\x07context\x18\x04\x20\x01(\x0cR\
-    \x07context*1\n\tEntryType\x12\x0f\n\x0bEntryNormal\x10\0\x12\x13\n\x0fE\
-    ntryConfChange\x10\x01*\xd3\x02\n\x0bMessageType\x12\n\n\x06MsgHup\x10\0\
-    \x12\x0b\n\x07MsgBeat\x10\x01\x12\x0b\n\x07MsgProp\x10\x02\x12\n\n\x06Ms\
-    gApp\x10\x03\x12\x0e\n\nMsgAppResp\x10\x04\x12\x0b\n\x07MsgVote\x10\x05\
-    \x12\x0f\n\x0bMsgVoteResp\x10\x06\x12\x0b\n\x07MsgSnap\x10\x07\x12\x10\n\
-    \x0cMsgHeartbeat\x10\x08\x12\x14\n\x10MsgHeartbeatResp\x10\t\x12\x12\n\
-    \x0eMsgUnreachable\x10\n\x12\x11\n\rMsgSnapStatus\x10\x0b\x12\x12\n\x0eM\
-    sgCheckQuorum\x10\x0c\x12\x15\n\x11MsgTransferLeader\x10\r\x12\x11\n\rMs\
-    gTimeoutNow\x10\x0e\x12\x10\n\x0cMsgReadIndex\x10\x0f\x12\x14\n\x10MsgRe\
-    adIndexResp\x10\x10\x12\x0e\n\nMsgPreVote\x10\x11\x12\x12\n\x0eMsgPreVot\
-    eResp\x10\x12*y\n\x0eConfChangeType\x12\x15\n\x11ConfChangeAddNode\x10\0\
-    \x12\x18\n\x14ConfChangeRemoveNode\x10\x01\x12\x18\n\x14ConfChangeUpdate\
-    Node\x10\x02\x12\x1c\n\x18ConfChangeAddLearnerNode\x10\x03J\xf3\x1c\n\
-    \x06\x12\x04\0\0V\x01\n\x08\n\x01\x0c\x12\x03\0\0\x12\n\x08\n\x01\x02\
-    \x12\x03\x01\x08\x0e\n\n\n\x02\x05\0\x12\x04\x03\0\x06\x01\n\n\n\x03\x05\
-    \0\x01\x12\x03\x03\x05\x0e\n\x0b\n\x04\x05\0\x02\0\x12\x03\x04\x04\x18\n\
-    \x0c\n\x05\x05\0\x02\0\x01\x12\x03\x04\x04\x0f\n\x0c\n\x05\x05\0\x02\0\
-    \x02\x12\x03\x04\x16\x17\n\x0b\n\x04\x05\0\x02\x01\x12\x03\x05\x04\x18\n\
-    \x0c\n\x05\x05\0\x02\x01\x01\x12\x03\x05\x04\x13\n\x0c\n\x05\x05\0\x02\
-    \x01\x02\x12\x03\x05\x16\x17\n\n\n\x02\x04\0\x12\x04\x08\0\r\x01\n\n\n\
-    \x03\x04\0\x01\x12\x03\x08\x08\r\n;\n\x04\x04\0\x02\0\x12\x03\t\x08#\".\
-    \x20must\x20be\x2064-bit\x20aligned\x20for\x20atomic\x20operations\n\n\r\
-    \n\x05\x04\0\x02\0\x04\x12\x04\t\x08\x08\x0f\n\x0c\n\x05\x04\0\x02\0\x05\
-    \x12\x03\t\x08\x0e\n\x0c\n\x05\x04\0\x02\0\x01\x12\x03\t\x13\x17\n\x0c\n\
-    \x05\x04\0\x02\0\x03\x12\x03\t!\"\n;\n\x04\x04\0\x02\x01\x12\x03\n\x08#\
-    \".\x20must\x20be\x2064-bit\x20aligned\x20for\x20atomic\x20operations\n\
-    \n\r\n\x05\x04\0\x02\x01\x04\x12\x04\n\x08\t#\n\x0c\n\x05\x04\0\x02\x01\
-    \x05\x12\x03\n\x08\x0e\n\x0c\n\x05\x04\0\x02\x01\x01\x12\x03\n\x13\x18\n\
-    \x0c\n\x05\x04\0\x02\x01\x03\x12\x03\n!\"\n\x0b\n\x04\x04\0\x02\x02\x12\
-    \x03\x0b\x08#\n\r\n\x05\x04\0\x02\x02\x04\x12\x04\x0b\x08\n#\n\x0c\n\x05\
-    \x04\0\x02\x02\x06\x12\x03\x0b\x08\x11\n\x0c\n\x05\x04\0\x02\x02\x01\x12\
-    \x03\x0b\x13\x1d\n\x0c\n\x05\x04\0\x02\x02\x03\x12\x03\x0b!\"\n\x0b\n\
-    \x04\x04\0\x02\x03\x12\x03\x0c\x08#\n\r\n\x05\x04\0\x02\x03\x04\x12\x04\
-    \x0c\x08\x0b#\n\x0c\n\x05\x04\0\x02\x03\x05\x12\x03\x0c\x08\r\n\x0c\n\
-    \x05\x04\0\x02\x03\x01\x12\x03\x0c\x13\x17\n\x0c\n\x05\x04\0\x02\x03\x03\
-    \x12\x03\x0c!\"\n\n\n\x02\x04\x01\x12\x04\x0f\0\x13\x01\n\n\n\x03\x04\
-    \x01\x01\x12\x03\x0f\x08\x18\n\x0b\n\x04\x04\x01\x02\0\x12\x03\x10\x04\
-    \x1d\n\r\n\x05\x04\x01\x02\0\x04\x12\x04\x10\x04\x0f\x1a\n\x0c\n\x05\x04\
-    \x01\x02\0\x06\x12\x03\x10\x04\r\n\x0c\n\x05\x04\x01\x02\0\x01\x12\x03\
-    \x10\x0e\x18\n\x0c\n\x05\x04\x01\x02\0\x03\x12\x03\x10\x1b\x1c\n\x0b\n\
-    \x04\x04\x01\x02\x01\x12\x03\x11\x04\x1d\n\r\n\x05\x04\x01\x02\x01\x04\
-    \x12\x04\x11\x04\x10\x1d\n\x0c\n\x05\x04\x01\x02\x01\x05\x12\x03\x11\x04\
-    \n\n\x0c\n\x05\x04\x01\x02\x01\x01\x12\x03\x11\x0e\x13\n\x0c\n\x05\x04\
-    \x01\x02\x01\x03\x12\x03\x11\x1b\x1c\n\x0b\n\x04\x04\x01\x02\x02\x12\x03\
-    \x12\x04\x1d\n\r\n\x05\x04\x01\x02\x02\x04\x12\x04\x12\x04\x11\x1d\n\x0c\
-    \n\x05\x04\x01\x02\x02\x05\x12\x03\x12\x04\n\n\x0c\n\x05\x04\x01\x02\x02\
-    \x01\x12\x03\x12\x0e\x12\n\x0c\n\x05\x04\x01\x02\x02\x03\x12\x03\x12\x1b\
-    \x1c\n\n\n\x02\x04\x02\x12\x04\x15\0\x18\x01\n\n\n\x03\x04\x02\x01\x12\
-    \x03\x15\x08\x10\n\x0b\n\x04\x04\x02\x02\0\x12\x03\x16\x04\"\n\r\n\x05\
-    \x04\x02\x02\0\x04\x12\x04\x16\x04\x15\x12\n\x0c\n\x05\x04\x02\x02\0\x05\
-    \x12\x03\x16\x04\t\n\x0c\n\x05\x04\x02\x02\0\x01\x12\x03\x16\x15\x19\n\
-    \x0c\n\x05\x04\x02\x02\0\x03\x12\x03\x16\x20!\n\x0b\n\x04\x04\x02\x02\
-    \x01\x12\x03\x17\x04\"\n\r\n\x05\x04\x02\x02\x01\x04\x12\x04\x17\x04\x16\
-    \"\n\x0c\n\x05\x04\x02\x02\x01\x06\x12\x03\x17\x04\x14\n\x0c\n\x05\x04\
-    \x02\x02\x01\x01\x12\x03\x17\x15\x1d\n\x0c\n\x05\x04\x02\x02\x01\x03\x12\
-    \x03\x17\x20!\n\n\n\x02\x04\x03\x12\x04\x1a\0\x1d\x01\n\n\n\x03\x04\x03\
-    \x01\x12\x03\x1a\x08\x11\n\x0b\n\x04\x04\x03\x02\0\x12\x03\x1b\x08%\n\
-    \x0c\n\x05\x04\x03\x02\0\x04\x12\x03\x1b\x08\x10\n\x0c\n\x05\x04\x03\x02\
-    \0\x05\x12\x03\x1b\x11\x17\n\x0c\n\x05\x04\x03\x02\0\x01\x12\x03\x1b\x18\
-    \x1d\n\x0c\n\x05\x04\x03\x02\0\x03\x12\x03\x1b#$\n\x0b\n\x04\x04\x03\x02\
-    \x01\x12\x03\x1c\x08%\n\x0c\n\x05\x04\x03\x02\x01\x04\x12\x03\x1c\x08\
-    \x10\n\x0c\n\x05\x04\x03\x02\x01\x05\x12\x03\x1c\x11\x17\n\x0c\n\x05\x04\
-    \x03\x02\x01\x01\x12\x03\x1c\x18\x20\n\x0c\n\x05\x04\x03\x02\x01\x03\x12\
-    \x03\x1c#$\n\n\n\x02\x05\x01\x12\x04\x1f\03\x01\n\n\n\x03\x05\x01\x01\
-    \x12\x03\x1f\x05\x10\n\x0b\n\x04\x05\x01\x02\0\x12\x03\x20\x08\x1f\n\x0c\
-    \n\x05\x05\x01\x02\0\x01\x12\x03\x20\x08\x0e\n\x0c\n\x05\x05\x01\x02\0\
-    \x02\x12\x03\x20\x1d\x1e\n\x0b\n\x04\x05\x01\x02\x01\x12\x03!\x08\x1f\n\
-    \x0c\n\x05\x05\x01\x02\x01\x01\x12\x03!\x08\x0f\n\x0c\n\x05\x05\x01\x02\
-    \x01\x02\x12\x03!\x1d\x1e\n\x0b\n\x04\x05\x01\x02\x02\x12\x03\"\x08\x1f\
-    \n\x0c\n\x05\x05\x01\x02\x02\x01\x12\x03\"\x08\x0f\n\x0c\n\x05\x05\x01\
-    \x02\x02\x02\x12\x03\"\x1d\x1e\n\x0b\n\x04\x05\x01\x02\x03\x12\x03#\x08\
-    \x1f\n\x0c\n\x05\x05\x01\x02\x03\x01\x12\x03#\x08\x0e\n\x0c\n\x05\x05\
-    \x01\x02\x03\x02\x12\x03#\x1d\x1e\n\x0b\n\x04\x05\x01\x02\x04\x12\x03$\
-    \x08\x1f\n\x0c\n\x05\x05\x01\x02\x04\x01\x12\x03$\x08\x12\n\x0c\n\x05\
-    \x05\x01\x02\x04\x02\x12\x03$\x1d\x1e\n\x0b\n\x04\x05\x01\x02\x05\x12\
-    \x03%\x08\x1f\n\x0c\n\x05\x05\x01\x02\x05\x01\x12\x03%\x08\x0f\n\x0c\n\
-    \x05\x05\x01\x02\x05\x02\x12\x03%\x1d\x1e\n\x0b\n\x04\x05\x01\x02\x06\
-    \x12\x03&\x08\x1f\n\x0c\n\x05\x05\x01\x02\x06\x01\x12\x03&\x08\x13\n\x0c\
-    \n\x05\x05\x01\x02\x06\x02\x12\x03&\x1d\x1e\n\x0b\n\x04\x05\x01\x02\x07\
-    \x12\x03'\x08\x1f\n\x0c\n\x05\x05\x01\x02\x07\x01\x12\x03'\x08\x0f\n\x0c\
-    \n\x05\x05\x01\x02\x07\x02\x12\x03'\x1d\x1e\n\x0b\n\x04\x05\x01\x02\x08\
-    \x12\x03(\x08\x1f\n\x0c\n\x05\x05\x01\x02\x08\x01\x12\x03(\x08\x14\n\x0c\
-    \n\x05\x05\x01\x02\x08\x02\x12\x03(\x1d\x1e\n\x0b\n\x04\x05\x01\x02\t\
-    \x12\x03)\x08\x1f\n\x0c\n\x05\x05\x01\x02\t\x01\x12\x03)\x08\x18\n\x0c\n\
-    \x05\x05\x01\x02\t\x02\x12\x03)\x1d\x1e\n\x0b\n\x04\x05\x01\x02\n\x12\
-    \x03*\x08\x20\n\x0c\n\x05\x05\x01\x02\n\x01\x12\x03*\x08\x16\n\x0c\n\x05\
-    \x05\x01\x02\n\x02\x12\x03*\x1d\x1f\n\x0b\n\x04\x05\x01\x02\x0b\x12\x03+\
-    \x08\x20\n\x0c\n\x05\x05\x01\x02\x0b\x01\x12\x03+\x08\x15\n\x0c\n\x05\
-    \x05\x01\x02\x0b\x02\x12\x03+\x1d\x1f\n\x0b\n\x04\x05\x01\x02\x0c\x12\
-    \x03,\x08\x20\n\x0c\n\x05\x05\x01\x02\x0c\x01\x12\x03,\x08\x16\n\x0c\n\
-    \x05\x05\x01\x02\x0c\x02\x12\x03,\x1d\x1f\n\x0b\n\x04\x05\x01\x02\r\x12\
-    \x03-\x08\x20\n\x0c\n\x05\x05\x01\x02\r\x01\x12\x03-\x08\x19\n\x0c\n\x05\
-    \x05\x01\x02\r\x02\x12\x03-\x1d\x1f\n\x0b\n\x04\x05\x01\x02\x0e\x12\x03.\
-    \x08\x20\n\x0c\n\x05\x05\x01\x02\x0e\x01\x12\x03.\x08\x15\n\x0c\n\x05\
-    \x05\x01\x02\x0e\x02\x12\x03.\x1d\x1f\n\x0b\n\x04\x05\x01\x02\x0f\x12\
-    \x03/\x08\x20\n\x0c\n\x05\x05\x01\x02\x0f\x01\x12\x03/\x08\x14\n\x0c\n\
-    \x05\x05\x01\x02\x0f\x02\x12\x03/\x1d\x1f\n\x0b\n\x04\x05\x01\x02\x10\
-    \x12\x030\x08\x20\n\x0c\n\x05\x05\x01\x02\x10\x01\x12\x030\x08\x18\n\x0c\
-    \n\x05\x05\x01\x02\x10\x02\x12\x030\x1d\x1f\n\x0b\n\x04\x05\x01\x02\x11\
-    \x12\x031\x08\x20\n\x0c\n\x05\x05\x01\x02\x11\x01\x12\x031\x08\x12\n\x0c\
-    \n\x05\x05\x01\x02\x11\x02\x12\x031\x1d\x1f\n\x0b\n\x04\x05\x01\x02\x12\
-    \x12\x032\x08\x20\n\x0c\n\x05\x05\x01\x02\x12\x01\x12\x032\x08\x16\n\x0c\
-    \n\x05\x05\x01\x02\x12\x02\x12\x032\x1d\x1f\n\n\n\x02\x04\x04\x12\x045\0\
-    9\x01\n\n\n\x03\x04\x04\x01\x12\x035\x08\x11\n\x0b\n\x04\x04\x04\x02\0\
-    \x12\x036\x04\x16\n\r\n\x05\x04\x04\x02\0\x04\x12\x046\x045\x13\n\x0c\n\
-    \x05\x04\x04\x02\0\x05\x12\x036\x04\n\n\x0c\n\x05\x04\x04\x02\0\x01\x12\
-    \x036\x0b\x0f\n\x0c\n\x05\x04\x04\x02\0\x03\x12\x036\x14\x15\n\x0b\n\x04\
-    \x04\x04\x02\x01\x12\x037\x04\x16\n\r\n\x05\x04\x04\x02\x01\x04\x12\x047\
-    \x046\x16\n\x0c\n\x05\x04\x04\x02\x01\x05\x12\x037\x04\n\n\x0c\n\x05\x04\
-    \x04\x02\x01\x01\x12\x037\x0b\x0f\n\x0c\n\x05\x04\x04\x02\x01\x03\x12\
-    \x037\x14\x15\n\x0b\n\x04\x04\x04\x02\x02\x12\x038\x04\x16\n\r\n\x05\x04\
-    \x04\x02\x02\x04\x12\x048\x047\x16\n\x0c\n\x05\x04\x04\x02\x02\x05\x12\
-    \x038\x04\n\n\x0c\n\x05\x04\x04\x02\x02\x01\x12\x038\x0b\x11\n\x0c\n\x05\
-    \x04\x04\x02\x02\x03\x12\x038\x14\x15\n\n\n\x02\x05\x02\x12\x04;\0@\x01\
-    \n\n\n\x03\x05\x02\x01\x12\x03;\x05\x13\n\x0b\n\x04\x05\x02\x02\0\x12\
-    \x03<\x08%\n\x0c\n\x05\x05\x02\x02\0\x01\x12\x03<\x08\x19\n\x0c\n\x05\
-    \x05\x02\x02\0\x02\x12\x03<#$\n\x0b\n\x04\x05\x02\x02\x01\x12\x03=\x08%\
-    \n\x0c\n\x05\x05\x02\x02\x01\x01\x12\x03=\x08\x1c\n\x0c\n\x05\x05\x02\
-    \x02\x01\x02\x12\x03=#$\n\x0b\n\x04\x05\x02\x02\x02\x12\x03>\x08%\n\x0c\
-    \n\x05\x05\x02\x02\x02\x01\x12\x03>\x08\x1c\n\x0c\n\x05\x05\x02\x02\x02\
-    \x02\x12\x03>#$\n\x0b\n\x04\x05\x02\x02\x03\x12\x03?\x08%\n\x0c\n\x05\
-    \x05\x02\x02\x03\x01\x12\x03?\x08\x20\n\x0c\n\x05\x05\x02\x02\x03\x02\
-    \x12\x03?#$\n\n\n\x02\x04\x05\x12\x04B\0O\x01\n\n\n\x03\x04\x05\x01\x12\
-    \x03B\x08\x0f\n\x0b\n\x04\x04\x05\x02\0\x12\x03C\x04\x20\n\r\n\x05\x04\
-    \x05\x02\0\x04\x12\x04C\x04B\x11\n\x0c\n\x05\x04\x05\x02\0\x06\x12\x03C\
-    \x04\x0f\n\x0c\n\x05\x04\x05\x02\0\x01\x12\x03C\x10\x18\n\x0c\n\x05\x04\
-    \x05\x02\0\x03\x12\x03C\x1e\x1f\n\x0b\n\x04\x04\x05\x02\x01\x12\x03D\x04\
-    \x20\n\r\n\x05\x04\x05\x02\x01\x04\x12\x04D\x04C\x20\n\x0c\n\x05\x04\x05\
-    \x02\x01\x05\x12\x03D\x04\n\n\x0c\n\x05\x04\x05\x02\x01\x01\x12\x03D\x0b\
-    \r\n\x0c\n\x05\x04\x05\x02\x01\x03\x12\x03D\x1e\x1f\n\x0b\n\x04\x04\x05\
-    \x02\x02\x12\x03E\x04\x20\n\r\n\x05\x04\x05\x02\x02\x04\x12\x04E\x04D\
-    \x20\n\x0c\n\x05\x04\x05\x02\x02\x05\x12\x03E\x04\n\n\x0c\n\x05\x04\x05\
-    \x02\x02\x01\x12\x03E\x0b\x0f\n\x0c\n\x05\x04\x05\x02\x02\x03\x12\x03E\
-    \x1e\x1f\n\x1e\n\x04\x04\x05\x02\x03\x12\x03F\x04\x20\"\x11\x20leader\
-    \xe2\x80\x99s\x20term\n\n\r\n\x05\x04\x05\x02\x03\x04\x12\x04F\x04E\x20\
-    \n\x0c\n\x05\x04\x05\x02\x03\x05\x12\x03F\x04\n\n\x0c\n\x05\x04\x05\x02\
-    \x03\x01\x12\x03F\x0b\x0f\n\x0c\n\x05\x04\x05\x02\x03\x03\x12\x03F\x1e\
-    \x1f\n)\n\x04\x04\x05\x02\x04\x12\x03G\x04\x20\"\x1c\x20term\x20of\x20pr\
-    evLogIndex\x20entry\n\n\r\n\x05\x04\x05\x02\x04\x04\x12\x04G\x04F\x20\n\
-    \x0c\n\x05\x04\x05\x02\x04\x05\x12\x03G\x04\n\n\x0c\n\x05\x04\x05\x02\
-    \x04\x01\x12\x03G\x0b\x13\n\x0c\n\x05\x04\x05\x02\x04\x03\x12\x03G\x1e\
-    \x1f\n@\n\x04\x04\x05\x02\x05\x12\x03H\x04\x20\"3\x20index\x20of\x20log\
-    \x20entry\x20immediately\x20preceding\x20new\x20ones\n\n\r\n\x05\x04\x05\
-    \x02\x05\x04\x12\x04H\x04G\x20\n\x0c\n\x05\x04\x05\x02\x05\x05\x12\x03H\
-    \x04\n\n\x0c\n\x05\x04\x05\x02\x05\x01\x12\x03H\x0b\x10\n\x0c\n\x05\x04\
-    \x05\x02\x05\x03\x12\x03H\x1e\x1f\n`\n\x04\x04\x05\x02\x06\x12\x03I\x04\
-    \x20\"S\x20log\x20entries\x20to\x20store\x20(empty\x20for\x20heartbeat;\
-    \x20may\x20send\x20more\x20than\x20one\x20for\x20efficiency)\n\n\x0c\n\
-    \x05\x04\x05\x02\x06\x04\x12\x03I\x04\x0c\n\x0c\n\x05\x04\x05\x02\x06\
-    \x06\x12\x03I\r\x12\n\x0c\n\x05\x04\x05\x02\x06\x01\x12\x03I\x13\x1a\n\
-    \x0c\n\x05\x04\x05\x02\x06\x03\x12\x03I\x1e\x1f\n%\n\x04\x04\x05\x02\x07\
-    \x12\x03J\x04\x20\"\x18\x20leader\xe2\x80\x99s\x20commitIndex\n\n\r\n\
-    \x05\x04\x05\x02\x07\x04\x12\x04J\x04I\x20\n\x0c\n\x05\x04\x05\x02\x07\
-    \x05\x12\x03J\x04\n\n\x0c\n\x05\x04\x05\x02\x07\x01\x12\x03J\x0b\x11\n\
-    \x0c\n\x05\x04\x05\x02\x07\x03\x12\x03J\x1e\x1f\n\x0b\n\x04\x04\x05\x02\
-    \x08\x12\x03K\x04\x20\n\r\n\x05\x04\x05\x02\x08\x04\x12\x04K\x04J\x20\n\
-    \x0c\n\x05\x04\x05\x02\x08\x06\x12\x03K\x04\x0c\n\x0c\n\x05\x04\x05\x02\
-    \x08\x01\x12\x03K\r\x15\n\x0c\n\x05\x04\x05\x02\x08\x03\x12\x03K\x1e\x1f\
-    \n\x0b\n\x04\x04\x05\x02\t\x12\x03L\x04!\n\r\n\x05\x04\x05\x02\t\x04\x12\
-    \x04L\x04K\x20\n\x0c\n\x05\x04\x05\x02\t\x05\x12\x03L\x04\x08\n\x0c\n\
-    \x05\x04\x05\x02\t\x01\x12\x03L\t\x0f\n\x0c\n\x05\x04\x05\x02\t\x03\x12\
-    \x03L\x1e\x20\n\x0b\n\x04\x04\x05\x02\n\x12\x03M\x04!\n\r\n\x05\x04\x05\
-    \x02\n\x04\x12\x04M\x04L!\n\x0c\n\x05\x04\x05\x02\n\x05\x12\x03M\x04\n\n\
-    \x0c\n\x05\x04\x05\x02\n\x01\x12\x03M\x0b\x16\n\x0c\n\x05\x04\x05\x02\n\
-    \x03\x12\x03M\x1e\x20\n\x0b\n\x04\x04\x05\x02\x0b\x12\x03N\x04!\n\r\n\
-    \x05\x04\x05\x02\x0b\x04\x12\x04N\x04M!\n\x0c\n\x05\x04\x05\x02\x0b\x05\
-    \x12\x03N\x04\t\n\x0c\n\x05\x04\x05\x02\x0b\x01\x12\x03N\n\x11\n\x0c\n\
-    \x05\x04\x05\x02\x0b\x03\x12\x03N\x1e\x20\n\n\n\x02\x04\x06\x12\x04Q\0V\
-    \x01\n\n\n\x03\x04\x06\x01\x12\x03Q\x08\x12\n\x0b\n\x04\x04\x06\x02\0\
-    \x12\x03R\x08(\n\r\n\x05\x04\x06\x02\0\x04\x12\x04R\x08Q\x14\n\x0c\n\x05\
-    \x04\x06\x02\0\x05\x12\x03R\x08\x0e\n\x0c\n\x05\x04\x06\x02\0\x01\x12\
-    \x03R\x18\x1a\n\x0c\n\x05\x04\x06\x02\0\x03\x12\x03R&'\n\x0b\n\x04\x04\
-    \x06\x02\x01\x12\x03S\x08(\n\r\n\x05\x04\x06\x02\x01\x04\x12\x04S\x08R(\
-    \n\x0c\n\x05\x04\x06\x02\x01\x06\x12\x03S\x08\x16\n\x0c\n\x05\x04\x06\
-    \x02\x01\x01\x12\x03S\x18#\n\x0c\n\x05\x04\x06\x02\x01\x03\x12\x03S&'\n\
-    \x0b\n\x04\x04\x06\x02\x02\x12\x03T\x08(\n\r\n\x05\x04\x06\x02\x02\x04\
-    \x12\x04T\x08S(\n\x0c\n\x05\x04\x06\x02\x02\x05\x12\x03T\x08\x0e\n\x0c\n\
-    \x05\x04\x06\x02\x02\x01\x12\x03T\x18\x1f\n\x0c\n\x05\x04\x06\x02\x02\
-    \x03\x12\x03T&'\n\x0b\n\x04\x04\x06\x02\x03\x12\x03U\x08(\n\r\n\x05\x04\
-    \x06\x02\x03\x04\x12\x04U\x08T(\n\x0c\n\x05\x04\x06\x02\x03\x05\x12\x03U\
-    \x08\r\n\x0c\n\x05\x04\x06\x02\x03\x01\x12\x03U\x18\x1f\n\x0c\n\x05\x04\
-    \x06\x02\x03\x03\x12\x03U&'b\x06proto3\
+    \n\x13raftpb/raftpb.proto\x12\x06raftpb\"\xad\x01\n\x05Entry\x12\x12\n\
+    \x04term\x18\x01\x20\x01(\x04R\x04term\x12\x14\n\x05index\x18\x02\x20\
+    \x01(\x04R\x05index\x120\n\nentry_type\x18\x03\x20\x01(\x0e2\x11.raftpb.\
+    EntryTypeR\tentryType\x12\x12\n\x04data\x18\x04\x20\x01(\x0cR\x04data\
+    \x12\x1a\n\x08checksum\x18\x05\x20\x01(\rR\x08checksum\x12\x18\n\x07cont\
+    ext\x18\x06\x20\x01(\x0cR\x07context\"n\n\x10SnapshotMetadata\x120\n\nco\
+    nf_state\x18\x01\x20\x01(\x0b2\x11.raftpb.ConfStateR\tconfState\x12\x14\
+    \n\x05index\x18\x02\x20\x01(\x04R\x05index\x12\x12\n\x04term\x18\x03\x20\
+    \x01(\x04R\x04term\"T\n\x08Snapshot\x12\x12\n\x04data\x18\x01\x20\x01(\
+    \x0cR\x04data\x124\n\x08metadata\x18\x02\x20\x01(\x0b2\x18.raftpb.Snapsh\
+    otMetadataR\x08metadata\"\xaa\x01\n\tConfState\x12\x14\n\x05nodes\x18\
+    \x01\x20\x03(\x04R\x05nodes\x12\x1a\n\x08learners\x18\x02\x20\x03(\x04R\
+    \x08learners\x12'\n\x0fvoters_outgoing\x18\x03\x20\x03(\x04R\x0evotersOu\
+    tgoing\x12\x1d\n\nauto_leave\x18\x04\x20\x01(\x08R\tautoLeave\x12#\n\rle\
+    arners_next\x18\x05\x20\x03(\x04R\x0clearnersNext\"\x87\x01\n\tHardState\
+    \x12\x12\n\x04term\x18\x01\x20\x01(\x04R\x04term\x12\x12\n\x04vote\x18\
+    \x02\x20\x01(\x04R\x04vote\x12\x16\n\x06commit\x18\x03\x20\x01(\x04R\x06\
+    commit\x12:\n\nextensions\x18\x04\x20\x03(\x0b2\x1a.raftpb.HardStateExte\
+    nsionR\nextensions\"8\n\x12HardStateExtension\x12\x0e\n\x02id\x18\x01\
+    \x20\x01(\rR\x02id\x12\x12\n\x04data\x18\x02\x20\x01(\x0cR\x04data\"\xa5\
+    \x03\n\x07Message\x12.\n\x08msg_type\x18\x01\x20\x01(\x0e2\x13.raftpb.Me\
+    ssageTypeR\x07msgType\x12\x0e\n\x02to\x18\x02\x20\x01(\x04R\x02to\x12\
+    \x12\n\x04from\x18\x03\x20\x01(\x04R\x04from\x12\x12\n\x04term\x18\x04\
+    \x20\x01(\x04R\x04term\x12\x19\n\x08log_term\x18\x05\x20\x01(\x04R\x07lo\
+    gTerm\x12\x14\n\x05index\x18\x06\x20\x01(\x04R\x05index\x12'\n\x07entrie\
+    s\x18\x07\x20\x03(\x0b2\r.raftpb.EntryR\x07entries\x12\x16\n\x06commit\
+    \x18\x08\x20\x01(\x04R\x06commit\x12,\n\x08snapshot\x18\t\x20\x01(\x0b2\
+    \x10.raftpb.SnapshotR\x08snapshot\x12\x16\n\x06reject\x18\n\x20\x01(\x08\
+    R\x06reject\x12\x1f\n\x0breject_hint\x18\x0b\x20\x01(\x04R\nrejectHint\
+    \x12\x18\n\x07context\x18\x0c\x20\x01(\x0cR\x07context\x12#\n\rappend_bu\
+    dget\x18\r\x20\x01(\x04R\x0cappendBudget\x12\x1a\n\x08priority\x18\x0e\
+    \x20\x01(\x04R\x08priority\"\x88\x01\n\nConfChange\x12\x0e\n\x02id\x18\
+    \x01\x20\x01(\x04R\x02id\x127\n\x0bchange_type\x18\x02\x20\x01(\x0e2\x16\
+    .raftpb.ConfChangeTypeR\nchangeType\x12\x17\n\x07node_id\x18\x03\x20\x01\
+    (\x04R\x06nodeId\x12\x18\n\x07context\x18\x04\x20\x01(\x0cR\x07context\"\
+    d\n\x10ConfChangeSingle\x127\n\x0bchange_type\x18\x01\x20\x01(\x0e2\x16.\
+    raftpb.ConfChangeTypeR\nchangeType\x12\x17\n\x07node_id\x18\x02\x20\x01(\
+    \x04R\x06nodeId\"\x9a\x01\n\x0cConfChangeV2\x12<\n\ntransition\x18\x01\
+    \x20\x01(\x0e2\x1c.raftpb.ConfChangeTransitionR\ntransition\x122\n\x07ch\
+    anges\x18\x02\x20\x03(\x0b2\x18.raftpb.ConfChangeSingleR\x07changes\x12\
+    \x18\n\x07context\x18\x03\x20\x01(\x0cR\x07context*H\n\tEntryType\x12\
+    \x0f\n\x0bEntryNormal\x10\0\x12\x13\n\x0fEntryConfChange\x10\x01\x12\x15\
+    \n\x11EntryConfChangeV2\x10\x02*\xe8\x02\n\x0bMessageType\x12\n\n\x06Msg\
+    Hup\x10\0\x12\x0b\n\x07MsgBeat\x10\x01\x12\x0b\n\x07MsgProp\x10\x02\x12\
+    \n\n\x06MsgApp\x10\x03\x12\x0e\n\nMsgAppResp\x10\x04\x12\x0b\n\x07MsgVot\
+    e\x10\x05\x12\x0f\n\x0bMsgVoteResp\x10\x06\x12\x0b\n\x07MsgSnap\x10\x07\
+    \x12\x10\n\x0cMsgHeartbeat\x10\x08\x12\x14\n\x10MsgHeartbeatResp\x10\t\
+    \x12\x12\n\x0eMsgUnreachable\x10\n\x12\x11\n\rMsgSnapStatus\x10\x0b\x12\
+    \x12\n\x0eMsgCheckQuorum\x10\x0c\x12\x15\n\x11MsgTransferLeader\x10\r\
+    \x12\x11\n\rMsgTimeoutNow\x10\x0e\x12\x10\n\x0cMsgReadIndex\x10\x0f\x12\
+    \x14\n\x10MsgReadIndexResp\x10\x10\x12\x0e\n\nMsgPreVote\x10\x11\x12\x12\
+    \n\x0eMsgPreVoteResp\x10\x12\x12\x13\n\x0fMsgForgetLeader\x10\x13*y\n\
+    \x0eConfChangeType\x12\x15\n\x11ConfChangeAddNode\x10\0\x12\x18\n\x14Con\
+    fChangeRemoveNode\x10\x01\x12\x18\n\x14ConfChangeUpdateNode\x10\x02\x12\
+    \x1c\n\x18ConfChangeAddLearnerNode\x10\x03*\x82\x01\n\x14ConfChangeTrans\
+    ition\x12\x1c\n\x18ConfChangeTransitionAuto\x10\0\x12%\n!ConfChangeTrans\
+    itionJointImplicit\x10\x01\x12%\n!ConfChangeTransitionJointExplicit\x10\
+    \x02J\xba,\n\x07\x12\x05\0\0\x85\x01\x01\n\x08\n\x01\x0c\x12\x03\0\0\x12\
+    \n\x08\n\x01\x02\x12\x03\x01\0\x0f\n\n\n\x02\x05\0\x12\x04\x03\0\x07\x01\
+    \n\n\n\x03\x05\0\x01\x12\x03\x03\x05\x0e\n\x0b\n\x04\x05\0\x02\0\x12\x03\
+    \x04\x04\x18\n\x0c\n\x05\x05\0\x02\0\x01\x12\x03\x04\x04\x0f\n\x0c\n\x05\
+    \x05\0\x02\0\x02\x12\x03\x04\x16\x17\n\x0b\n\x04\x05\0\x02\x01\x12\x03\
+    \x05\x04\x18\n\x0c\n\x05\x05\0\x02\x01\x01\x12\x03\x05\x04\x13\n\x0c\n\
+    \x05\x05\0\x02\x01\x02\x12\x03\x05\x16\x17\n\x0b\n\x04\x05\0\x02\x02\x12\
+    \x03\x06\x04\x1a\n\x0c\n\x05\x05\0\x02\x02\x01\x12\x03\x06\x04\x15\n\x0c\
+    \n\x05\x05\0\x02\x02\x02\x12\x03\x06\x18\x19\n\n\n\x02\x04\0\x12\x04\t\0\
+    \x10\x01\n\n\n\x03\x04\0\x01\x12\x03\t\x08\r\n;\n\x04\x04\0\x02\0\x12\
+    \x03\n\x08#\".\x20must\x20be\x2064-bit\x20aligned\x20for\x20atomic\x20op\
+    erations\n\n\x0c\n\x05\x04\0\x02\0\x05\x12\x03\n\x08\x0e\n\x0c\n\x05\x04\
+    \0\x02\0\x01\x12\x03\n\x13\x17\n\x0c\n\x05\x04\0\x02\0\x03\x12\x03\n!\"\
+    \n;\n\x04\x04\0\x02\x01\x12\x03\x0b\x08#\".\x20must\x20be\x2064-bit\x20a\
+    ligned\x20for\x20atomic\x20operations\n\n\x0c\n\x05\x04\0\x02\x01\x05\
+    \x12\x03\x0b\x08\x0e\n\x0c\n\x05\x04\0\x02\x01\x01\x12\x03\x0b\x13\x18\n\
+    \x0c\n\x05\x04\0\x02\x01\x03\x12\x03\x0b!\"\n\x0b\n\x04\x04\0\x02\x02\
+    \x12\x03\x0c\x08#\n\x0c\n\x05\x04\0\x02\x02\x06\x12\x03\x0c\x08\x11\n\
+    \x0c\n\x05\x04\0\x02\x02\x01\x12\x03\x0c\x13\x1d\n\x0c\n\x05\x04\0\x02\
+    \x02\x03\x12\x03\x0c!\"\n\x0b\n\x04\x04\0\x02\x03\x12\x03\r\x08#\n\x0c\n\
+    \x05\x04\0\x02\x03\x05\x12\x03\r\x08\r\n\x0c\n\x05\x04\0\x02\x03\x01\x12\
+    \x03\r\x13\x17\n\x0c\n\x05\x04\0\x02\x03\x03\x12\x03\r!\"\n8\n\x04\x04\0\
+    \x02\x04\x12\x03\x0e\x08#\"+\x20optional\x20CRC-32C\x20of\x20data,\x20ze\
+    ro\x20if\x20not\x20set\n\n\x0c\n\x05\x04\0\x02\x04\x05\x12\x03\x0e\x08\
+    \x0e\n\x0c\n\x05\x04\0\x02\x04\x01\x12\x03\x0e\x13\x1b\n\x0c\n\x05\x04\0\
+    \x02\x04\x03\x12\x03\x0e!\"\n?\n\x04\x04\0\x02\x05\x12\x03\x0f\x08#\"2\
+    \x20opaque\x20proposal\x20context,\x20not\x20interpreted\x20by\x20raft\n\
+    \n\x0c\n\x05\x04\0\x02\x05\x05\x12\x03\x0f\x08\r\n\x0c\n\x05\x04\0\x02\
+    \x05\x01\x12\x03\x0f\x13\x1a\n\x0c\n\x05\x04\0\x02\x05\x03\x12\x03\x0f!\
+    \"\n\n\n\x02\x04\x01\x12\x04\x12\0\x16\x01\n\n\n\x03\x04\x01\x01\x12\x03\
+    \x12\x08\x18\n\x0b\n\x04\x04\x01\x02\0\x12\x03\x13\x04\x1d\n\x0c\n\x05\
+    \x04\x01\x02\0\x06\x12\x03\x13\x04\r\n\x0c\n\x05\x04\x01\x02\0\x01\x12\
+    \x03\x13\x0e\x18\n\x0c\n\x05\x04\x01\x02\0\x03\x12\x03\x13\x1b\x1c\n\x0b\
+    \n\x04\x04\x01\x02\x01\x12\x03\x14\x04\x1d\n\x0c\n\x05\x04\x01\x02\x01\
+    \x05\x12\x03\x14\x04\n\n\x0c\n\x05\x04\x01\x02\x01\x01\x12\x03\x14\x0e\
+    \x13\n\x0c\n\x05\x04\x01\x02\x01\x03\x12\x03\x14\x1b\x1c\n\x0b\n\x04\x04\
+    \x01\x02\x02\x12\x03\x15\x04\x1d\n\x0c\n\x05\x04\x01\x02\x02\x05\x12\x03\
+    \x15\x04\n\n\x0c\n\x05\x04\x01\x02\x02\x01\x12\x03\x15\x0e\x12\n\x0c\n\
+    \x05\x04\x01\x02\x02\x03\x12\x03\x15\x1b\x1c\n\n\n\x02\x04\x02\x12\x04\
+    \x18\0\x1b\x01\n\n\n\x03\x04\x02\x01\x12\x03\x18\x08\x10\n\x0b\n\x04\x04\
+    \x02\x02\0\x12\x03\x19\x04\"\n\x0c\n\x05\x04\x02\x02\0\x05\x12\x03\x19\
+    \x04\t\n\x0c\n\x05\x04\x02\x02\0\x01\x12\x03\x19\x15\x19\n\x0c\n\x05\x04\
+    \x02\x02\0\x03\x12\x03\x19\x20!\n\x0b\n\x04\x04\x02\x02\x01\x12\x03\x1a\
+    \x04\"\n\x0c\n\x05\x04\x02\x02\x01\x06\x12\x03\x1a\x04\x14\n\x0c\n\x05\
+    \x04\x02\x02\x01\x01\x12\x03\x1a\x15\x1d\n\x0c\n\x05\x04\x02\x02\x01\x03\
+    \x12\x03\x1a\x20!\n\n\n\x02\x04\x03\x12\x04\x1d\0'\x01\n\n\n\x03\x04\x03\
+    \x01\x12\x03\x1d\x08\x11\n\x0b\n\x04\x04\x03\x02\0\x12\x03\x1e\x08,\n\
+    \x0c\n\x05\x04\x03\x02\0\x04\x12\x03\x1e\x08\x10\n\x0c\n\x05\x04\x03\x02\
+    \0\x05\x12\x03\x1e\x11\x17\n\x0c\n\x05\x04\x03\x02\0\x01\x12\x03\x1e\x18\
+    \x1d\n\x0c\n\x05\x04\x03\x02\0\x03\x12\x03\x1e*+\n\x0b\n\x04\x04\x03\x02\
+    \x01\x12\x03\x1f\x08,\n\x0c\n\x05\x04\x03\x02\x01\x04\x12\x03\x1f\x08\
+    \x10\n\x0c\n\x05\x04\x03\x02\x01\x05\x12\x03\x1f\x11\x17\n\x0c\n\x05\x04\
+    \x03\x02\x01\x01\x12\x03\x1f\x18\x20\n\x0c\n\x05\x04\x03\x02\x01\x03\x12\
+    \x03\x1f*+\nV\n\x04\x04\x03\x02\x02\x12\x03!\x08,\x1aI\x20voters\x20of\
+    \x20the\x20outgoing\x20config\x20while\x20in\x20a\x20joint\x20config,\
+    \x20empty\x20otherwise.\n\n\x0c\n\x05\x04\x03\x02\x02\x04\x12\x03!\x08\
+    \x10\n\x0c\n\x05\x04\x03\x02\x02\x05\x12\x03!\x11\x17\n\x0c\n\x05\x04\
+    \x03\x02\x02\x01\x12\x03!\x18'\n\x0c\n\x05\x04\x03\x02\x02\x03\x12\x03!*\
+    +\nI\n\x04\x04\x03\x02\x03\x12\x03#\x08,\x1a<\x20leave\x20the\x20joint\
+    \x20config\x20automatically\x20once\x20it\x20is\x20committed.\n\n\x0c\n\
+    \x05\x04\x03\x02\x03\x05\x12\x03#\x08\x0c\n\x0c\n\x05\x04\x03\x02\x03\
+    \x01\x12\x03#\x18\"\n\x0c\n\x05\x04\x03\x02\x03\x03\x12\x03#*+\n\x8c\x01\
+    \n\x04\x04\x03\x02\x04\x12\x03&\x08,\x1a\x7f\x20voters\x20of\x20the\x20o\
+    utgoing\x20config\x20which\x20become\x20learners\x20once\x20the\x20joint\
+    \n\x20config\x20is\x20left.\x20They\x20are\x20not\x20part\x20of\x20learn\
+    ers\x20until\x20then.\n\n\x0c\n\x05\x04\x03\x02\x04\x04\x12\x03&\x08\x10\
+    \n\x0c\n\x05\x04\x03\x02\x04\x05\x12\x03&\x11\x17\n\x0c\n\x05\x04\x03\
+    \x02\x04\x01\x12\x03&\x18%\n\x0c\n\x05\x04\x03\x02\x04\x03\x12\x03&*+\n\
+    \n\n\x02\x05\x01\x12\x04)\0>\x01\n\n\n\x03\x05\x01\x01\x12\x03)\x05\x10\
+    \n\x0b\n\x04\x05\x01\x02\0\x12\x03*\x08\x1f\n\x0c\n\x05\x05\x01\x02\0\
+    \x01\x12\x03*\x08\x0e\n\x0c\n\x05\x05\x01\x02\0\x02\x12\x03*\x1d\x1e\n\
+    \x0b\n\x04\x05\x01\x02\x01\x12\x03+\x08\x1f\n\x0c\n\x05\x05\x01\x02\x01\
+    \x01\x12\x03+\x08\x0f\n\x0c\n\x05\x05\x01\x02\x01\x02\x12\x03+\x1d\x1e\n\
+    \x0b\n\x04\x05\x01\x02\x02\x12\x03,\x08\x1f\n\x0c\n\x05\x05\x01\x02\x02\
+    \x01\x12\x03,\x08\x0f\n\x0c\n\x05\x05\x01\x02\x02\x02\x12\x03,\x1d\x1e\n\
+    \x0b\n\x04\x05\x01\x02\x03\x12\x03-\x08\x1f\n\x0c\n\x05\x05\x01\x02\x03\
+    \x01\x12\x03-\x08\x0e\n\x0c\n\x05\x05\x01\x02\x03\x02\x12\x03-\x1d\x1e\n\
+    \x0b\n\x04\x05\x01\x02\x04\x12\x03.\x08\x1f\n\x0c\n\x05\x05\x01\x02\x04\
+    \x01\x12\x03.\x08\x12\n\x0c\n\x05\x05\x01\x02\x04\x02\x12\x03.\x1d\x1e\n\
+    \x0b\n\x04\x05\x01\x02\x05\x12\x03/\x08\x1f\n\x0c\n\x05\x05\x01\x02\x05\
+    \x01\x12\x03/\x08\x0f\n\x0c\n\x05\x05\x01\x02\x05\x02\x12\x03/\x1d\x1e\n\
+    \x0b\n\x04\x05\x01\x02\x06\x12\x030\x08\x1f\n\x0c\n\x05\x05\x01\x02\x06\
+    \x01\x12\x030\x08\x13\n\x0c\n\x05\x05\x01\x02\x06\x02\x12\x030\x1d\x1e\n\
+    \x0b\n\x04\x05\x01\x02\x07\x12\x031\x08\x1f\n\x0c\n\x05\x05\x01\x02\x07\
+    \x01\x12\x031\x08\x0f\n\x0c\n\x05\x05\x01\x02\x07\x02\x12\x031\x1d\x1e\n\
+    \x0b\n\x04\x05\x01\x02\x08\x12\x032\x08\x1f\n\x0c\n\x05\x05\x01\x02\x08\
+    \x01\x12\x032\x08\x14\n\x0c\n\x05\x05\x01\x02\x08\x02\x12\x032\x1d\x1e\n\
+    \x0b\n\x04\x05\x01\x02\t\x12\x033\x08\x1f\n\x0c\n\x05\x05\x01\x02\t\x01\
+    \x12\x033\x08\x18\n\x0c\n\x05\x05\x01\x02\t\x02\x12\x033\x1d\x1e\n\x0b\n\
+    \x04\x05\x01\x02\n\x12\x034\x08\x20\n\x0c\n\x05\x05\x01\x02\n\x01\x12\
+    \x034\x08\x16\n\x0c\n\x05\x05\x01\x02\n\x02\x12\x034\x1d\x1f\n\x0b\n\x04\
+    \x05\x01\x02\x0b\x12\x035\x08\x20\n\x0c\n\x05\x05\x01\x02\x0b\x01\x12\
+    \x035\x08\x15\n\x0c\n\x05\x05\x01\x02\x0b\x02\x12\x035\x1d\x1f\n\x0b\n\
+    \x04\x05\x01\x02\x0c\x12\x036\x08\x20\n\x0c\n\x05\x05\x01\x02\x0c\x01\
+    \x12\x036\x08\x16\n\x0c\n\x05\x05\x01\x02\x0c\x02\x12\x036\x1d\x1f\n\x0b\
+    \n\x04\x05\x01\x02\r\x12\x037\x08\x20\n\x0c\n\x05\x05\x01\x02\r\x01\x12\
+    \x037\x08\x19\n\x0c\n\x05\x05\x01\x02\r\x02\x12\x037\x1d\x1f\n\x0b\n\x04\
+    \x05\x01\x02\x0e\x12\x038\x08\x20\n\x0c\n\x05\x05\x01\x02\x0e\x01\x12\
+    \x038\x08\x15\n\x0c\n\x05\x05\x01\x02\x0e\x02\x12\x038\x1d\x1f\n\x0b\n\
+    \x04\x05\x01\x02\x0f\x12\x039\x08\x20\n\x0c\n\x05\x05\x01\x02\x0f\x01\
+    \x12\x039\x08\x14\n\x0c\n\x05\x05\x01\x02\x0f\x02\x12\x039\x1d\x1f\n\x0b\
+    \n\x04\x05\x01\x02\x10\x12\x03:\x08\x20\n\x0c\n\x05\x05\x01\x02\x10\x01\
+    \x12\x03:\x08\x18\n\x0c\n\x05\x05\x01\x02\x10\x02\x12\x03:\x1d\x1f\n\x0b\
+    \n\x04\x05\x01\x02\x11\x12\x03;\x08\x20\n\x0c\n\x05\x05\x01\x02\x11\x01\
+    \x12\x03;\x08\x12\n\x0c\n\x05\x05\x01\x02\x11\x02\x12\x03;\x1d\x1f\n\x0b\
+    \n\x04\x05\x01\x02\x12\x12\x03<\x08\x20\n\x0c\n\x05\x05\x01\x02\x12\x01\
+    \x12\x03<\x08\x16\n\x0c\n\x05\x05\x01\x02\x12\x02\x12\x03<\x1d\x1f\n\x0b\
+    \n\x04\x05\x01\x02\x13\x12\x03=\x08\x20\n\x0c\n\x05\x05\x01\x02\x13\x01\
+    \x12\x03=\x08\x17\n\x0c\n\x05\x05\x01\x02\x13\x02\x12\x03=\x1d\x1f\n\n\n\
+    \x02\x04\x04\x12\x04@\0E\x01\n\n\n\x03\x04\x04\x01\x12\x03@\x08\x11\n\
+    \x0b\n\x04\x04\x04\x02\0\x12\x03A\x04/\n\x0c\n\x05\x04\x04\x02\0\x05\x12\
+    \x03A\x04\n\n\x0c\n\x05\x04\x04\x02\0\x01\x12\x03A\x0b\x0f\n\x0c\n\x05\
+    \x04\x04\x02\0\x03\x12\x03A-.\n\x0b\n\x04\x04\x04\x02\x01\x12\x03B\x04/\
+    \n\x0c\n\x05\x04\x04\x02\x01\x05\x12\x03B\x04\n\n\x0c\n\x05\x04\x04\x02\
+    \x01\x01\x12\x03B\x0b\x0f\n\x0c\n\x05\x04\x04\x02\x01\x03\x12\x03B-.\n\
+    \x0b\n\x04\x04\x04\x02\x02\x12\x03C\x04/\n\x0c\n\x05\x04\x04\x02\x02\x05\
+    \x12\x03C\x04\n\n\x0c\n\x05\x04\x04\x02\x02\x01\x12\x03C\x0b\x11\n\x0c\n\
+    \x05\x04\x04\x02\x02\x03\x12\x03C-.\n\x1b\n\x04\x04\x04\x02\x03\x12\x03D\
+    \x04/\"\x0e\x20sorted\x20by\x20id\n\n\x0c\n\x05\x04\x04\x02\x03\x04\x12\
+    \x03D\x04\x0c\n\x0c\n\x05\x04\x04\x02\x03\x06\x12\x03D\r\x1f\n\x0c\n\x05\
+    \x04\x04\x02\x03\x01\x12\x03D\x20*\n\x0c\n\x05\x04\x04\x02\x03\x03\x12\
+    \x03D-.\n\xc0\x01\n\x02\x04\x05\x12\x04J\0M\x01\x1a\xb3\x01\x20HardState\
+    Extension\x20is\x20an\x20opaque\x20record\x20of\x20additional\x20durable\
+    \x20per-node\n\x20state,\x20persisted\x20together\x20with\x20HardState.\
+    \x20Nodes\x20keep\x20extensions\x20with\x20ids\n\x20they\x20don't\x20kno\
+    w\x20about\x20untouched.\n\n\n\n\x03\x04\x05\x01\x12\x03J\x08\x1a\n\x0b\
+    \n\x04\x04\x05\x02\0\x12\x03K\x04\x14\n\x0c\n\x05\x04\x05\x02\0\x05\x12\
+    \x03K\x04\n\n\x0c\n\x05\x04\x05\x02\0\x01\x12\x03K\x0b\r\n\x0c\n\x05\x04\
+    \x05\x02\0\x03\x12\x03K\x12\x13\n\x0b\n\x04\x04\x05\x02\x01\x12\x03L\x04\
+    \x14\n\x0c\n\x05\x04\x05\x02\x01\x05\x12\x03L\x04\t\n\x0c\n\x05\x04\x05\
+    \x02\x01\x01\x12\x03L\x0b\x0f\n\x0c\n\x05\x04\x05\x02\x01\x03\x12\x03L\
+    \x12\x13\n\n\n\x02\x05\x02\x12\x04O\0T\x01\n\n\n\x03\x05\x02\x01\x12\x03\
+    O\x05\x13\n\x0b\n\x04\x05\x02\x02\0\x12\x03P\x08%\n\x0c\n\x05\x05\x02\
+    \x02\0\x01\x12\x03P\x08\x19\n\x0c\n\x05\x05\x02\x02\0\x02\x12\x03P#$\n\
+    \x0b\n\x04\x05\x02\x02\x01\x12\x03Q\x08%\n\x0c\n\x05\x05\x02\x02\x01\x01\
+    \x12\x03Q\x08\x1c\n\x0c\n\x05\x05\x02\x02\x01\x02\x12\x03Q#$\n\x0b\n\x04\
+    \x05\x02\x02\x02\x12\x03R\x08%\n\x0c\n\x05\x05\x02\x02\x02\x01\x12\x03R\
+    \x08\x1c\n\x0c\n\x05\x05\x02\x02\x02\x02\x12\x03R#$\n\x0b\n\x04\x05\x02\
+    \x02\x03\x12\x03S\x08%\n\x0c\n\x05\x05\x02\x02\x03\x01\x12\x03S\x08\x20\
+    \n\x0c\n\x05\x05\x02\x02\x03\x02\x12\x03S#$\n\n\n\x02\x04\x06\x12\x04V\0\
+    e\x01\n\n\n\x03\x04\x06\x01\x12\x03V\x08\x0f\n\x0b\n\x04\x04\x06\x02\0\
+    \x12\x03W\x04\x20\n\x0c\n\x05\x04\x06\x02\0\x06\x12\x03W\x04\x0f\n\x0c\n\
+    \x05\x04\x06\x02\0\x01\x12\x03W\x10\x18\n\x0c\n\x05\x04\x06\x02\0\x03\
+    \x12\x03W\x1e\x1f\n\x0b\n\x04\x04\x06\x02\x01\x12\x03X\x04\x20\n\x0c\n\
+    \x05\x04\x06\x02\x01\x05\x12\x03X\x04\n\n\x0c\n\x05\x04\x06\x02\x01\x01\
+    \x12\x03X\x0b\r\n\x0c\n\x05\x04\x06\x02\x01\x03\x12\x03X\x1e\x1f\n\x0b\n\
+    \x04\x04\x06\x02\x02\x12\x03Y\x04\x20\n\x0c\n\x05\x04\x06\x02\x02\x05\
+    \x12\x03Y\x04\n\n\x0c\n\x05\x04\x06\x02\x02\x01\x12\x03Y\x0b\x0f\n\x0c\n\
+    \x05\x04\x06\x02\x02\x03\x12\x03Y\x1e\x1f\n\x1e\n\x04\x04\x06\x02\x03\
+    \x12\x03Z\x04\x20\"\x11\x20leader\xe2\x80\x99s\x20term\n\n\x0c\n\x05\x04\
+    \x06\x02\x03\x05\x12\x03Z\x04\n\n\x0c\n\x05\x04\x06\x02\x03\x01\x12\x03Z\
+    \x0b\x0f\n\x0c\n\x05\x04\x06\x02\x03\x03\x12\x03Z\x1e\x1f\n)\n\x04\x04\
+    \x06\x02\x04\x12\x03[\x04\x20\"\x1c\x20term\x20of\x20prevLogIndex\x20ent\
+    ry\n\n\x0c\n\x05\x04\x06\x02\x04\x05\x12\x03[\x04\n\n\x0c\n\x05\x04\x06\
+    \x02\x04\x01\x12\x03[\x0b\x13\n\x0c\n\x05\x04\x06\x02\x04\x03\x12\x03[\
+    \x1e\x1f\n@\n\x04\x04\x06\x02\x05\x12\x03\\\x04\x20\"3\x20index\x20of\
+    \x20log\x20entry\x20immediately\x20preceding\x20new\x20ones\n\n\x0c\n\
+    \x05\x04\x06\x02\x05\x05\x12\x03\\\x04\n\n\x0c\n\x05\x04\x06\x02\x05\x01\
+    \x12\x03\\\x0b\x10\n\x0c\n\x05\x04\x06\x02\x05\x03\x12\x03\\\x1e\x1f\n`\
+    \n\x04\x04\x06\x02\x06\x12\x03]\x04\x20\"S\x20log\x20entries\x20to\x20st\
+    ore\x20(empty\x20for\x20heartbeat;\x20may\x20send\x20more\x20than\x20one\
+    \x20for\x20efficiency)\n\n\x0c\n\x05\x04\x06\x02\x06\x04\x12\x03]\x04\
+    \x0c\n\x0c\n\x05\x04\x06\x02\x06\x06\x12\x03]\r\x12\n\x0c\n\x05\x04\x06\
+    \x02\x06\x01\x12\x03]\x13\x1a\n\x0c\n\x05\x04\x06\x02\x06\x03\x12\x03]\
+    \x1e\x1f\n%\n\x04\x04\x06\x02\x07\x12\x03^\x04\x20\"\x18\x20leader\xe2\
+    \x80\x99s\x20commitIndex\n\n\x0c\n\x05\x04\x06\x02\x07\x05\x12\x03^\x04\
+    \n\n\x0c\n\x05\x04\x06\x02\x07\x01\x12\x03^\x0b\x11\n\x0c\n\x05\x04\x06\
+    \x02\x07\x03\x12\x03^\x1e\x1f\n\x0b\n\x04\x04\x06\x02\x08\x12\x03_\x04\
+    \x20\n\x0c\n\x05\x04\x06\x02\x08\x06\x12\x03_\x04\x0c\n\x0c\n\x05\x04\
+    \x06\x02\x08\x01\x12\x03_\r\x15\n\x0c\n\x05\x04\x06\x02\x08\x03\x12\x03_\
+    \x1e\x1f\n\x0b\n\x04\x04\x06\x02\t\x12\x03`\x04!\n\x0c\n\x05\x04\x06\x02\
+    \t\x05\x12\x03`\x04\x08\n\x0c\n\x05\x04\x06\x02\t\x01\x12\x03`\t\x0f\n\
+    \x0c\n\x05\x04\x06\x02\t\x03\x12\x03`\x1e\x20\n\x0b\n\x04\x04\x06\x02\n\
+    \x12\x03a\x04!\n\x0c\n\x05\x04\x06\x02\n\x05\x12\x03a\x04\n\n\x0c\n\x05\
+    \x04\x06\x02\n\x01\x12\x03a\x0b\x16\n\x0c\n\x05\x04\x06\x02\n\x03\x12\
+    \x03a\x1e\x20\n\x0b\n\x04\x04\x06\x02\x0b\x12\x03b\x04!\n\x0c\n\x05\x04\
+    \x06\x02\x0b\x05\x12\x03b\x04\t\n\x0c\n\x05\x04\x06\x02\x0b\x01\x12\x03b\
+    \n\x11\n\x0c\n\x05\x04\x06\x02\x0b\x03\x12\x03b\x1e\x20\n_\n\x04\x04\x06\
+    \x02\x0c\x12\x03c\x04!\"R\x20max\x20size\x20of\x20the\x20next\x20MsgApp\
+    \x20wanted\x20by\x20the\x20sender\x20of\x20a\x20MsgAppResp,\x200\x20for\
+    \x20no\x20limit\n\n\x0c\n\x05\x04\x06\x02\x0c\x05\x12\x03c\x04\n\n\x0c\n\
+    \x05\x04\x06\x02\x0c\x01\x12\x03c\x0b\x18\n\x0c\n\x05\x04\x06\x02\x0c\
+    \x03\x12\x03c\x1e\x20\n@\n\x04\x04\x06\x02\r\x12\x03d\x04!\"3\x20electio\
+    n\x20priority\x20of\x20the\x20sender\x20of\x20a\x20vote\x20request\n\n\
+    \x0c\n\x05\x04\x06\x02\r\x05\x12\x03d\x04\n\n\x0c\n\x05\x04\x06\x02\r\
+    \x01\x12\x03d\x0b\x13\n\x0c\n\x05\x04\x06\x02\r\x03\x12\x03d\x1e\x20\n\n\
+    \n\x02\x04\x07\x12\x04g\0l\x01\n\n\n\x03\x04\x07\x01\x12\x03g\x08\x12\n\
+    \x0b\n\x04\x04\x07\x02\0\x12\x03h\x08(\n\x0c\n\x05\x04\x07\x02\0\x05\x12\
+    \x03h\x08\x0e\n\x0c\n\x05\x04\x07\x02\0\x01\x12\x03h\x18\x1a\n\x0c\n\x05\
+    \x04\x07\x02\0\x03\x12\x03h&'\n\x0b\n\x04\x04\x07\x02\x01\x12\x03i\x08(\
+    \n\x0c\n\x05\x04\x07\x02\x01\x06\x12\x03i\x08\x16\n\x0c\n\x05\x04\x07\
+    \x02\x01\x01\x12\x03i\x18#\n\x0c\n\x05\x04\x07\x02\x01\x03\x12\x03i&'\n\
+    \x0b\n\x04\x04\x07\x02\x02\x12\x03j\x08(\n\x0c\n\x05\x04\x07\x02\x02\x05\
+    \x12\x03j\x08\x0e\n\x0c\n\x05\x04\x07\x02\x02\x01\x12\x03j\x18\x1f\n\x0c\
+    \n\x05\x04\x07\x02\x02\x03\x12\x03j&'\n\x0b\n\x04\x04\x07\x02\x03\x12\
+    \x03k\x08(\n\x0c\n\x05\x04\x07\x02\x03\x05\x12\x03k\x08\r\n\x0c\n\x05\
+    \x04\x07\x02\x03\x01\x12\x03k\x18\x1f\n\x0c\n\x05\x04\x07\x02\x03\x03\
+    \x12\x03k&'\nY\n\x02\x05\x03\x12\x04o\0x\x01\x1aM\x20ConfChangeTransitio\
+    n\x20tells\x20how\x20a\x20ConfChangeV2\x20moves\x20through\x20a\x20joint\
+    \x20config.\n\n\n\n\x03\x05\x03\x01\x12\x03o\x05\x19\n\x80\x01\n\x04\x05\
+    \x03\x02\0\x12\x03r\x08.\x1as\x20Auto\x20uses\x20a\x20joint\x20config\
+    \x20only\x20when\x20the\x20changes\x20need\x20it\x20(more\x20than\x20one\
+    \n\x20change),\x20and\x20then\x20leaves\x20it\x20automatically.\n\n\x0c\
+    \n\x05\x05\x03\x02\0\x01\x12\x03r\x08\x20\n\x0c\n\x05\x05\x03\x02\0\x02\
+    \x12\x03r,-\nQ\n\x04\x05\x03\x02\x01\x12\x03t\x08.\x1aD\x20Implicit\x20a\
+    lways\x20enters\x20a\x20joint\x20config\x20and\x20leaves\x20it\x20automa\
+    tically.\n\n\x0c\n\x05\x05\x03\x02\x01\x01\x12\x03t\x08)\n\x0c\n\x05\x05\
+    \x03\x02\x01\x02\x12\x03t,-\no\n\x04\x05\x03\x02\x02\x12\x03w\x08.\x1ab\
+    \x20Explicit\x20enters\x20a\x20joint\x20config\x20which\x20the\x20applic\
+    ation\x20leaves\x20by\n\x20proposing\x20an\x20empty\x20ConfChangeV2.\n\n\
+    \x0c\n\x05\x05\x03\x02\x02\x01\x12\x03w\x08)\n\x0c\n\x05\x05\x03\x02\x02\
+    \x02\x12\x03w,-\n\n\n\x02\x04\x08\x12\x04z\0}\x01\n\n\n\x03\x04\x08\x01\
+    \x12\x03z\x08\x18\n\x0b\n\x04\x04\x08\x02\0\x12\x03{\x08(\n\x0c\n\x05\
+    \x04\x08\x02\0\x06\x12\x03{\x08\x16\n\x0c\n\x05\x04\x08\x02\0\x01\x12\
+    \x03{\x18#\n\x0c\n\x05\x04\x08\x02\0\x03\x12\x03{&'\n\x0b\n\x04\x04\x08\
+    \x02\x01\x12\x03|\x08(\n\x0c\n\x05\x04\x08\x02\x01\x05\x12\x03|\x08\x0e\
+    \n\x0c\n\x05\x04\x08\x02\x01\x01\x12\x03|\x18\x1f\n\x0c\n\x05\x04\x08\
+    \x02\x01\x03\x12\x03|&'\n\x8d\x01\n\x02\x04\t\x12\x06\x81\x01\0\x85\x01\
+    \x01\x1a\x7f\x20ConfChangeV2\x20applies\x20several\x20changes\x20at\x20o\
+    nce\x20through\x20joint\x20consensus.\x20An\n\x20empty\x20ConfChangeV2\
+    \x20leaves\x20the\x20current\x20joint\x20config.\n\n\x0b\n\x03\x04\t\x01\
+    \x12\x04\x81\x01\x08\x14\n\x0c\n\x04\x04\t\x02\0\x12\x04\x82\x01\x081\n\
+    \r\n\x05\x04\t\x02\0\x06\x12\x04\x82\x01\x08\x1c\n\r\n\x05\x04\t\x02\0\
+    \x01\x12\x04\x82\x01\",\n\r\n\x05\x04\t\x02\0\x03\x12\x04\x82\x01/0\n\
+    \x0c\n\x04\x04\t\x02\x01\x12\x04\x83\x01\x081\n\r\n\x05\x04\t\x02\x01\
+    \x04\x12\x04\x83\x01\x08\x10\n\r\n\x05\x04\t\x02\x01\x06\x12\x04\x83\x01\
+    \x11!\n\r\n\x05\x04\t\x02\x01\x01\x12\x04\x83\x01\")\n\r\n\x05\x04\t\x02\
+    \x01\x03\x12\x04\x83\x01/0\n\x0c\n\x04\x04\t\x02\x02\x12\x04\x84\x01\x08\
+    1\n\r\n\x05\x04\t\x02\x02\x05\x12\x04\x84\x01\x08\r\n\r\n\x05\x04\t\x02\
+    \x02\x01\x12\x04\x84\x01\")\n\r\n\x05\x04\t\x02\x02\x03\x12\x04\x84\x01/\
+    0b\x06proto3\
 ";
 
 static mut file_descriptor_proto_lazy: ::protobuf::lazy::Lazy<::protobuf::descriptor::FileDescriptorProto> = ::protobuf::lazy::Lazy {
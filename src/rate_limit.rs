@@ -0,0 +1,147 @@
+/// TokenBucket holds up to capacity tokens and gains rate tokens per tick.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenBucket {
+    rate: u64,
+    capacity: u64,
+    tokens: u64,
+}
+
+impl TokenBucket {
+    /// new creates a full bucket.
+    pub fn new(rate: u64, capacity: u64) -> TokenBucket {
+        TokenBucket {
+            rate,
+            capacity,
+            tokens: capacity,
+        }
+    }
+
+    pub fn tokens(&self) -> u64 {
+        self.tokens
+    }
+
+    /// refill adds the tokens gained over the given number of ticks.
+    pub fn refill(&mut self, ticks: u64) {
+        let gained = self.rate.saturating_mul(ticks);
+        self.tokens = self.capacity.min(self.tokens.saturating_add(gained));
+    }
+
+    /// can_take returns true if n tokens can be taken. More tokens than the
+    /// capacity can be taken from a full bucket, so that a request larger
+    /// than the capacity is delayed rather than refused forever.
+    pub fn can_take(&self, n: u64) -> bool {
+        n <= self.tokens || self.tokens == self.capacity
+    }
+
+    /// take takes n tokens, or all of them if there are fewer.
+    pub fn take(&mut self, n: u64) {
+        self.tokens = self.tokens.saturating_sub(n);
+    }
+}
+
+/// ProposalLimiter limits proposals per tick and proposal bytes per tick. Conf
+/// changes and messages from peers are never limited.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProposalLimiter {
+    proposals: Option<TokenBucket>,
+    bytes: Option<TokenBucket>,
+}
+
+impl ProposalLimiter {
+    /// new creates a limiter accepting on average max_proposals proposals and
+    /// max_bytes bytes per tick, with bursts of up to burst_ticks ticks worth
+    /// of either. 0 disables the respective limit.
+    pub fn new(max_proposals: u64, max_bytes: u64, burst_ticks: u64) -> ProposalLimiter {
+        let burst_ticks = burst_ticks.max(1);
+        let bucket = |rate: u64| {
+            if rate == 0 {
+                None
+            } else {
+                Some(TokenBucket::new(rate, rate.saturating_mul(burst_ticks)))
+            }
+        };
+        ProposalLimiter {
+            proposals: bucket(max_proposals),
+            bytes: bucket(max_bytes),
+        }
+    }
+
+    /// tick refills the buckets for the given number of ticks.
+    pub fn tick(&mut self, ticks: u64) {
+        for b in self.proposals.iter_mut().chain(self.bytes.iter_mut()) {
+            b.refill(ticks);
+        }
+    }
+
+    /// can_allow returns true if both limits allow a proposal of the given
+    /// size, without consuming anything.
+    pub fn can_allow(&self, bytes: u64) -> bool {
+        let allows = |bucket: &Option<TokenBucket>, n: u64| match *bucket {
+            Some(ref b) => b.can_take(n),
+            None => true,
+        };
+        allows(&self.proposals, 1) && allows(&self.bytes, bytes)
+    }
+
+    /// consume consumes the tokens of a proposal of the given size.
+    pub fn consume(&mut self, bytes: u64) {
+        if let Some(b) = self.proposals.as_mut() {
+            b.take(1);
+        }
+        if let Some(b) = self.bytes.as_mut() {
+            b.take(bytes);
+        }
+    }
+
+    /// allow returns true and consumes the tokens of a proposal of the given
+    /// size if both limits allow it, and consumes nothing otherwise.
+    pub fn allow(&mut self, bytes: u64) -> bool {
+        let ok = self.can_allow(bytes);
+        if ok {
+            self.consume(bytes);
+        }
+        ok
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_token_bucket() {
+        let mut b = TokenBucket::new(2, 4);
+        assert!(b.can_take(4));
+        b.take(3);
+        assert_eq!(b.tokens(), 1);
+        assert!(!b.can_take(2));
+        b.refill(1);
+        assert_eq!(b.tokens(), 3);
+        b.refill(10);
+        assert_eq!(b.tokens(), 4);
+
+        // a full bucket lets an oversized request through once.
+        assert!(b.can_take(10));
+        b.take(10);
+        assert_eq!(b.tokens(), 0);
+        assert!(!b.can_take(10));
+    }
+
+    #[test]
+    fn test_proposal_limiter() {
+        let mut l = ProposalLimiter::new(1, 10, 2);
+        assert!(l.allow(15));
+        // out of bytes, nothing is consumed.
+        assert!(!l.allow(10));
+        assert!(l.allow(5));
+        // out of proposals.
+        assert!(!l.allow(0));
+        l.tick(1);
+        assert!(l.allow(10));
+
+        let mut unlimited = ProposalLimiter::default();
+        for _ in 0..100 {
+            assert!(unlimited.allow(1 << 20));
+        }
+    }
+}
@@ -1,10 +1,16 @@
+use std::mem;
+
+use chunk::split_proposal;
 use errors::{Error, Result};
+use payload::Payload;
 use progress::Progress;
+use quorum::QuorumPolicy;
 use raft::{Config, Peer, Raft, StateType, Status, NONE};
 use raftpb::{
-    ConfChange, ConfChangeType, ConfState, Entry, EntryType, HardState, Message, MessageType,
-    Snapshot,
+    ConfChange, ConfChangeTransition, ConfChangeType, ConfChangeV2, ConfState, Entry, EntryType,
+    HardState, Message, MessageType, Snapshot,
 };
+use rate_limit::ProposalLimiter;
 use read_only::ReadState;
 use storage::Storage;
 use util::{is_empty_snap, is_local_msg, is_response_msg};
@@ -23,12 +29,21 @@ pub enum SnapshotStatus {
 pub struct SoftState {
     pub lead: u64,
     pub raft_state: StateType,
+    // lead_transferee is the target of the leadership transfer in progress
+    // on the leader, NONE otherwise.
+    pub lead_transferee: u64,
 }
 
+/// ConfChangeFilter inspects a committed ConfChange before it is applied and
+/// returns false to turn it into a no-op.
+pub type ConfChangeFilter = Box<dyn Fn(&ConfChange) -> bool + Send>;
+
 pub struct RawNode<T: Storage> {
     pub raft: Raft<T>,
     pub pre_soft_state: SoftState,
     pub pre_hard_state: HardState,
+    conf_change_filter: Option<ConfChangeFilter>,
+    proposal_limiter: ProposalLimiter,
 }
 
 /// Ready encapsulates the entries and messages that are ready to read,
@@ -76,15 +91,50 @@ pub struct Ready {
 }
 
 impl Ready {
+    /// contains_updates returns true if the Ready carries anything the
+    /// application has to persist, apply or send.
+    pub fn contains_updates(&self) -> bool {
+        self.soft_state.is_some()
+            || self.hard_state != HardState::new()
+            || !is_empty_snap(&self.snapshot)
+            || !self.entries.is_empty()
+            || !self.committed_entries.is_empty()
+            || !self.messages.is_empty()
+            || !self.read_states.is_empty()
+    }
+
+    // advance_state returns a Ready holding only what advance needs of this
+    // one, so that it can be kept for it without copying entries, snapshot
+    // data and messages.
+    pub(crate) fn advance_state(&self) -> Ready {
+        let position = |e: &Entry| {
+            let mut p = Entry::new();
+            p.set_index(e.get_index());
+            p.set_term(e.get_term());
+            p
+        };
+        let mut snapshot = Snapshot::new();
+        snapshot.set_metadata(self.snapshot.get_metadata().clone());
+        Ready {
+            soft_state: self.soft_state.clone(),
+            hard_state: self.hard_state.clone(),
+            read_states: self.read_states.iter().take(1).cloned().collect(),
+            entries: self.entries.last().map(position).into_iter().collect(),
+            snapshot,
+            committed_entries: self.committed_entries.last().map(position).into_iter().collect(),
+            ..Default::default()
+        }
+    }
+
     fn new<T: Storage>(
-        r: &Raft<T>,
+        r: &mut Raft<T>,
         prev_soft_state: &SoftState,
         prev_hard_state: &HardState,
     ) -> Ready {
         let mut rd = Ready {
             entries: r.raft_log.unstable_entries(),
             committed_entries: r.raft_log.next_ents(),
-            messages: r.msgs.clone(),
+            messages: mem::take(&mut r.msgs),
             ..Default::default()
         };
         let ss = r.soft_state();
@@ -92,6 +142,7 @@ impl Ready {
             rd.soft_state = Some(ss);
         }
         let hs = r.hard_state();
+        let extensions_changed = hs.get_extensions() != prev_hard_state.get_extensions();
         if &hs != prev_hard_state {
             rd.hard_state = hs;
         }
@@ -104,7 +155,8 @@ impl Ready {
 
         rd.must_sync = !rd.entries.is_empty()
             || rd.hard_state.get_vote() != prev_hard_state.get_vote()
-            || rd.hard_state.get_term() != prev_hard_state.get_term();
+            || rd.hard_state.get_term() != prev_hard_state.get_term()
+            || extensions_changed;
         rd
     }
 }
@@ -119,6 +171,12 @@ impl<T: Storage> RawNode<T> {
             raft: r,
             pre_soft_state: Default::default(),
             pre_hard_state: Default::default(),
+            conf_change_filter: None,
+            proposal_limiter: ProposalLimiter::new(
+                c.max_proposals_per_tick,
+                c.max_proposal_bytes_per_tick,
+                c.proposal_burst_ticks,
+            ),
         };
 
         let last_index = rn.raft.raft_log.get_storage().last_index().unwrap();
@@ -160,22 +218,79 @@ impl<T: Storage> RawNode<T> {
 
     // tick advances the internal logical clock by a single tick.
     pub fn tick(&mut self) {
+        self.proposal_limiter.tick(1);
         self.raft.tick();
     }
 
-    // propose proposes data be appended to the raft log.
-    pub fn propose(&mut self, data: Vec<u8>) -> Result<()> {
+    /// next_timeout returns the number of ticks until the next timeout, see
+    /// Raft::next_timeout.
+    pub fn next_timeout(&self) -> Option<u64> {
+        self.raft.next_timeout()
+    }
+
+    /// on_timeout advances the logical clock up to the next timeout, see
+    /// Raft::on_timeout.
+    pub fn on_timeout(&mut self) {
+        if let Some(ticks) = self.raft.next_timeout() {
+            self.proposal_limiter.tick(ticks);
+        }
+        self.raft.on_timeout();
+    }
+
+    // propose proposes data be appended to the raft log. The data can be any
+    // Payload. The context is stored in the entry next to the data and handed
+    // back with it once committed. Proposals are dropped with ProposalDropped
+    // when there is no leader to forward them to or the leader can not accept
+    // them, and can then be retried. Only the proposals which are not dropped
+    // count against the rate limits.
+    pub fn propose<P: Payload>(&mut self, context: Vec<u8>, data: P) -> Result<()> {
+        let data = data.into_data();
+        let size = data.len() as u64;
+        if !self.proposal_limiter.can_allow(size) {
+            return Err(Error::ProposalRateLimited);
+        }
         let mut m = Message::new();
         m.set_msg_type(MessageType::MsgProp);
         m.set_from(self.raft.id);
         let mut e = Entry::new();
         e.set_data(data);
+        e.set_context(context);
         m.set_entries(RepeatedField::from_vec(vec![e]));
-        self.raft.step(m)
+        self.raft.step(m)?;
+        self.proposal_limiter.consume(size);
+        Ok(())
+    }
+
+    // propose_chunked proposes data split into chunks of at most max_chunk_size
+    // bytes with split_proposal. All chunks are proposed in a single message,
+    // so they are either all dropped or appended to the log back to back.
+    pub fn propose_chunked(&mut self, id: u64, data: &[u8], max_chunk_size: usize) -> Result<()> {
+        if !self.proposal_limiter.can_allow(data.len() as u64) {
+            return Err(Error::ProposalRateLimited);
+        }
+        let mut m = Message::new();
+        m.set_msg_type(MessageType::MsgProp);
+        m.set_from(self.raft.id);
+        let ents = split_proposal(id, data, max_chunk_size)
+            .into_iter()
+            .map(|chunk| {
+                let mut e = Entry::new();
+                e.set_data(chunk);
+                e
+            }).collect();
+        m.set_entries(RepeatedField::from_vec(ents));
+        self.raft.step(m)?;
+        self.proposal_limiter.consume(data.len() as u64);
+        Ok(())
     }
 
-    // propose_conf_change proposes a config change.
+    // propose_conf_change proposes a config change. A change which is invalid
+    // in the current config, see Raft::check_conf_change, returns
+    // ConfChangeInvalid instead of being proposed.
     pub fn propose_conf_change(&mut self, cc: &ConfChange) -> Result<()> {
+        if cc.get_node_id() != NONE && !self.raft.is_joint() {
+            self.raft.check_conf_change(cc.get_change_type(), cc.get_node_id())?;
+        }
         let data = protobuf::Message::write_to_bytes(cc)?;
         let mut m = Message::new();
         m.set_msg_type(MessageType::MsgProp);
@@ -186,6 +301,51 @@ impl<T: Storage> RawNode<T> {
         self.raft.step(m)
     }
 
+    // propose_conf_change_v2 proposes a set of config changes, applied through
+    // a joint config when needed, or leaving the current joint config if the
+    // set is empty. A set which is invalid in the current config returns
+    // ConfChangeInvalid instead of being proposed, like propose_conf_change.
+    pub fn propose_conf_change_v2(&mut self, cc: &ConfChangeV2) -> Result<()> {
+        self.check_conf_change_v2(cc)?;
+        let data = protobuf::Message::write_to_bytes(cc)?;
+        let mut m = Message::new();
+        m.set_msg_type(MessageType::MsgProp);
+        let mut e = Entry::new();
+        e.set_entry_type(EntryType::EntryConfChangeV2);
+        e.set_data(data);
+        m.set_entries(RepeatedField::from_vec(vec![e]));
+        self.raft.step(m)
+    }
+
+    // check_conf_change_v2 runs the checks apply_conf_change_v2 would run on
+    // the given set, against the current config.
+    fn check_conf_change_v2(&self, cc: &ConfChangeV2) -> Result<()> {
+        let changes = cc.get_changes();
+        if changes.is_empty() {
+            if !self.raft.is_joint() {
+                return Err(Error::ConfChangeInvalid(
+                    "can't leave a non-joint config".to_string(),
+                ));
+            }
+            return Ok(());
+        }
+        if cc.get_transition() != ConfChangeTransition::ConfChangeTransitionAuto
+            || changes.len() != 1
+        {
+            return self.raft.check_enter_joint(changes);
+        }
+        if self.raft.is_joint() {
+            return Err(Error::ConfChangeInvalid(
+                "can't apply a simple config change in a joint config".to_string(),
+            ));
+        }
+        if changes[0].get_node_id() == NONE {
+            return Ok(());
+        }
+        self.raft
+            .check_conf_change(changes[0].get_change_type(), changes[0].get_node_id())
+    }
+
     pub fn step(&mut self, msg: Message) -> Result<()> {
         if is_local_msg(msg.get_msg_type()) {
             return Err(Error::StepLocalMsg);
@@ -198,8 +358,10 @@ impl<T: Storage> RawNode<T> {
         self.raft.step(msg)
     }
 
-    pub fn ready(&self) -> Ready {
-        Ready::new(&self.raft, &self.pre_soft_state, &self.pre_hard_state)
+    // ready returns the current point-in-time state. The messages it contains
+    // are handed over to the caller and will not be returned again.
+    pub fn ready(&mut self) -> Ready {
+        Ready::new(&mut self.raft, &self.pre_soft_state, &self.pre_hard_state)
     }
 
     pub fn advance(&mut self, rd: Ready) {
@@ -223,19 +385,30 @@ impl<T: Storage> RawNode<T> {
             // committed entries (and previously-committed entries may be
             // incorporated into the snapshot, even if rd.CommittedEntries is
             // empty). Therefore we mark all committed entries as applied
-            // whether they were included in rd.HardState or not.
-            self.raft.raft_log.applied_to(self.pre_hard_state.commit);
+            // whether they were included in rd.HardState or not, unless the
+            // committed entries were cut short by max_committed_size_per_ready,
+            // in which case the next Ready continues after the last of them.
+            let applied = match rd.committed_entries.last() {
+                Some(e) => e.get_index(),
+                None => self.pre_hard_state.commit,
+            };
+            self.raft.raft_log.applied_to(applied);
+            self.raft.on_applied();
         }
 
+        let persisted = self.raft.raft_log.persisted;
         if !rd.entries.is_empty() {
             let e = &rd.entries[rd.entries.len() - 1];
             self.raft.raft_log.stable_to(e.get_index(), e.get_term());
         }
-        if is_empty_snap(&rd.snapshot) {
+        if !is_empty_snap(&rd.snapshot) {
             self.raft
                 .raft_log
                 .stable_snap_to(rd.snapshot.get_metadata().get_index());
         }
+        if self.raft.raft_log.persisted != persisted {
+            self.raft.on_persisted();
+        }
         if !rd.read_states.is_empty() {
             self.raft.read_states.clear();
         }
@@ -254,15 +427,67 @@ impl<T: Storage> RawNode<T> {
         let _ = self.raft.step(m);
     }
 
-    // apply_conf_change applies a config change to the local node.
-    pub fn apply_conf_change(&mut self, cc: &ConfChange) -> ConfState {
+    /// set_conf_change_filter registers a callback that is consulted by
+    /// apply_conf_change before a committed ConfChange is applied. If the
+    /// callback returns false the change is ignored and the current ConfState
+    /// is returned, e.g. when the change carries a stale application epoch in
+    /// its context.
+    pub fn set_conf_change_filter<F>(&mut self, f: F)
+    where
+        F: Fn(&ConfChange) -> bool + Send + 'static,
+    {
+        self.conf_change_filter = Some(Box::new(f));
+    }
+
+    /// set_quorum_policy replaces the simple majority quorum, see
+    /// Raft::set_quorum_policy.
+    pub fn set_quorum_policy<P>(&mut self, policy: P)
+    where
+        P: QuorumPolicy + 'static,
+    {
+        self.raft.set_quorum_policy(policy);
+    }
+
+    // apply_conf_change applies a config change to the local node. Adding a
+    // node already in the set it is added to changes nothing, as happens when
+    // committed entries are replayed. Any other change that
+    // Raft::check_conf_change rejects is not applied and returns
+    // ConfChangeInvalid.
+    pub fn apply_conf_change(&mut self, cc: &ConfChange) -> Result<ConfState> {
         if cc.get_node_id() == NONE {
-            let mut cs = ConfState::new();
-            cs.set_nodes(self.raft.nodes());
-            cs.set_learners(self.raft.learner_nodes());
-            return cs;
+            return Ok(self.raft.conf_state());
+        }
+        if self.raft.is_joint() {
+            error!(
+                "{} {} ignored conf change {:?} in a joint config",
+                self.raft.tag, self.raft.id, cc
+            );
+            return Ok(self.raft.conf_state());
+        }
+
+        if let Some(ref filter) = self.conf_change_filter {
+            if !filter(cc) {
+                info!(
+                    "{} {} ignored conf change {:?} rejected by application filter",
+                    self.raft.tag, self.raft.id, cc
+                );
+                return Ok(self.raft.conf_state());
+            }
+        }
+
+        let id = cc.get_node_id();
+        let applied = match cc.get_change_type() {
+            ConfChangeType::ConfChangeAddNode => self.raft.tracker.prs.contains_key(&id),
+            ConfChangeType::ConfChangeAddLearnerNode => {
+                self.raft.tracker.learner_prs.contains_key(&id)
+            }
+            _ => false,
+        };
+        if applied {
+            return Ok(self.raft.conf_state());
         }
 
+        self.raft.check_conf_change(cc.get_change_type(), id)?;
         match cc.get_change_type() {
             ConfChangeType::ConfChangeAddNode => {
                 self.raft.add_node(cc.get_node_id());
@@ -276,10 +501,107 @@ impl<T: Storage> RawNode<T> {
             ConfChangeType::ConfChangeUpdateNode => {}
         }
 
-        let mut cs = ConfState::new();
-        cs.set_nodes(self.raft.nodes());
-        cs.set_learners(self.raft.learner_nodes());
-        cs
+        Ok(self.raft.conf_state())
+    }
+
+    // apply_conf_change_v2 applies a set of config changes to the local node.
+    // A single change with the auto transition is applied directly, like
+    // apply_conf_change; other sets enter a joint config, which an empty set
+    // leaves. The conf change filter sees every change of the set, each with
+    // the context of the set, and all of them are ignored if it rejects one.
+    pub fn apply_conf_change_v2(&mut self, cc: &ConfChangeV2) -> Result<ConfState> {
+        let changes = cc.get_changes();
+        if changes.is_empty() {
+            self.raft.leave_joint()?;
+            return Ok(self.raft.conf_state());
+        }
+
+        if let Some(ref filter) = self.conf_change_filter {
+            let rejected = changes.iter().any(|c| {
+                let mut v1 = ConfChange::new();
+                v1.set_change_type(c.get_change_type());
+                v1.set_node_id(c.get_node_id());
+                v1.set_context(cc.get_context().to_vec());
+                !filter(&v1)
+            });
+            if rejected {
+                info!(
+                    "{} {} ignored conf change {:?} rejected by application filter",
+                    self.raft.tag, self.raft.id, cc
+                );
+                return Ok(self.raft.conf_state());
+            }
+        }
+
+        if cc.get_transition() == ConfChangeTransition::ConfChangeTransitionAuto
+            && changes.len() == 1
+        {
+            if self.raft.is_joint() {
+                return Err(Error::ConfChangeInvalid(
+                    "can't apply a simple config change in a joint config".to_string(),
+                ));
+            }
+            let mut v1 = ConfChange::new();
+            v1.set_change_type(changes[0].get_change_type());
+            v1.set_node_id(changes[0].get_node_id());
+            v1.set_context(cc.get_context().to_vec());
+            return self.apply_conf_change(&v1);
+        }
+
+        let auto_leave =
+            cc.get_transition() != ConfChangeTransition::ConfChangeTransitionJointExplicit;
+        self.raft.enter_joint(auto_leave, changes)?;
+        Ok(self.raft.conf_state())
+    }
+
+    /// apply_committed_entries walks the given committed entries in order,
+    /// decodes and applies the conf changes with apply_conf_change, and hands
+    /// every normal entry to f, so that the application can't forget to apply
+    /// a conf change. It returns the ConfState after the last applied conf
+    /// change, if there was any, for the application to persist.
+    ///
+    /// A committed conf change which is invalid is skipped on every node
+    /// alike, so it is logged and the following entries are still applied.
+    /// Only an entry which can't be decoded returns an error.
+    pub fn apply_committed_entries<F>(
+        &mut self,
+        ents: &[Entry],
+        mut f: F,
+    ) -> Result<Option<ConfState>>
+    where
+        F: FnMut(&Entry),
+    {
+        let mut conf_state = None;
+        for e in ents {
+            match e.get_entry_type() {
+                EntryType::EntryNormal => f(e),
+                EntryType::EntryConfChange => {
+                    let cc: ConfChange = protobuf::parse_from_bytes(e.get_data())?;
+                    match self.apply_conf_change(&cc) {
+                        Ok(cs) => conf_state = Some(cs),
+                        Err(err) => self.ignore_conf_change(e, &err),
+                    }
+                }
+                EntryType::EntryConfChangeV2 => {
+                    let cc: ConfChangeV2 = protobuf::parse_from_bytes(e.get_data())?;
+                    match self.apply_conf_change_v2(&cc) {
+                        Ok(cs) => conf_state = Some(cs),
+                        Err(err) => self.ignore_conf_change(e, &err),
+                    }
+                }
+            }
+        }
+        Ok(conf_state)
+    }
+
+    fn ignore_conf_change(&self, e: &Entry, err: &Error) {
+        warn!(
+            "{} {} ignored committed conf change at index {}: {}",
+            self.raft.tag,
+            self.raft.id,
+            e.get_index(),
+            err
+        );
     }
 
     /// Campaign causes this RawNode to transition to candidate state.
@@ -289,6 +611,17 @@ impl<T: Storage> RawNode<T> {
         self.raft.step(m)
     }
 
+    /// forget_leader makes a follower forget its current leader, so that it
+    /// grants votes right away instead of waiting for the election timeout,
+    /// e.g. when the leader is known to be dead. It is ignored by candidates
+    /// and leaders, and when read_only_option is LeaseBased, since the lease
+    /// relies on followers not voting while they have a leader.
+    pub fn forget_leader(&mut self) -> Result<()> {
+        let mut m = Message::new();
+        m.set_msg_type(MessageType::MsgForgetLeader);
+        self.raft.step(m)
+    }
+
     /// HasReady called when RawNode user need to check if any Ready pending.
     pub fn has_ready(&self) -> bool {
         if self.raft.soft_state() != self.pre_soft_state {
@@ -340,6 +673,12 @@ impl<T: Storage> RawNode<T> {
         let _ = self.raft.step(m).is_ok();
     }
 
+    /// snapshot_ready reports that a snapshot which was temporarily
+    /// unavailable has been generated, see Raft::snapshot_ready.
+    pub fn snapshot_ready(&mut self, index: u64) {
+        self.raft.snapshot_ready(index);
+    }
+
     /// transfer_leader tries to transfer leadership to the given transferee.
     pub fn transfer_leader(&mut self, transferee: u64) {
         let mut m = Message::new();
@@ -348,6 +687,67 @@ impl<T: Storage> RawNode<T> {
         let _ = self.raft.step(m);
     }
 
+    /// abort_leader_transfer gives up a leadership transfer in progress, so
+    /// that the leader accepts proposals again right away instead of waiting
+    /// for the transfer to time out after an election timeout.
+    pub fn abort_leader_transfer(&mut self) {
+        if self.raft.lead_transferee == NONE {
+            return;
+        }
+        info!(
+            "{} {} [term {}] aborted transferring leadership to {}",
+            self.raft.tag, self.raft.id, self.raft.term, self.raft.lead_transferee
+        );
+        self.raft.abort_leader_transfer();
+    }
+
+    /// transfer_leadership_auto transfers leadership to the most caught up
+    /// follower and returns it, see Raft::transfer_leadership_auto.
+    pub fn transfer_leadership_auto(&mut self) -> Option<u64> {
+        self.raft.transfer_leadership_auto()
+    }
+
+    /// replace_node replaces the voter old by new, adding new as a learner
+    /// until it catches up, see Raft::replace_node.
+    pub fn replace_node(&mut self, old: u64, new: u64) -> Result<()> {
+        self.raft.replace_node(old, new)
+    }
+
+    /// pause_replication stops replicating entries to the given peer until
+    /// resume_replication is called, see Raft::pause_replication.
+    pub fn pause_replication(&mut self, id: u64) -> bool {
+        self.raft.pause_replication(id)
+    }
+
+    /// resume_replication resumes replicating entries to the given peer.
+    pub fn resume_replication(&mut self, id: u64) -> bool {
+        self.raft.resume_replication(id)
+    }
+
+    /// set_append_budget sets the max size of the appends this node wants to
+    /// receive from the leader, see Raft::set_append_budget.
+    pub fn set_append_budget(&mut self, bytes: u64) {
+        self.raft.set_append_budget(bytes);
+    }
+
+    /// report_send_queue reports whether the transport's send queue to the
+    /// given peer is full, see Raft::report_send_queue.
+    pub fn report_send_queue(&mut self, id: u64, full: bool) -> bool {
+        self.raft.report_send_queue(id, full)
+    }
+
+    /// force_probe makes the leader probe the log of the given peer again, see
+    /// Raft::force_probe.
+    pub fn force_probe(&mut self, id: u64) -> bool {
+        self.raft.force_probe(id)
+    }
+
+    /// force_snapshot makes the leader send its latest snapshot to the given
+    /// peer, see Raft::force_snapshot.
+    pub fn force_snapshot(&mut self, id: u64) -> bool {
+        self.raft.force_snapshot(id)
+    }
+
     /// status returns the current status of the given group.
     pub fn status(&self) -> Status {
         self.raft.get_status()
@@ -79,13 +79,13 @@ impl ReadOnly {
 
     // recv_ack notifies the readonly struct that the raft state machine received
     // an acknowledgment of the heartbeat that attached with the read only request
-    // context.
-    pub(crate) fn recv_ack(&mut self, msg: &Message) -> usize {
+    // context. It returns the peers which acknowledged it so far.
+    pub(crate) fn recv_ack(&mut self, msg: &Message) -> Vec<u64> {
         if let Some(rs) = self.pending_read_index.get_mut(msg.get_context()) {
             rs.acks.insert(msg.get_from());
-            rs.acks.len() + 1
+            rs.acks.iter().cloned().collect()
         } else {
-            0
+            vec![]
         }
     }
 
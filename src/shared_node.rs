@@ -0,0 +1,131 @@
+use std::sync::{Arc, Mutex, MutexGuard, TryLockError};
+
+use errors::{Error, Result};
+use payload::Payload;
+use raftpb::Message;
+use raw_node::RawNode;
+use storage::Storage;
+
+/// SharedRawNode serializes the access of several threads to a RawNode. A
+/// Ready should be handled, from ready to advance, within one with_node call.
+pub struct SharedRawNode<T: Storage> {
+    node: Arc<Mutex<RawNode<T>>>,
+}
+
+impl<T: Storage> Clone for SharedRawNode<T> {
+    fn clone(&self) -> SharedRawNode<T> {
+        SharedRawNode {
+            node: self.node.clone(),
+        }
+    }
+}
+
+impl<T: Storage> SharedRawNode<T> {
+    pub fn new(node: RawNode<T>) -> SharedRawNode<T> {
+        SharedRawNode {
+            node: Arc::new(Mutex::new(node)),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, RawNode<T>> {
+        self.node.lock().unwrap()
+    }
+
+    /// with_node runs f with exclusive access to the node, blocking until no
+    /// other thread is using it.
+    pub fn with_node<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce(&mut RawNode<T>) -> R,
+    {
+        f(&mut self.lock())
+    }
+
+    /// step advances the state machine using the given message.
+    pub fn step(&self, msg: Message) -> Result<()> {
+        self.lock().step(msg)
+    }
+
+    /// propose proposes data be appended to the raft log, blocking until no
+    /// other thread is using the node.
+    pub fn propose<P: Payload>(&self, context: Vec<u8>, data: P) -> Result<()> {
+        self.lock().propose(context, data)
+    }
+
+    /// try_propose proposes data be appended to the raft log if the node is
+    /// not in use by another thread, e.g. processing a Ready, and returns
+    /// ProposalDropped otherwise, so that the caller can retry later instead
+    /// of waiting.
+    pub fn try_propose<P: Payload>(&self, context: Vec<u8>, data: P) -> Result<()> {
+        match self.node.try_lock() {
+            Ok(mut node) => node.propose(context, data),
+            Err(TryLockError::WouldBlock) => Err(Error::ProposalDropped),
+            Err(TryLockError::Poisoned(e)) => panic!("raw node lock poisoned: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use raft::{Config, Peer};
+    use std::thread;
+    use storage::MemStorage;
+    use util::NO_LIMIT;
+
+    fn new_shared_leader() -> SharedRawNode<MemStorage> {
+        let mut s = MemStorage::new();
+        let mut c = Config {
+            id: 1,
+            election_tick: 10,
+            heartbeat_tick: 1,
+            max_size_per_msg: NO_LIMIT,
+            max_inflight_msgs: 256,
+            ..Default::default()
+        };
+        let peers = vec![Peer {
+            id: 1,
+            context: vec![],
+        }];
+        let node = SharedRawNode::new(RawNode::new(&mut c, s.clone(), peers).unwrap());
+        node.with_node(|n| {
+            let rd = n.ready();
+            s.append(&rd.entries).unwrap();
+            n.advance(rd);
+            n.campaign().unwrap();
+            let rd = n.ready();
+            s.append(&rd.entries).unwrap();
+            n.advance(rd);
+        });
+        node
+    }
+
+    #[test]
+    fn test_shared_raw_node_try_propose() {
+        let node = new_shared_leader();
+        let other = node.clone();
+        node.with_node(|_| {
+            assert_eq!(other.try_propose(vec![], b"foo".to_vec()), Err(Error::ProposalDropped));
+        });
+        other.try_propose(vec![], b"foo".to_vec()).unwrap();
+        node.with_node(|n| {
+            let rd = n.ready();
+            assert_eq!(rd.entries.len(), 1);
+            assert_eq!(rd.entries[0].get_data(), b"foo");
+        });
+    }
+
+    #[test]
+    fn test_shared_raw_node_threads() {
+        let node = new_shared_leader();
+        let handles: Vec<_> = (0..4)
+            .map(|i| {
+                let node = node.clone();
+                thread::spawn(move || node.propose(vec![], vec![i]).unwrap())
+            }).collect();
+        for h in handles {
+            h.join().unwrap();
+        }
+        let rd = node.with_node(|n| n.ready());
+        assert_eq!(rd.entries.len(), 4);
+    }
+}
@@ -2,8 +2,9 @@ use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
 use std::u64;
 
 use errors::{Error, Result, StorageError};
+use protobuf::Message;
 use raftpb::{ConfState, Entry, HardState, Snapshot};
-use util::limit_size;
+use util::{entries_size, limit_size};
 
 pub trait Storage {
     /// initial_state returns the saved HardState and ConfState information.
@@ -34,12 +35,30 @@ pub trait Storage {
     /// so raft state machine could know that Storage needs some time to prepare
     /// snapshot and call snapshot later.
     fn snapshot(&self) -> Result<Snapshot>;
+
+    /// applied_index returns the last applied index persisted by a durable
+    /// state machine, or 0 if it isn't persisted. raft uses it as
+    /// Config.applied when that is unset, so that entries applied before a
+    /// restart are not handed out again.
+    fn applied_index(&self) -> Result<u64> {
+        Ok(0)
+    }
+
+    /// log_size returns the total size in bytes of the entries available via
+    /// entries, or 0 if the storage doesn't keep track of it. It can be used
+    /// by size based snapshot and compaction policies.
+    fn log_size(&self) -> Result<u64> {
+        Ok(0)
+    }
 }
 
 pub struct MemStorageCore {
     hard_state: HardState,
     snapshot: Snapshot,
     entries: Vec<Entry>,
+    applied: u64,
+    // total size of entries[1..], the dummy entry is not counted.
+    entries_size: u64,
 }
 
 impl Default for MemStorageCore {
@@ -49,6 +68,8 @@ impl Default for MemStorageCore {
             entries: vec![Entry::new()],
             hard_state: HardState::new(),
             snapshot: Snapshot::new(),
+            applied: 0,
+            entries_size: 0,
         }
     }
 }
@@ -63,6 +84,11 @@ impl MemStorageCore {
         self.hard_state = hs;
     }
 
+    /// set_applied saves the last applied index, see Storage::applied_index.
+    pub fn set_applied(&mut self, applied: u64) {
+        self.applied = applied;
+    }
+
     /// apply_snapshot overwrites the contents of this Storage object with
     /// those of the given snapshot.
     pub fn apply_snapshot(&mut self, snapshot: Snapshot) -> Result<()> {
@@ -77,6 +103,7 @@ impl MemStorageCore {
         e.set_term(snapshot.get_metadata().get_term());
         e.set_index(snapshot.get_metadata().get_index());
         self.entries = vec![e];
+        self.entries_size = 0;
         self.snapshot = snapshot;
         Ok(())
     }
@@ -99,6 +126,8 @@ impl MemStorageCore {
         }
 
         let i = (compact_index - offset) as usize;
+        // entries[i] becomes the new dummy entry.
+        self.entries_size -= entries_size(&self.entries[1..=i]);
         let entries = self.entries.drain(i..).collect();
         self.entries = entries;
         Ok(())
@@ -131,11 +160,14 @@ impl MemStorageCore {
         let offset = te[0].get_index() - self.entries[0].get_index();
 
         if self.entries.len() as u64 > offset {
+            self.entries_size -= entries_size(&self.entries[offset as usize..]);
+            self.entries_size += entries_size(te);
             let mut new_entries: Vec<Entry> = vec![];
             new_entries.extend_from_slice(&self.entries[..offset as usize]);
             new_entries.extend_from_slice(te);
             self.entries = new_entries;
         } else if self.entries.len() as u64 == offset {
+            self.entries_size += entries_size(te);
             self.entries.extend_from_slice(te);
         } else {
             panic!(
@@ -208,6 +240,11 @@ impl MemStorage {
         self.write_lock().set_hard_state(hs);
     }
 
+    /// set_applied saves the last applied index.
+    pub fn set_applied(&mut self, applied: u64) {
+        self.write_lock().set_applied(applied);
+    }
+
     pub fn append(&mut self, ents: &[Entry]) -> Result<()> {
         self.write_lock().append(ents)
     }
@@ -289,12 +326,19 @@ impl Storage for MemStorage {
         let core = self.read_lock();
         Ok(core.snapshot.clone())
     }
+
+    fn applied_index(&self) -> Result<u64> {
+        Ok(self.read_lock().applied)
+    }
+
+    fn log_size(&self) -> Result<u64> {
+        Ok(self.read_lock().entries_size)
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    use protobuf::Message;
     use raftpb::SnapshotMetadata;
     use util::NO_LIMIT;
 
@@ -308,6 +352,7 @@ mod test {
     fn new_memory_storage(ents: Vec<Entry>) -> MemStorage {
         let mut s = MemStorage::new();
         let mut core = MemStorageCore::default();
+        core.entries_size = entries_size(ents.get(1..).unwrap_or(&[]));
         core.entries = ents;
         s.core = Arc::new(RwLock::new(core));
         s
@@ -545,6 +590,33 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_storage_log_size() {
+        let mut s = MemStorage::new();
+        assert_eq!(s.log_size(), Ok(0));
+
+        let ents = vec![new_entry(1, 1), new_entry(2, 1), new_entry(3, 1)];
+        s.append(&ents).unwrap();
+        assert_eq!(s.log_size(), Ok(entries_size(&ents)));
+
+        // truncate the conflicting tail.
+        let mut e = new_entry(3, 2);
+        e.set_data(b"data".to_vec());
+        s.append(&[e.clone()]).unwrap();
+        assert_eq!(
+            s.log_size(),
+            Ok(entries_size(&ents[..2]) + entries_size(&[e.clone()]))
+        );
+
+        // entry 2 becomes the dummy entry.
+        s.compact(2).unwrap();
+        assert_eq!(s.log_size(), Ok(entries_size(&[e])));
+
+        s.apply_snapshot(new_snapshot(5, 2, ConfState::new(), vec![]))
+            .unwrap();
+        assert_eq!(s.log_size(), Ok(0));
+    }
+
     #[test]
     fn test_storage_create_snapshot() {
         let ents = vec![new_entry(3, 3), new_entry(4, 4), new_entry(5, 5)];
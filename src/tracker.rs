@@ -0,0 +1,376 @@
+use std::collections::HashMap;
+
+use progress::Progress;
+use quorum::{JointConfig, MajorityConfig, MajorityQuorum, QuorumPolicy};
+use raftpb::ConfState;
+
+/// VoteResult is the outcome of an election so far.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum VoteResult {
+    /// Won means the granted votes form a quorum, of both configs while in a
+    /// joint config.
+    Won,
+    /// Lost means the election can't be won any more, even if all the voters
+    /// yet to respond grant their vote.
+    Lost,
+    /// Pending means the election is decided by the votes yet to come.
+    Pending,
+}
+
+/// ProgressTracker holds the Progress of the voters and learners, the votes
+/// received and the config they form, and takes its quorum decisions through
+/// the QuorumPolicy.
+#[derive(Default)]
+pub struct ProgressTracker {
+    // prs tracks the voters, of both configs while in a joint config.
+    pub prs: HashMap<u64, Progress>,
+    pub learner_prs: HashMap<u64, Progress>,
+    // votes records the vote of every voter which responded to the current
+    // campaign, true if it was granted.
+    pub votes: HashMap<u64, bool>,
+    // voters_incoming and voters_outgoing hold the voters of the incoming and
+    // outgoing configs while in a joint config, and are empty otherwise.
+    voters_incoming: Vec<u64>,
+    voters_outgoing: Vec<u64>,
+    // learners_next holds the outgoing voters demoted by the joint config,
+    // which stay in prs until it is left and then move to learner_prs.
+    learners_next: Vec<u64>,
+    // auto_leave is set when the joint config is to be left automatically.
+    pub auto_leave: bool,
+    // replaces the simple majority quorum if set, see set_quorum_policy.
+    quorum_policy: Option<Box<dyn QuorumPolicy>>,
+}
+
+impl ProgressTracker {
+    pub fn new() -> ProgressTracker {
+        Default::default()
+    }
+
+    /// voters returns the voters, or the voters of the incoming config while
+    /// in a joint config.
+    pub fn voters(&self) -> Vec<u64> {
+        if self.is_joint() {
+            return self.voters_incoming.clone();
+        }
+        let mut voters = self.voter_ids();
+        voters.sort();
+        voters
+    }
+
+    /// voter_ids returns the ids of all the voters tracked in prs, in no
+    /// particular order.
+    pub fn voter_ids(&self) -> Vec<u64> {
+        self.prs.keys().cloned().collect()
+    }
+
+    pub fn learners(&self) -> Vec<u64> {
+        let mut learners: Vec<u64> = self.learner_prs.keys().cloned().collect();
+        learners.sort();
+        learners
+    }
+
+    /// progress_ids returns the ids of the voters followed by those of the
+    /// learners, each in ascending order.
+    pub fn progress_ids(&self) -> Vec<u64> {
+        let mut ids = self.voter_ids();
+        ids.sort();
+        ids.extend(self.learners());
+        ids
+    }
+
+    /// visit calls f with the Progress of every peer, in the order of
+    /// progress_ids, so that walking the peers is deterministic.
+    pub fn visit<F>(&mut self, mut f: F)
+    where
+        F: FnMut(u64, &mut Progress),
+    {
+        for id in self.progress_ids() {
+            let pr = match self.prs.get_mut(&id) {
+                Some(pr) => pr,
+                None => self.learner_prs.get_mut(&id).unwrap(),
+            };
+            f(id, pr);
+        }
+    }
+
+    /// voters_outgoing returns the voters of the outgoing config while in a
+    /// joint config, and nothing otherwise.
+    pub fn voters_outgoing(&self) -> Vec<u64> {
+        self.voters_outgoing.clone()
+    }
+
+    /// learners_next returns the outgoing voters which become learners once
+    /// the joint config is left.
+    pub fn learners_next(&self) -> Vec<u64> {
+        self.learners_next.clone()
+    }
+
+    pub fn is_joint(&self) -> bool {
+        !self.voters_outgoing.is_empty()
+    }
+
+    /// conf_state returns the current config.
+    pub fn conf_state(&self) -> ConfState {
+        let mut cs = ConfState::new();
+        cs.set_nodes(self.voters());
+        cs.set_learners(self.learners());
+        cs.set_voters_outgoing(self.voters_outgoing());
+        cs.set_learners_next(self.learners_next());
+        cs.set_auto_leave(self.auto_leave);
+        cs
+    }
+
+    /// set_joint records the two configs of a joint config, if outgoing is not
+    /// empty, or leaves it otherwise. prs must already track both.
+    pub fn set_joint(
+        &mut self,
+        incoming: &[u64],
+        outgoing: &[u64],
+        learners_next: &[u64],
+        auto_leave: bool,
+    ) {
+        if outgoing.is_empty() {
+            self.voters_incoming.clear();
+            self.voters_outgoing.clear();
+            self.learners_next.clear();
+            self.auto_leave = false;
+            return;
+        }
+        self.voters_incoming = incoming.to_vec();
+        self.voters_incoming.sort();
+        self.voters_outgoing = outgoing.to_vec();
+        self.voters_outgoing.sort();
+        self.learners_next = learners_next.to_vec();
+        self.learners_next.sort();
+        self.auto_leave = auto_leave;
+    }
+
+    /// joint_config returns the majority configs of the voters, the outgoing
+    /// one being empty outside of a joint config.
+    pub fn joint_config(&self) -> JointConfig {
+        if self.is_joint() {
+            return JointConfig::new(
+                MajorityConfig::new(self.voters_incoming.clone()),
+                MajorityConfig::new(self.voters_outgoing.clone()),
+            );
+        }
+        JointConfig::new(MajorityConfig::new(self.voter_ids()), MajorityConfig::default())
+    }
+
+    /// set_quorum_policy replaces the simple majority quorum used to elect a
+    /// leader and to commit entries, see QuorumPolicy.
+    pub fn set_quorum_policy<P>(&mut self, policy: P)
+    where
+        P: QuorumPolicy + 'static,
+    {
+        self.quorum_policy = Some(Box::new(policy));
+    }
+
+    fn policy(&self) -> &dyn QuorumPolicy {
+        match self.quorum_policy {
+            Some(ref p) => p.as_ref(),
+            None => &MajorityQuorum,
+        }
+    }
+
+    /// is_election_quorum returns true if the granted votes win an election
+    /// among the given voters, or in both configs of a joint config.
+    pub fn is_election_quorum(&self, voters: &[u64], granted: &[u64]) -> bool {
+        let policy = self.policy();
+        if self.is_joint() {
+            return policy.is_election_quorum(&self.voters_incoming, granted)
+                && policy.is_election_quorum(&self.voters_outgoing, granted);
+        }
+        policy.is_election_quorum(voters, granted)
+    }
+
+    /// is_replication_quorum returns true if the acks commit an entry among
+    /// the given voters, or in both configs of a joint config.
+    pub fn is_replication_quorum(&self, voters: &[u64], acked: &[u64]) -> bool {
+        let policy = self.policy();
+        if self.is_joint() {
+            return policy.is_replication_quorum(&self.voters_incoming, acked)
+                && policy.is_replication_quorum(&self.voters_outgoing, acked);
+        }
+        policy.is_replication_quorum(voters, acked)
+    }
+
+    /// committed returns the highest index stored by a replication quorum of
+    /// the voters, according to their matched index.
+    pub fn committed(&self) -> u64 {
+        if self.prs.is_empty() {
+            return 0;
+        }
+        if self.quorum_policy.is_none() {
+            return self
+                .joint_config()
+                .committed_index(|id| self.prs.get(&id).map(|pr| pr.matched));
+        }
+        let mut matched: Vec<(u64, u64)> =
+            self.prs.iter().map(|(&id, p)| (p.matched, id)).collect();
+        matched.sort_by(|a, b| b.cmp(a));
+        // the voters matching at least an index are a prefix of matched.
+        let voters = self.voter_ids();
+        let mut acked = Vec::with_capacity(matched.len());
+        for (index, id) in matched {
+            acked.push(id);
+            if self.is_replication_quorum(&voters, &acked) {
+                return index;
+            }
+        }
+        0
+    }
+
+    /// quorum_active returns true if the voters which were recently active,
+    /// along with self_id, form a quorum, and resets recent_active for every
+    /// peer, so that the next check only sees the peers heard from again.
+    pub fn quorum_active(&mut self, self_id: u64) -> bool {
+        let mut active = vec![self_id];
+        for (&id, pr) in &mut self.prs {
+            if id != self_id && pr.recent_active {
+                active.push(id);
+            }
+            pr.recent_active = false;
+        }
+        for pr in self.learner_prs.values_mut() {
+            pr.recent_active = false;
+        }
+        self.is_replication_quorum(&self.voter_ids(), &active)
+    }
+
+    /// reset_votes forgets the votes of the previous campaign.
+    pub fn reset_votes(&mut self) {
+        self.votes.clear();
+    }
+
+    /// record_vote records the vote of id, granted or not. Only the first vote
+    /// of a voter counts.
+    pub fn record_vote(&mut self, id: u64, granted: bool) {
+        self.votes.entry(id).or_insert(granted);
+    }
+
+    /// tally_votes returns the number of granted and rejected votes so far.
+    pub fn tally_votes(&self) -> (usize, usize) {
+        let granted = self.votes.values().filter(|&&v| v).count();
+        (granted, self.votes.len() - granted)
+    }
+
+    /// vote_result returns the outcome of the election given the votes
+    /// recorded so far: Won as soon as a quorum granted, Lost as soon as no
+    /// quorum can grant any more, and Pending otherwise.
+    pub fn vote_result(&self) -> VoteResult {
+        let voters = self.voter_ids();
+        let granted: Vec<u64> = self
+            .votes
+            .iter()
+            .filter(|&(_, &v)| v)
+            .map(|(&id, _)| id)
+            .collect();
+        if self.is_election_quorum(&voters, &granted) {
+            return VoteResult::Won;
+        }
+        let possible: Vec<u64> = voters
+            .iter()
+            .filter(|id| self.votes.get(id) != Some(&false))
+            .cloned()
+            .collect();
+        if self.is_election_quorum(&voters, &possible) {
+            return VoteResult::Pending;
+        }
+        VoteResult::Lost
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn new_tracker(voters: &[(u64, u64)]) -> ProgressTracker {
+        let mut t = ProgressTracker::new();
+        for &(id, matched) in voters {
+            let pr = Progress {
+                matched,
+                ..Default::default()
+            };
+            t.prs.insert(id, pr);
+        }
+        t
+    }
+
+    #[test]
+    fn test_tracker_committed() {
+        let mut t = new_tracker(&[(1, 5), (2, 3), (3, 1)]);
+        assert_eq!(t.committed(), 3);
+
+        // both configs of a joint config must store the entry.
+        t.prs.insert(4, Progress::default());
+        t.set_joint(&[1, 4], &[1, 2, 3], &[], false);
+        assert_eq!(t.committed(), 0);
+        t.prs.get_mut(&4).unwrap().matched = 4;
+        assert_eq!(t.committed(), 3);
+    }
+
+    #[test]
+    fn test_tracker_votes() {
+        let mut t = new_tracker(&[(1, 0), (2, 0), (3, 0)]);
+        t.record_vote(1, true);
+        assert_eq!(t.vote_result(), VoteResult::Pending);
+        t.record_vote(2, false);
+        // only the first vote of a voter counts.
+        t.record_vote(2, true);
+        assert_eq!(t.tally_votes(), (1, 1));
+        assert_eq!(t.vote_result(), VoteResult::Pending);
+        t.record_vote(3, false);
+        assert_eq!(t.vote_result(), VoteResult::Lost);
+
+        t.reset_votes();
+        t.record_vote(1, true);
+        t.record_vote(3, true);
+        assert_eq!(t.vote_result(), VoteResult::Won);
+    }
+
+    #[test]
+    fn test_tracker_vote_result_joint() {
+        let mut t = new_tracker(&[(1, 0), (2, 0), (3, 0), (4, 0), (5, 0)]);
+        t.set_joint(&[1, 4, 5], &[1, 2, 3], &[], false);
+        t.record_vote(1, true);
+        t.record_vote(2, true);
+        // the outgoing config is won, the incoming one isn't yet.
+        assert_eq!(t.vote_result(), VoteResult::Pending);
+        t.record_vote(4, false);
+        t.record_vote(5, false);
+        // the incoming config is lost.
+        assert_eq!(t.vote_result(), VoteResult::Lost);
+
+        t.reset_votes();
+        t.record_vote(1, true);
+        t.record_vote(2, true);
+        t.record_vote(4, true);
+        assert_eq!(t.vote_result(), VoteResult::Won);
+    }
+
+    #[test]
+    fn test_tracker_visit() {
+        let mut t = new_tracker(&[(3, 0), (1, 0), (2, 0)]);
+        t.learner_prs.insert(5, Progress::default());
+        t.learner_prs.insert(4, Progress::default());
+        let mut visited = vec![];
+        t.visit(|id, pr| {
+            visited.push(id);
+            pr.matched = id;
+        });
+        assert_eq!(visited, vec![1, 2, 3, 4, 5]);
+        assert_eq!(t.prs[&3].matched, 3);
+        assert_eq!(t.learner_prs[&5].matched, 5);
+    }
+
+    #[test]
+    fn test_tracker_quorum_active() {
+        let mut t = new_tracker(&[(1, 0), (2, 0), (3, 0)]);
+        assert!(!t.quorum_active(1));
+        t.prs.get_mut(&2).unwrap().recent_active = true;
+        assert!(t.quorum_active(1));
+        // the check resets recent_active.
+        assert!(!t.quorum_active(1));
+    }
+}
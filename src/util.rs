@@ -1,6 +1,6 @@
 use std::u64;
 
-use raftpb::{Entry, EntryType, MessageType, Snapshot};
+use raftpb::{ConfChangeV2, Entry, EntryType, MessageType, Snapshot};
 
 use protobuf::Message;
 
@@ -28,10 +28,85 @@ pub fn limit_size<T: Message + Clone>(entries: &mut Vec<T>, max: u64) {
     entries.truncate(limit);
 }
 
+/// entries_size returns the total encoded size of the entries.
+pub fn entries_size(ents: &[Entry]) -> u64 {
+    ents.iter()
+        .map(|e| u64::from(Message::compute_size(e)))
+        .sum()
+}
+
+const CASTAGNOLI: u32 = 0x82f6_3b78;
+
+const fn crc32c_table() -> [u32; 256] {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut j = 0;
+        while j < 8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ CASTAGNOLI
+            } else {
+                crc >> 1
+            };
+            j += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+}
+
+static CRC32C_TABLE: [u32; 256] = crc32c_table();
+
+/// crc32c computes the CRC-32C (Castagnoli) checksum of the given data.
+pub fn crc32c(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &b in data {
+        crc = CRC32C_TABLE[((crc ^ u32::from(b)) & 0xff) as usize] ^ (crc >> 8);
+    }
+    !crc
+}
+
+/// set_entry_checksum fills in the checksum of the entry payload, unless one
+/// is already present. A payload whose CRC happens to be 0 is left without
+/// checksum, as 0 means none.
+pub fn set_entry_checksum(e: &mut Entry) {
+    if e.get_checksum() == 0 {
+        let checksum = crc32c(e.get_data());
+        e.set_checksum(checksum);
+    }
+}
+
+/// verify_entry_checksum returns false if the entry carries a checksum that
+/// does not match its payload. Entries without a checksum are always valid.
+///
+/// This is a best effort check with two blind spots. The checksum covers the
+/// data only, so corruption of the entry type or context goes undetected.
+/// And as a stored checksum of 0 means unset, corruption which zeroes the
+/// checksum field disables the check rather than failing it.
+pub fn verify_entry_checksum(e: &Entry) -> bool {
+    e.get_checksum() == 0 || e.get_checksum() == crc32c(e.get_data())
+}
+
+/// is_conf_change returns true if the entry holds a ConfChange or a
+/// ConfChangeV2.
+pub fn is_conf_change(e: &Entry) -> bool {
+    e.get_entry_type() == EntryType::EntryConfChange
+        || e.get_entry_type() == EntryType::EntryConfChangeV2
+}
+
+/// is_leave_joint returns true if the entry holds a ConfChangeV2 without
+/// changes, which leaves a joint config.
+pub fn is_leave_joint(e: &Entry) -> bool {
+    e.get_entry_type() == EntryType::EntryConfChangeV2
+        && protobuf::parse_from_bytes::<ConfChangeV2>(e.get_data())
+            .map(|cc| cc.get_changes().is_empty())
+            .unwrap_or(false)
+}
+
 pub fn num_of_pending_conf(ents: &[Entry]) -> u64 {
-    ents.into_iter()
-        .filter(|e| e.get_entry_type() == EntryType::EntryConfChange)
-        .count() as u64
+    ents.iter().filter(|e| is_conf_change(e)).count() as u64
 }
 
 pub fn vote_msg_resp_type(t: MessageType) -> MessageType {
@@ -48,6 +123,7 @@ pub fn is_local_msg(msgt: MessageType) -> bool {
         || msgt == MessageType::MsgUnreachable
         || msgt == MessageType::MsgSnapStatus
         || msgt == MessageType::MsgCheckQuorum
+        || msgt == MessageType::MsgForgetLeader
 }
 
 pub fn is_response_msg(msgt: MessageType) -> bool {
@@ -58,6 +134,42 @@ pub fn is_response_msg(msgt: MessageType) -> bool {
         || msgt == MessageType::MsgUnreachable
 }
 
+/// MessagePriority classifies messages so that transports can keep separate
+/// queues and let elections through even when bulk replication saturates
+/// the link. Variants are ordered from the lowest to the highest priority.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum MessagePriority {
+    Snapshot,
+    Append,
+    Heartbeat,
+    Election,
+}
+
+pub fn msg_priority(msgt: MessageType) -> MessagePriority {
+    match msgt {
+        MessageType::MsgHup
+        | MessageType::MsgVote
+        | MessageType::MsgVoteResp
+        | MessageType::MsgPreVote
+        | MessageType::MsgPreVoteResp
+        | MessageType::MsgTimeoutNow
+        | MessageType::MsgTransferLeader
+        | MessageType::MsgForgetLeader => MessagePriority::Election,
+        MessageType::MsgBeat
+        | MessageType::MsgHeartbeat
+        | MessageType::MsgHeartbeatResp
+        | MessageType::MsgCheckQuorum
+        | MessageType::MsgReadIndex
+        | MessageType::MsgReadIndexResp => MessagePriority::Heartbeat,
+        MessageType::MsgProp
+        | MessageType::MsgApp
+        | MessageType::MsgAppResp
+        | MessageType::MsgUnreachable
+        | MessageType::MsgSnapStatus => MessagePriority::Append,
+        MessageType::MsgSnap => MessagePriority::Snapshot,
+    }
+}
+
 pub fn is_empty_snap(snap: &Snapshot) -> bool {
     snap.get_metadata().get_index() == 0 
 }
@@ -88,6 +200,7 @@ mod test {
             (MessageType::MsgReadIndexResp, false),
             (MessageType::MsgPreVote, false),
             (MessageType::MsgPreVoteResp, false),
+            (MessageType::MsgForgetLeader, true),
         ];
 
         for (msgt, is_local) in tests {
@@ -137,6 +250,50 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_msg_priority() {
+        let tests = vec![
+            (MessageType::MsgVote, MessagePriority::Election),
+            (MessageType::MsgPreVoteResp, MessagePriority::Election),
+            (MessageType::MsgTimeoutNow, MessagePriority::Election),
+            (MessageType::MsgHeartbeat, MessagePriority::Heartbeat),
+            (MessageType::MsgHeartbeatResp, MessagePriority::Heartbeat),
+            (MessageType::MsgReadIndex, MessagePriority::Heartbeat),
+            (MessageType::MsgApp, MessagePriority::Append),
+            (MessageType::MsgAppResp, MessagePriority::Append),
+            (MessageType::MsgProp, MessagePriority::Append),
+            (MessageType::MsgSnap, MessagePriority::Snapshot),
+        ];
+
+        for (msgt, priority) in tests {
+            assert_eq!(msg_priority(msgt), priority);
+        }
+
+        assert!(MessagePriority::Election > MessagePriority::Heartbeat);
+        assert!(MessagePriority::Heartbeat > MessagePriority::Append);
+        assert!(MessagePriority::Append > MessagePriority::Snapshot);
+    }
+
+    #[test]
+    fn test_crc32c() {
+        assert_eq!(crc32c(b""), 0);
+        assert_eq!(crc32c(b"123456789"), 0xe306_9283);
+    }
+
+    #[test]
+    fn test_entry_checksum() {
+        let mut e = new_entry(1, 1);
+        e.set_data(b"somedata".to_vec());
+        assert!(verify_entry_checksum(&e));
+
+        set_entry_checksum(&mut e);
+        assert_eq!(e.get_checksum(), crc32c(b"somedata"));
+        assert!(verify_entry_checksum(&e));
+
+        e.mut_data()[0] = b'S';
+        assert!(!verify_entry_checksum(&e));
+    }
+
     #[test]
     fn test_limit_size() {
         let ents = vec![new_entry(4, 4), new_entry(5, 5), new_entry(6, 6)];
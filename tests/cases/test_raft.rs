@@ -5,18 +5,19 @@ use std::ops::DerefMut;
 
 use libraft::errors::{Error, Result};
 use libraft::log_unstable::Unstable;
-use libraft::progress::{Inflights, Progress, ProgressState};
-use libraft::raft::{Config, Raft, StateType, NONE};
+use libraft::progress::{Inflights, PausedReason, Progress, ProgressState};
+use libraft::quorum::{FlexibleQuorum, QuorumPolicy};
+use libraft::raft::{CampaignType, Config, Raft, StateType, NONE};
 use libraft::raft_log::RaftLog;
 use libraft::raftpb::{
-    ConfChange, ConfChangeType, ConfState, Entry, EntryType, HardState, Message, MessageType,
-    Snapshot, SnapshotMetadata,
+    ConfChange, ConfChangeSingle, ConfChangeType, ConfChangeV2, ConfState, Entry, EntryType,
+    HardState, Message, MessageType, Snapshot, SnapshotMetadata,
 };
 use libraft::read_only::ReadOnlyOption;
 use libraft::storage::{MemStorage, Storage};
-use libraft::util::{vote_msg_resp_type, NO_LIMIT};
+use libraft::util::{crc32c, vote_msg_resp_type, NO_LIMIT};
 
-use protobuf::{self, RepeatedField};
+use protobuf::{self, Message as PbMessage, RepeatedField};
 use rand::{self, Rng};
 
 pub fn new_snapshot(index: u64, term: u64, learners: Vec<u64>, nodes: Vec<u64>) -> Snapshot {
@@ -236,12 +237,12 @@ impl Network {
                     if p.raft.is_some() {
                         p.id = id;
                         let learner_prs = p.take_learner_prs();
-                        p.learner_prs = HashMap::new();
-                        p.prs = HashMap::new();
+                        p.tracker.learner_prs = HashMap::new();
+                        p.tracker.prs = HashMap::new();
 
                         for i in &peer_addrs {
                             if learner_prs.contains_key(i) {
-                                p.learner_prs.insert(
+                                p.tracker.learner_prs.insert(
                                     *i,
                                     Progress {
                                         is_learner: true,
@@ -249,7 +250,7 @@ impl Network {
                                     },
                                 );
                             } else {
-                                p.prs.insert(
+                                p.tracker.prs.insert(
                                     *i,
                                     Progress {
                                         ..Default::default()
@@ -501,6 +502,66 @@ fn test_progress_is_paused() {
     }
 }
 
+#[test]
+fn test_progress_paused_reason() {
+    let tests = vec![
+        (ProgressState::Probe, false, false, None),
+        (
+            ProgressState::Probe,
+            true,
+            false,
+            Some(PausedReason::ProbeAwaitingResponse),
+        ),
+        (ProgressState::Replicate, false, false, None),
+        (
+            ProgressState::Replicate,
+            false,
+            true,
+            Some(PausedReason::InflightsFull),
+        ),
+        (
+            ProgressState::Snapshot,
+            false,
+            false,
+            Some(PausedReason::PendingSnapshot),
+        ),
+    ];
+
+    for (state, paused, full, w) in tests {
+        let mut p = Progress {
+            state,
+            paused,
+            ins: Inflights::new(1),
+            ..Default::default()
+        };
+        if full {
+            p.ins.count = 1;
+        }
+        assert_eq!(p.paused_reason(), w);
+        assert_eq!(p.is_paused(), w.is_some());
+
+        p.send_queue_full = true;
+        assert_eq!(p.paused_reason(), Some(PausedReason::SendQueueFull));
+        p.replication_paused = true;
+        assert_eq!(p.paused_reason(), Some(PausedReason::OperatorPaused));
+    }
+}
+
+#[test]
+fn test_status_paused_reasons() {
+    let mut r = new_test_raft(1, vec![1, 2, 3], 10, 1, MemStorage::new());
+    assert!(r.get_status().paused.is_empty());
+    r.become_candidate();
+    r.become_leader();
+    r.tracker.prs.get_mut(&2).unwrap().pause();
+    r.pause_replication(3);
+
+    let paused = r.get_status().paused;
+    assert_eq!(paused.len(), 2);
+    assert_eq!(paused[&2], PausedReason::ProbeAwaitingResponse);
+    assert_eq!(paused[&3], PausedReason::OperatorPaused);
+}
+
 #[test]
 fn test_progress_resume() {
     let mut p = Progress {
@@ -516,19 +577,88 @@ fn test_progress_resume() {
     assert!(!p.paused);
 }
 
+#[test]
+fn test_progress_inflight_window() {
+    let mut p = Progress::new(1, 8, false);
+    assert_eq!(p.inflight_window, 0);
+    p.grow_inflight_window();
+    assert_eq!(p.inflight_window, 0);
+
+    p.shrink_inflight_window();
+    assert_eq!(p.inflight_window, 4);
+    p.shrink_inflight_window();
+    p.shrink_inflight_window();
+    p.shrink_inflight_window();
+    assert_eq!(p.inflight_window, 1);
+
+    // window, acks needed to grow it
+    for &(window, acks) in &[(1, 1), (2, 2), (3, 3), (4, 4), (5, 5), (6, 6), (7, 7)] {
+        assert_eq!(p.inflight_window, window);
+        for _ in 0..acks {
+            p.grow_inflight_window();
+        }
+    }
+    // back at the capacity of the inflights.
+    assert_eq!(p.inflight_window, 0);
+}
+
+#[test]
+fn test_progress_probe_backoff() {
+    let mut p = Progress::new(1, 8, false);
+    p.back_off_probe(0);
+    assert!(!p.skip_probe());
+
+    // consecutive reports, heartbeats skipped
+    for &(reports, skipped) in &[(1, 1), (2, 3), (3, 5), (10, 5)] {
+        p.reset_probe_backoff();
+        for _ in 0..reports {
+            p.back_off_probe(5);
+        }
+        let mut n = 0;
+        while p.skip_probe() {
+            n += 1;
+        }
+        assert_eq!(n, skipped);
+    }
+}
+
+#[test]
+fn test_progress_maybe_probe_from() {
+    // state, matched, next, last, wok, wnext
+    let tests = vec![
+        (ProgressState::Probe, 0, 10, 4, true, 5),
+        (ProgressState::Probe, 6, 10, 4, true, 7),
+        (ProgressState::Probe, 0, 10, 9, false, 10),
+        (ProgressState::Probe, 0, 10, 12, false, 10),
+        (ProgressState::Replicate, 0, 10, 4, false, 10),
+        (ProgressState::Snapshot, 0, 10, 4, false, 10),
+    ];
+
+    for (state, matched, next, last, wok, wnext) in tests {
+        let mut p = Progress {
+            state,
+            matched,
+            next,
+            ..Default::default()
+        };
+        assert_eq!(p.maybe_probe_from(last), wok);
+        assert_eq!(p.next, wnext);
+    }
+}
+
 #[test]
 fn test_progress_resume_by_heartbeat_resp() {
     let mut r = new_test_raft(1, vec![1, 2], 5, 1, MemStorage::new());
     r.become_candidate();
     r.become_leader();
-    r.prs.get_mut(&2).unwrap().paused = true;
+    r.tracker.prs.get_mut(&2).unwrap().paused = true;
 
     r.step(new_message(1, 1, MessageType::MsgHeartbeat)).is_ok();
-    assert!(r.prs.get(&2).unwrap().paused);
-    r.prs.get_mut(&2).unwrap().become_replicate();
+    assert!(r.tracker.prs.get(&2).unwrap().paused);
+    r.tracker.prs.get_mut(&2).unwrap().become_replicate();
     r.step(new_message(2, 1, MessageType::MsgHeartbeatResp))
         .is_ok();
-    assert!(!r.prs.get(&2).unwrap().paused);
+    assert!(!r.tracker.prs.get(&2).unwrap().paused);
 }
 
 #[test]
@@ -550,6 +680,240 @@ fn test_progress_paused() {
     assert_eq!(r.msgs.len(), 1);
 }
 
+// ensures that the leader stops replicating to a peer paused by the operator
+// while keeping its heartbeats, and catches it up once resumed.
+#[test]
+fn test_pause_replication() {
+    let mut r = new_test_raft(1, vec![1, 2, 3], 10, 1, MemStorage::new());
+    r.become_candidate();
+    r.become_leader();
+    r.tracker.prs.get_mut(&2).unwrap().become_replicate();
+    r.tracker.prs.get_mut(&3).unwrap().become_replicate();
+    r.msgs.clear();
+
+    assert!(!r.pause_replication(4));
+    assert!(r.pause_replication(2));
+    r.step(new_message_with_entries(1, 1, MessageType::MsgProp, vec![Entry::new()]))
+        .unwrap();
+    let msgs: Vec<Message> = r.msgs.drain(..).collect();
+    assert_eq!(msgs.len(), 1);
+    assert_eq!(msgs[0].get_to(), 3);
+
+    r.tick();
+    let mut tos: Vec<u64> = r.msgs
+        .drain(..)
+        .filter(|m| m.get_msg_type() == MessageType::MsgHeartbeat)
+        .map(|m| m.get_to())
+        .collect();
+    tos.sort();
+    assert_eq!(tos, vec![2, 3]);
+    r.step(new_message(2, 1, MessageType::MsgHeartbeatResp))
+        .unwrap();
+    assert!(r.msgs.is_empty());
+
+    // the pause survives the leader's own re-election.
+    r.become_follower(r.term + 1, NONE);
+    r.become_candidate();
+    r.become_leader();
+    assert!(r.tracker.prs.get(&2).unwrap().replication_paused);
+    r.msgs.clear();
+
+    assert!(r.resume_replication(2));
+    let msgs: Vec<Message> = r.msgs.drain(..).collect();
+    assert_eq!(msgs.len(), 1);
+    assert_eq!(msgs[0].get_to(), 2);
+    assert_eq!(msgs[0].get_msg_type(), MessageType::MsgApp);
+}
+
+// ensures that the leader backs off heartbeats to a peer repeatedly reported
+// unreachable, and stops backing off as soon as the peer responds.
+#[test]
+fn test_probe_backoff_unreachable() {
+    let mut r = new_test_raft(1, vec![1, 2, 3], 10, 1, MemStorage::new());
+    r.max_probe_backoff = 4;
+    r.become_candidate();
+    r.become_leader();
+    r.msgs.clear();
+
+    let heartbeats_to_2 = |r: &mut Raft<MemStorage>, ticks: usize| {
+        let mut n = 0;
+        for _ in 0..ticks {
+            r.tick();
+            n += r.msgs
+                .drain(..)
+                .filter(|m| m.get_msg_type() == MessageType::MsgHeartbeat && m.get_to() == 2)
+                .count();
+        }
+        n
+    };
+
+    // reports, heartbeats to 2 over the next 5 heartbeat intervals
+    for &(reports, sent) in &[(1, 4), (1, 2), (1, 1), (3, 1)] {
+        for _ in 0..reports {
+            r.step(new_message(2, 1, MessageType::MsgUnreachable))
+                .unwrap();
+        }
+        assert_eq!(heartbeats_to_2(&mut r, 5), sent);
+    }
+
+    r.step(new_message(2, 1, MessageType::MsgHeartbeatResp))
+        .unwrap();
+    assert_eq!(heartbeats_to_2(&mut r, 5), 5);
+}
+
+// ensures that a forced probe drops what the leader knows about the peer's
+// log and probes it from the leader's last index.
+#[test]
+fn test_force_probe() {
+    let mut r = new_test_raft(1, vec![1, 2], 10, 1, MemStorage::new());
+    assert!(!r.force_probe(2));
+    r.become_candidate();
+    r.become_leader();
+    r.step(new_message_with_entries(1, 1, MessageType::MsgProp, vec![Entry::new()]))
+        .unwrap();
+    r.tracker.prs.get_mut(&2).unwrap().maybe_update(2);
+    r.tracker.prs.get_mut(&2).unwrap().become_replicate();
+    r.msgs.clear();
+
+    assert!(!r.force_probe(1));
+    assert!(!r.force_probe(3));
+    assert!(r.force_probe(2));
+    let pr = r.tracker.prs.get(&2).unwrap();
+    assert_eq!(pr.state, ProgressState::Probe);
+    assert_eq!(pr.matched, 0);
+    assert_eq!(pr.next, 3);
+
+    let msgs: Vec<Message> = r.msgs.drain(..).collect();
+    assert_eq!(msgs.len(), 1);
+    assert_eq!(msgs[0].get_msg_type(), MessageType::MsgApp);
+    assert_eq!(msgs[0].get_index(), 2);
+    assert!(msgs[0].get_entries().is_empty());
+
+    // the restored follower only has the first entry.
+    let mut m = new_message(2, 1, MessageType::MsgAppResp);
+    m.set_index(2);
+    m.set_reject(true);
+    m.set_reject_hint(1);
+    r.step(m).unwrap();
+    let msgs: Vec<Message> = r.msgs.drain(..).collect();
+    assert_eq!(msgs.len(), 1);
+    assert_eq!(msgs[0].get_index(), 1);
+    assert_eq!(msgs[0].get_entries().len(), 1);
+}
+
+// ensures that with ack_persisted_only a follower acknowledges appended
+// entries only once they are persisted.
+#[test]
+fn test_follower_ack_persisted_only() {
+    let mut s = MemStorage::new();
+    let mut r = new_test_raft(2, vec![1, 2], 10, 1, s.clone());
+    r.ack_persisted_only = true;
+    r.become_follower(1, 1);
+
+    let mut m = new_message_with_entries(
+        1,
+        2,
+        MessageType::MsgApp,
+        vec![new_entry(1, 1), new_entry(1, 2)],
+    );
+    m.set_term(1);
+    r.step(m).unwrap();
+    let msgs: Vec<Message> = r.msgs.drain(..).collect();
+    assert_eq!(msgs.len(), 1);
+    assert_eq!(msgs[0].get_msg_type(), MessageType::MsgAppResp);
+    assert_eq!(msgs[0].get_index(), 0);
+
+    s.append(&r.raft_log.unstable_entries()).unwrap();
+    r.raft_log.stable_to(2, 1);
+    assert_eq!(r.raft_log.persisted, 2);
+    r.on_persisted();
+    let msgs: Vec<Message> = r.msgs.drain(..).collect();
+    assert_eq!(msgs.len(), 1);
+    assert_eq!(msgs[0].get_to(), 1);
+    assert_eq!(msgs[0].get_msg_type(), MessageType::MsgAppResp);
+    assert_eq!(msgs[0].get_index(), 2);
+
+    // a conflicting append makes the replaced entries non durable again.
+    let mut m = new_message_with_entries(1, 2, MessageType::MsgApp, vec![new_entry(2, 2)]);
+    m.set_term(2);
+    m.set_index(1);
+    m.set_log_term(1);
+    r.step(m).unwrap();
+    assert_eq!(r.raft_log.persisted, 1);
+    let msgs: Vec<Message> = r.msgs.drain(..).collect();
+    assert_eq!(msgs[0].get_index(), 1);
+}
+
+// ensures that next_timeout reports the ticks until the next election or
+// heartbeat, and that on_timeout fires exactly that deadline.
+#[test]
+fn test_next_timeout() {
+    let mut r = new_test_raft(1, vec![1, 2], 10, 3, MemStorage::new());
+    r.tick();
+    let ticks = r.randomized_election_timeout - 1;
+    assert_eq!(r.next_timeout(), Some(ticks));
+    r.on_timeout();
+    assert_eq!(r.state, StateType::Candidate);
+
+    r.become_leader();
+    r.msgs.clear();
+    assert_eq!(r.next_timeout(), Some(3));
+    r.on_timeout();
+    let msgs: Vec<Message> = r.msgs.drain(..).collect();
+    assert_eq!(msgs.len(), 1);
+    assert_eq!(msgs[0].get_msg_type(), MessageType::MsgHeartbeat);
+
+    // with check_quorum the leader also wakes up to check its quorum.
+    r.check_quorum = true;
+    r.election_elapsed = 9;
+    assert_eq!(r.next_timeout(), Some(1));
+
+    // a node which is not a voter only waits for its leader lease.
+    let mut l = new_test_learner_raft(2, vec![1], vec![2], 10, 1, MemStorage::new());
+    assert_eq!(l.next_timeout(), None);
+    l.check_quorum = true;
+    assert_eq!(l.next_timeout(), Some(10));
+}
+
+// ensures that a flexible quorum commits with a smaller replication quorum
+// and in exchange requires a larger election quorum.
+#[test]
+fn test_flexible_quorum() {
+    let mut r = new_test_raft(1, vec![1, 2, 3, 4, 5], 10, 1, MemStorage::new());
+    r.set_quorum_policy(FlexibleQuorum::new(2));
+    r.step(new_message(1, 1, MessageType::MsgHup)).unwrap();
+    for id in 2..4 {
+        let mut m = new_message(id, 1, MessageType::MsgVoteResp);
+        m.set_term(r.term);
+        r.step(m).unwrap();
+    }
+    // 3 of 5 votes are not enough to win.
+    assert_eq!(r.state, StateType::Candidate);
+    let mut m = new_message(4, 1, MessageType::MsgVoteResp);
+    m.set_term(r.term);
+    r.step(m).unwrap();
+    assert_eq!(r.state, StateType::Leader);
+
+    // a single follower storing an entry commits it.
+    let mut m = new_message(2, 1, MessageType::MsgAppResp);
+    m.set_term(r.term);
+    m.set_index(r.raft_log.last_index());
+    r.step(m).unwrap();
+    assert_eq!(r.raft_log.committed, r.raft_log.last_index());
+
+    // two rejections of five make winning impossible.
+    let mut r = new_test_raft(1, vec![1, 2, 3, 4, 5], 10, 1, MemStorage::new());
+    r.set_quorum_policy(FlexibleQuorum::new(2));
+    r.step(new_message(1, 1, MessageType::MsgHup)).unwrap();
+    for id in 2..4 {
+        let mut m = new_message(id, 1, MessageType::MsgVoteResp);
+        m.set_term(r.term);
+        m.set_reject(true);
+        r.step(m).unwrap();
+    }
+    assert_eq!(r.state, StateType::Follower);
+}
+
 #[test]
 fn test_leader_election() {
     leader_election(false);
@@ -1479,6 +1843,59 @@ fn test_step_ignore_old_term_msg() {
     sm.step(m).unwrap();
 }
 
+// ensures that leader and follower skip whole divergent terms when an append
+// is rejected, instead of probing back one index per round trip.
+#[test]
+fn test_fast_log_rejection() {
+    let mut s1 = MemStorage::new();
+    let leader_log: Vec<Entry> = [1, 2, 2, 4, 4, 4, 4]
+        .iter()
+        .enumerate()
+        .map(|(i, &t)| new_entry(t, i as u64 + 1))
+        .collect();
+    s1.append(&leader_log).unwrap();
+    let mut leader = new_test_raft(1, vec![1, 2], 10, 1, s1);
+    leader.term = 4;
+    leader.become_candidate();
+    leader.become_leader();
+
+    let mut s2 = MemStorage::new();
+    let follower_log: Vec<Entry> = [1, 2, 2, 3, 3, 3, 3, 3, 3]
+        .iter()
+        .enumerate()
+        .map(|(i, &t)| new_entry(t, i as u64 + 1))
+        .collect();
+    s2.append(&follower_log).unwrap();
+    let mut follower = new_test_raft(2, vec![1, 2], 10, 1, s2);
+    follower.become_follower(5, 1);
+
+    // the leader probes with its new empty entry at 5/8.
+    let _ = leader.step(new_message(2, 1, MessageType::MsgHeartbeatResp));
+    let msgs: Vec<Message> = leader.msgs.drain(..).collect();
+    assert_eq!(msgs.len(), 1);
+    assert_eq!((msgs[0].get_log_term(), msgs[0].get_index()), (4, 7));
+
+    // the follower's entry at 7 has term 3, below the leader's, so it may
+    // still be where the logs agree.
+    let _ = follower.step(msgs[0].clone());
+    let msgs: Vec<Message> = follower.msgs.drain(..).collect();
+    assert_eq!(msgs.len(), 1);
+    assert!(msgs[0].get_reject());
+    assert_eq!((msgs[0].get_log_term(), msgs[0].get_reject_hint()), (3, 7));
+
+    // the leader skips its term 4 entries, which the follower doesn't have.
+    let _ = leader.step(msgs[0].clone());
+    let msgs: Vec<Message> = leader.msgs.drain(..).collect();
+    assert_eq!(msgs.len(), 1);
+    assert_eq!((msgs[0].get_log_term(), msgs[0].get_index()), (2, 3));
+
+    let _ = follower.step(msgs[0].clone());
+    let msgs: Vec<Message> = follower.msgs.drain(..).collect();
+    assert!(!msgs[0].get_reject());
+    assert_eq!(follower.raft_log.last_index(), 8);
+    assert_eq!(follower.raft_log.last_term(), 5);
+}
+
 #[test]
 fn test_handle_msg_app() {
     let tests = vec![
@@ -1637,6 +2054,136 @@ fn test_handle_heartbeat_resp() {
     assert_eq!(msgs.len(), 0);
 }
 
+// ensures that a probing leader jumps to the last index reported in a
+// heartbeat response instead of probing from its own last index.
+#[test]
+fn test_handle_heartbeat_resp_with_last_index() {
+    let mut s = MemStorage::new();
+    let _ = s.append(&[
+        new_entry(1, 1),
+        new_entry(1, 2),
+        new_entry(1, 3),
+        new_entry(1, 4),
+    ]);
+    let mut sm = new_test_raft(1, vec![1, 2], 5, 1, s);
+    sm.become_candidate();
+    sm.become_leader();
+    sm.msgs.drain(..);
+    assert_eq!(sm.tracker.prs.get(&2).unwrap().next, 5);
+
+    let mut m = new_heartbeat_resp_message(2);
+    m.set_index(2);
+    let _ = sm.step(m);
+    let msgs: Vec<Message> = sm.msgs.drain(..).collect();
+    assert_eq!(msgs.len(), 1);
+    assert_eq!(msgs[0].get_msg_type(), MessageType::MsgApp);
+    assert_eq!(msgs[0].get_index(), 2);
+    assert_eq!(msgs[0].get_entries().len(), 3);
+}
+
+fn new_checksum_leader() -> Raft<MemStorage> {
+    let mut cfg = new_test_config(1, vec![1], 10, 1);
+    cfg.entry_checksum = true;
+    let mut r = Raft::new(&mut cfg, MemStorage::new());
+    r.become_candidate();
+    r.become_leader();
+    r.step(new_message_with_entries(
+        1,
+        1,
+        MessageType::MsgProp,
+        vec![new_entry_with_data(Vec::from("somedata"))],
+    )).unwrap();
+    r
+}
+
+// ensures that proposals get a checksum of their payload when
+// entry_checksum is enabled.
+#[test]
+fn test_propose_with_entry_checksum() {
+    let r = new_checksum_leader();
+    let ents = r.raft_log.next_ents();
+    assert_eq!(ents.len(), 2);
+    assert_eq!(ents[1].get_data(), b"somedata");
+    assert_eq!(ents[1].get_checksum(), crc32c(b"somedata"));
+}
+
+// ensures that a committed entry whose payload does not match its
+// checksum is never handed to the application.
+#[test]
+#[should_panic(expected = "checksum mismatch")]
+fn test_committed_entry_checksum_mismatch() {
+    let mut r = new_checksum_leader();
+    r.raft_log.unstable.entries[1].mut_data()[0] = b'S';
+    r.raft_log.next_ents();
+}
+
+// ensures that proposals larger than max_proposal_size are refused with
+// ProposalTooLarge, by the leader as well as by a forwarding follower.
+#[test]
+fn test_propose_too_large() {
+    let mut r = new_test_raft(1, vec![1, 2], 10, 1, MemStorage::new());
+    r.max_proposal_size = 4;
+    r.become_candidate();
+    r.become_leader();
+    let last_index = r.raft_log.last_index();
+
+    let prop = |data: &[u8]| {
+        let ents = vec![new_entry_with_data(data.to_vec())];
+        new_message_with_entries(1, 1, MessageType::MsgProp, ents)
+    };
+    assert_eq!(r.step(prop(b"large")), Err(Error::ProposalTooLarge(5, 4)));
+    assert_eq!(r.raft_log.last_index(), last_index);
+    r.step(prop(b"data")).unwrap();
+    assert_eq!(r.raft_log.last_index(), last_index + 1);
+
+    let mut f = new_test_raft(2, vec![1, 2], 10, 1, MemStorage::new());
+    f.max_proposal_size = 4;
+    f.become_follower(1, 1);
+    assert_eq!(f.step(prop(b"large")), Err(Error::ProposalTooLarge(5, 4)));
+    assert!(f.msgs.is_empty());
+}
+
+// ensures that the leader drops proposals while its log runs too far ahead
+// of the commit index, and accepts them again once entries are committed.
+#[test]
+fn test_propose_uncommitted_limits() {
+    let prop = |data: &[u8]| {
+        let ents = vec![new_entry_with_data(data.to_vec())];
+        new_message_with_entries(1, 1, MessageType::MsgProp, ents)
+    };
+    let commit_all = |r: &mut Raft<MemStorage>| {
+        let mut m = new_message(2, 1, MessageType::MsgAppResp);
+        m.set_term(r.term);
+        m.set_index(r.raft_log.last_index());
+        r.step(m).unwrap();
+    };
+
+    // the new leader's empty entry is uncommitted.
+    let mut r = new_test_raft(1, vec![1, 2, 3], 10, 1, MemStorage::new());
+    r.max_uncommitted_entries = 3;
+    r.become_candidate();
+    r.become_leader();
+    r.step(prop(b"a")).unwrap();
+    r.step(prop(b"b")).unwrap();
+    assert_eq!(r.step(prop(b"c")), Err(Error::ProposalDropped));
+    commit_all(&mut r);
+    r.step(prop(b"c")).unwrap();
+
+    let mut r = new_test_raft(1, vec![1, 2, 3], 10, 1, MemStorage::new());
+    r.max_uncommitted_bytes = 10;
+    r.become_candidate();
+    r.become_leader();
+    // a proposal larger than the limit is accepted while nothing is pending.
+    r.step(prop(b"0123456789ab")).unwrap();
+    assert_eq!(r.step(prop(b"a")), Err(Error::ProposalDropped));
+    commit_all(&mut r);
+    r.step(prop(b"01234567")).unwrap();
+    r.step(prop(b"89")).unwrap();
+    assert_eq!(r.step(prop(b"a")), Err(Error::ProposalDropped));
+    commit_all(&mut r);
+    r.step(prop(b"a")).unwrap();
+}
+
 fn new_heartbeat_resp_message(from: u64) -> Message {
     let mut m = Message::new();
     m.set_from(from);
@@ -1678,6 +2225,42 @@ fn test_raft_frees_read_only_mem() {
     assert!(!sm.read_only.pending_read_index.contains_key(&ctx));
 }
 
+// ensures that with batch_read_index the ReadIndex requests received between
+// two heartbeats are all confirmed by a single heartbeat round.
+#[test]
+fn test_batch_read_index() {
+    let mut sm = new_test_raft(1, vec![1, 2, 3], 5, 1, MemStorage::new());
+    sm.batch_read_index = true;
+    sm.become_candidate();
+    sm.become_leader();
+    let last_index = sm.raft_log.last_index();
+    sm.raft_log.commit_to(last_index);
+    sm.msgs.clear();
+
+    for ctx in &[b"ctx1", b"ctx2", b"ctx3"] {
+        let ents = vec![new_entry_with_data(ctx.to_vec())];
+        sm.step(new_message_with_entries(1, 1, MessageType::MsgReadIndex, ents))
+            .unwrap();
+    }
+    assert!(sm.msgs.is_empty());
+
+    sm.tick();
+    let msgs: Vec<Message> = sm.msgs.drain(..).collect();
+    assert_eq!(msgs.len(), 2);
+    for m in &msgs {
+        assert_eq!(m.get_msg_type(), MessageType::MsgHeartbeat);
+        assert_eq!(m.get_context(), b"ctx3");
+    }
+
+    let mut m = new_message(2, 1, MessageType::MsgHeartbeatResp);
+    m.set_term(sm.term);
+    m.set_context(b"ctx3".to_vec());
+    sm.step(m).unwrap();
+    let ctxs: Vec<&[u8]> = sm.read_states.iter().map(|rs| &rs.request_ctx[..]).collect();
+    assert_eq!(ctxs, vec![b"ctx1", b"ctx2", b"ctx3"]);
+    assert!(sm.read_states.iter().all(|rs| rs.index == last_index));
+}
+
 #[test]
 fn test_msg_app_resp_wait_reset() {
     let mut sm = new_test_raft(1, vec![1, 2, 3], 5, 1, MemStorage::new());
@@ -2119,6 +2702,53 @@ fn test_non_promotable_voter_with_check_quorum() {
     assert_eq!(nt.peers.get_mut(&2).unwrap().lead, 1);
 }
 
+// ensures that the campaign type decides whether an election goes through
+// pre-vote, regardless of Config.pre_vote.
+#[test]
+fn test_campaign_type() {
+    let tests = vec![
+        (CampaignType::PreElection, StateType::PreCandidate, MessageType::MsgPreVote),
+        (CampaignType::Election, StateType::Candidate, MessageType::MsgVote),
+        (CampaignType::Transfer, StateType::Candidate, MessageType::MsgVote),
+    ];
+    for (i, (t, wstate, wmsg)) in tests.into_iter().enumerate() {
+        let mut r = new_test_raft(1, vec![1, 2, 3], 10, 1, MemStorage::new());
+        r.pre_vote = true;
+        r.hup(t);
+        assert_eq!(r.state, wstate, "#{}", i);
+        let msgs: Vec<Message> = r.msgs.drain(..).collect();
+        assert_eq!(msgs.len(), 2, "#{}", i);
+        for m in msgs {
+            assert_eq!(m.get_msg_type(), wmsg, "#{}", i);
+            assert_eq!(m.get_term(), 1, "#{}", i);
+            let wctx: &[u8] = if t == CampaignType::Transfer {
+                b"CampaignTransfer"
+            } else {
+                b""
+            };
+            assert_eq!(m.get_context(), wctx, "#{}", i);
+        }
+    }
+}
+
+// ensures that a follower which forgets its leader grants votes right away
+// despite check_quorum, unless reads are served by lease.
+#[test]
+fn test_forget_leader() {
+    for &(option, wvote) in &[(ReadOnlyOption::Safe, 3), (ReadOnlyOption::LeaseBased, 0)] {
+        let mut r = new_test_raft(1, vec![1, 2, 3], 10, 1, MemStorage::new());
+        r.check_quorum = true;
+        r.read_only.option = option;
+        r.become_follower(1, 2);
+
+        r.step(new_message(1, 1, MessageType::MsgForgetLeader)).unwrap();
+        let mut m = new_message(3, 1, MessageType::MsgVote);
+        m.set_term(2);
+        r.step(m).unwrap();
+        assert_eq!(r.vote, wvote, "{:?}", option);
+    }
+}
+
 #[test]
 fn test_disruptive_follower() {
     let mut n1 = new_test_raft(1, vec![1, 2, 3], 10, 1, MemStorage::new());
@@ -2462,6 +3092,26 @@ fn test_restore_learner_promotion() {
     assert!(!sm.is_learner);
 }
 
+// ensures that restoring a snapshot rebuilds the peers from its conf state,
+// all probing from the snapshot index.
+#[test]
+fn test_restore_progress() {
+    let s = new_snapshot(11, 11, vec![4], vec![1, 2, 3]);
+    let mut store = MemStorage::new();
+    store.append(&[new_entry(1, 1), new_entry(1, 2)]).unwrap();
+    let mut sm = new_test_raft(1, vec![1, 2], 10, 1, store);
+    assert!(sm.restore(s));
+
+    assert_eq!(sm.nodes(), vec![1, 2, 3]);
+    assert_eq!(sm.learner_nodes(), vec![4]);
+    for (id, pr) in sm.tracker.prs.iter().chain(sm.tracker.learner_prs.iter()) {
+        let wmatched = if *id == 1 { 11 } else { 0 };
+        assert_eq!(pr.matched, wmatched, "peer {}", id);
+        assert_eq!(pr.next, 12, "peer {}", id);
+        assert_eq!(pr.state, ProgressState::Probe, "peer {}", id);
+    }
+}
+
 #[test]
 fn test_learner_receive_snapshot() {
     let s = new_snapshot(11, 11, vec![1], vec![2]);
@@ -2520,7 +3170,7 @@ fn test_provide_snap() {
     sm.become_candidate();
     sm.become_leader();
     let first_index = sm.raft_log.first_index();
-    sm.prs.get_mut(&2).unwrap().next = first_index;
+    sm.tracker.prs.get_mut(&2).unwrap().next = first_index;
     let mut m = new_message(2, 1, MessageType::MsgAppResp);
     m.set_index(first_index - 1);
     m.set_reject(true);
@@ -2531,6 +3181,44 @@ fn test_provide_snap() {
     assert_eq!(msgs[0].get_msg_type(), MessageType::MsgSnap);
 }
 
+#[test]
+fn test_progress_replication_metrics() {
+    let s = new_snapshot(11, 11, vec![], vec![1, 2]);
+    let mut sm = new_test_raft(1, vec![1], 10, 1, MemStorage::new());
+    sm.restore(s);
+    sm.become_candidate();
+    sm.become_leader();
+
+    // a rejection below the first index makes the leader send a snapshot.
+    let first_index = sm.raft_log.first_index();
+    sm.tracker.prs.get_mut(&2).unwrap().next = first_index;
+    let mut m = new_message(2, 1, MessageType::MsgAppResp);
+    m.set_index(first_index - 1);
+    m.set_reject(true);
+    let _ = sm.step(m);
+    // the first snapshot fails and is sent again, the second one succeeds.
+    let mut m = new_message(2, 1, MessageType::MsgSnapStatus);
+    m.set_reject(true);
+    let _ = sm.step(m);
+    let _ = sm.step(new_message(2, 1, MessageType::MsgHeartbeatResp));
+    let _ = sm.step(new_message(2, 1, MessageType::MsgSnapStatus));
+    let _ = sm.step(new_message(2, 1, MessageType::MsgHeartbeatResp));
+
+    let msgs: Vec<Message> = sm.msgs.drain(..).collect();
+    let types: Vec<MessageType> = msgs.iter().map(|m| m.get_msg_type()).collect();
+    assert_eq!(
+        types,
+        vec![MessageType::MsgSnap, MessageType::MsgSnap, MessageType::MsgApp]
+    );
+    assert_eq!(msgs[2].get_entries().len(), 1);
+    let pr = &sm.get_status().progress[&2];
+    assert_eq!(pr.rejects_received, 1);
+    assert_eq!(pr.snapshots_sent, 2);
+    assert_eq!(pr.snapshots_failed, 1);
+    assert_eq!(pr.entries_sent, 1);
+    assert_eq!(pr.bytes_sent, u64::from(msgs[2].get_entries()[0].compute_size()));
+}
+
 #[test]
 fn test_ignore_providing_snap() {
     let s = new_snapshot(11, 11, vec![], vec![1, 2]);
@@ -2540,8 +3228,8 @@ fn test_ignore_providing_snap() {
     sm.become_leader();
 
     let first_index = sm.raft_log.first_index();
-    sm.prs.get_mut(&2).unwrap().next = first_index - 1;
-    sm.prs.get_mut(&2).unwrap().recent_active = false;
+    sm.tracker.prs.get_mut(&2).unwrap().next = first_index - 1;
+    sm.tracker.prs.get_mut(&2).unwrap().recent_active = false;
 
     let _ = sm.step(new_message_with_entries(
         1,
@@ -2611,7 +3299,7 @@ fn test_slow_node_restore() {
             vec![Entry::new()],
         )]);
 
-        if nt.peers.get(&1).unwrap().prs.get(&3).unwrap().recent_active {
+        if nt.peers.get(&1).unwrap().tracker.prs.get(&3).unwrap().recent_active {
             break;
         }
     }
@@ -2656,15 +3344,36 @@ fn test_step_ignore_config() {
 
     let index = r.raft_log.last_index();
     let pending_conf_index = r.pending_conf_index;
-    let _ = r.step(m);
-
-    let mut e = new_entry(1, 3);
-    e.set_entry_type(EntryType::EntryNormal);
-    let wents = vec![e];
-    let ents = r.raft_log.entries(index + 1, NO_LIMIT).unwrap();
+    assert_eq!(r.step(m), Err(Error::ProposalDropped));
 
     assert_eq!(pending_conf_index, r.pending_conf_index);
-    assert_eq!(wents, ents);
+    assert_eq!(index, r.raft_log.last_index());
+}
+
+// ensures that a joint config only accepts the conf change leaving it, and
+// that leaving is refused outside of a joint config.
+#[test]
+fn test_step_ignore_config_joint() {
+    let mut r = new_test_raft(1, vec![1, 2], 10, 1, MemStorage::new());
+    r.become_candidate();
+    r.become_leader();
+    let leave = || {
+        let mut e = Entry::new();
+        e.set_entry_type(EntryType::EntryConfChangeV2);
+        new_message_with_entries(1, 1, MessageType::MsgProp, vec![e])
+    };
+    assert_eq!(r.step(leave()), Err(Error::ProposalDropped));
+
+    let add = new_conf_change_single(ConfChangeType::ConfChangeAddNode, 3);
+    r.enter_joint(false, &[add]).unwrap();
+    let mut e = Entry::new();
+    e.set_entry_type(EntryType::EntryConfChange);
+    let m = new_message_with_entries(1, 1, MessageType::MsgProp, vec![e]);
+    assert_eq!(r.step(m), Err(Error::ProposalDropped));
+
+    let index = r.raft_log.last_index();
+    assert_eq!(r.step(leave()), Ok(()));
+    assert_eq!(r.pending_conf_index, index + 1);
 }
 
 #[test]
@@ -2700,7 +3409,7 @@ fn test_add_learner() {
     let nodes = r.learner_nodes();
     let wnodes = vec![2];
     assert_eq!(wnodes, nodes);
-    assert!(r.learner_prs.get(&2).unwrap().is_learner);
+    assert!(r.tracker.learner_prs.get(&2).unwrap().is_learner);
 }
 
 #[test]
@@ -2738,6 +3447,162 @@ fn test_remove_node() {
     assert_eq!(w, r.nodes());
 }
 
+// FixedQuorum needs a fixed number of voters for any quorum, whatever the
+// number of voters.
+struct FixedQuorum(usize);
+
+impl QuorumPolicy for FixedQuorum {
+    fn is_election_quorum(&self, voters: &[u64], granted: &[u64]) -> bool {
+        granted.iter().filter(|id| voters.contains(id)).count() >= self.0
+    }
+
+    fn is_replication_quorum(&self, voters: &[u64], acked: &[u64]) -> bool {
+        self.is_election_quorum(voters, acked)
+    }
+}
+
+#[test]
+fn test_check_conf_change() {
+    let invalid = |s: &str| Err(Error::ConfChangeInvalid(s.to_string()));
+    let r = new_test_learner_raft(1, vec![1, 2], vec![3], 10, 1, MemStorage::new());
+    let tests = vec![
+        (ConfChangeType::ConfChangeAddNode, 2, invalid("can't add 2, already a voter")),
+        (ConfChangeType::ConfChangeAddNode, 3, Ok(())),
+        (ConfChangeType::ConfChangeAddNode, 4, Ok(())),
+        (
+            ConfChangeType::ConfChangeAddLearnerNode,
+            3,
+            invalid("can't add 3, already a learner"),
+        ),
+        (ConfChangeType::ConfChangeAddLearnerNode, 2, Ok(())),
+        (ConfChangeType::ConfChangeAddLearnerNode, 4, Ok(())),
+        (ConfChangeType::ConfChangeRemoveNode, 2, Ok(())),
+        (ConfChangeType::ConfChangeRemoveNode, 3, Ok(())),
+        (ConfChangeType::ConfChangeRemoveNode, 4, Ok(())),
+        (ConfChangeType::ConfChangeUpdateNode, 2, Ok(())),
+    ];
+    for (i, (t, id, w)) in tests.into_iter().enumerate() {
+        assert_eq!(r.check_conf_change(t, id), w, "#{}", i);
+    }
+
+    let r = new_test_learner_raft(1, vec![1], vec![2], 10, 1, MemStorage::new());
+    let tests = vec![
+        (ConfChangeType::ConfChangeRemoveNode, 1, invalid("can't remove 1, the last voter")),
+        (
+            ConfChangeType::ConfChangeAddLearnerNode,
+            1,
+            invalid("can't demote 1, the only voter"),
+        ),
+        (ConfChangeType::ConfChangeRemoveNode, 2, Ok(())),
+        (ConfChangeType::ConfChangeAddNode, 2, Ok(())),
+    ];
+    for (i, (t, id, w)) in tests.into_iter().enumerate() {
+        assert_eq!(r.check_conf_change(t, id), w, "#{}", i);
+    }
+
+    // the remaining voters must still form a quorum under the policy.
+    let mut r = new_test_raft(1, vec![1, 2, 3], 10, 1, MemStorage::new());
+    r.set_quorum_policy(FixedQuorum(3));
+    let tests = vec![
+        (
+            ConfChangeType::ConfChangeRemoveNode,
+            3,
+            invalid("can't change 3, voters [1, 2] would have no quorum"),
+        ),
+        (
+            ConfChangeType::ConfChangeAddLearnerNode,
+            2,
+            invalid("can't change 2, voters [1, 3] would have no quorum"),
+        ),
+        (ConfChangeType::ConfChangeAddNode, 4, Ok(())),
+    ];
+    for (i, (t, id, w)) in tests.into_iter().enumerate() {
+        assert_eq!(r.check_conf_change(t, id), w, "#{}", i);
+    }
+}
+
+// test_replace_node ensures replace_node adds the new node as a learner first,
+// and only proposes the joint conf change swapping it for the old voter once
+// it has caught up.
+#[test]
+fn test_replace_node() {
+    let mut r = new_test_raft(1, vec![1, 2, 3], 10, 1, MemStorage::new());
+    assert_eq!(r.replace_node(3, 4), Err(Error::ProposalDropped));
+    r.become_candidate();
+    r.become_leader();
+    r.msgs.clear();
+
+    let invalid = |s: &str| Err(Error::ConfChangeInvalid(s.to_string()));
+    assert_eq!(r.replace_node(4, 5), invalid("can't replace 4, not a voter"));
+    assert_eq!(r.replace_node(3, 2), invalid("can't replace 3 by 2, already a voter"));
+
+    let conf_change = |r: &Raft<MemStorage>, i: u64| {
+        let ents = r.raft_log.entries(i, NO_LIMIT).unwrap();
+        assert_eq!(ents[0].get_entry_type(), EntryType::EntryConfChangeV2);
+        let cc: ConfChangeV2 = protobuf::parse_from_bytes(ents[0].get_data()).unwrap();
+        cc.get_changes()
+            .iter()
+            .map(|c| (c.get_change_type(), c.get_node_id()))
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(r.replace_node(3, 4), Ok(()));
+    let index = r.raft_log.last_index();
+    assert_eq!(conf_change(&r, index), vec![(ConfChangeType::ConfChangeAddLearnerNode, 4)]);
+    assert_eq!(r.pending_replace, Some((3, 4)));
+    assert_eq!(r.replace_node(2, 5), invalid("replacement of 3 by 4 is in progress"));
+
+    // the learner is added, but has yet to catch up.
+    r.raft_log.commit_to(index);
+    r.raft_log.applied_to(index);
+    r.add_learner(4);
+    r.on_applied();
+    assert_eq!(r.raft_log.last_index(), index);
+
+    let mut m = new_message(4, 1, MessageType::MsgAppResp);
+    m.set_term(r.term);
+    m.set_index(index);
+    r.step(m).expect("");
+    assert_eq!(r.pending_replace, None);
+    assert_eq!(
+        conf_change(&r, index + 1),
+        vec![
+            (ConfChangeType::ConfChangeAddNode, 4),
+            (ConfChangeType::ConfChangeRemoveNode, 3),
+        ]
+    );
+}
+
+// ensures that adding a voter as learner demotes it in place, and that a
+// demoted leader steps down.
+#[test]
+fn test_demote_voter() {
+    let mut r = new_test_raft(1, vec![1, 2, 3], 10, 1, MemStorage::new());
+    r.become_candidate();
+    r.become_leader();
+    let last_index = r.raft_log.last_index();
+    r.tracker.prs.get_mut(&2).unwrap().maybe_update(last_index);
+    r.tracker.prs.get_mut(&3).unwrap().maybe_update(last_index);
+
+    r.add_learner(2);
+    assert_eq!(r.nodes(), vec![1, 3]);
+    assert_eq!(r.learner_nodes(), vec![2]);
+    let pr = &r.tracker.learner_prs[&2];
+    assert!(pr.is_learner);
+    assert_eq!(pr.matched, last_index);
+    assert_eq!(r.state, StateType::Leader);
+
+    r.msgs.clear();
+    r.add_learner(1);
+    assert!(r.is_learner);
+    assert_eq!(r.state, StateType::Follower);
+    assert_eq!(r.nodes(), vec![3]);
+    let msgs: Vec<Message> = r.msgs.drain(..).collect();
+    assert!(msgs
+        .iter()
+        .any(|m| m.get_msg_type() == MessageType::MsgTimeoutNow && m.get_to() == 3));
+}
+
 #[test]
 fn test_remove_learner() {
     let mut r = new_test_learner_raft(1, vec![1], vec![2], 10, 1, MemStorage::new());
@@ -2749,6 +3614,199 @@ fn test_remove_learner() {
     assert!(r.nodes().is_empty());
 }
 
+// ensures that a leader removed from the configuration hands leadership over
+// to the most up-to-date voter, and can no longer campaign or take proposals.
+#[test]
+fn test_remove_self_leader() {
+    let mut r = new_test_raft(1, vec![1, 2, 3], 10, 1, MemStorage::new());
+    r.become_candidate();
+    r.become_leader();
+    let last_index = r.raft_log.last_index();
+    r.tracker.prs.get_mut(&2).unwrap().maybe_update(last_index - 1);
+    r.tracker.prs.get_mut(&3).unwrap().maybe_update(last_index);
+    r.msgs.clear();
+
+    r.remove_node(1);
+    assert_eq!(r.state, StateType::Follower);
+    assert_eq!(r.lead, NONE);
+    let msgs: Vec<Message> = r.msgs.drain(..).collect();
+    assert!(msgs
+        .iter()
+        .any(|m| m.get_msg_type() == MessageType::MsgTimeoutNow && m.get_to() == 3));
+
+    r.step(new_message(1, 1, MessageType::MsgHup)).unwrap();
+    assert_eq!(r.state, StateType::Follower);
+    assert_eq!(
+        r.step(new_message(1, 1, MessageType::MsgProp)),
+        Err(Error::ProposalDropped)
+    );
+}
+
+// ensures that a removed leader only hands leadership over to a lagging voter
+// once it has caught up, so that the forced campaign can't fail.
+#[test]
+fn test_remove_self_leader_lagging_transferee() {
+    let mut r = new_test_raft(1, vec![1, 2, 3], 10, 1, MemStorage::new());
+    r.become_candidate();
+    r.become_leader();
+    let last_index = r.raft_log.last_index();
+    r.msgs.clear();
+
+    r.remove_node(1);
+    assert_eq!(r.state, StateType::Leader);
+    assert_eq!(r.lead_transferee, 2);
+    let msgs: Vec<Message> = r.msgs.drain(..).collect();
+    assert!(msgs.iter().all(|m| m.get_msg_type() != MessageType::MsgTimeoutNow));
+    assert!(msgs
+        .iter()
+        .any(|m| m.get_msg_type() == MessageType::MsgApp && m.get_to() == 2));
+    let mut m = new_message(1, 1, MessageType::MsgProp);
+    m.set_entries(RepeatedField::from_vec(vec![Entry::new()]));
+    assert_eq!(r.step(m), Err(Error::ProposalDropped));
+
+    let mut m = new_message(2, 1, MessageType::MsgAppResp);
+    m.set_term(r.term);
+    m.set_index(last_index);
+    r.step(m).unwrap();
+    let msgs: Vec<Message> = r.msgs.drain(..).collect();
+    assert!(msgs
+        .iter()
+        .any(|m| m.get_msg_type() == MessageType::MsgTimeoutNow && m.get_to() == 2));
+}
+
+fn new_conf_change_single(change_type: ConfChangeType, node_id: u64) -> ConfChangeSingle {
+    let mut cc = ConfChangeSingle::new();
+    cc.set_change_type(change_type);
+    cc.set_node_id(node_id);
+    cc
+}
+
+// ensures that a joint config commits entries only once both the incoming
+// and the outgoing configs have a quorum, and drops the outgoing voters once
+// left.
+#[test]
+fn test_joint_config_commit() {
+    let mut r = new_test_raft(1, vec![1, 2, 3], 10, 1, MemStorage::new());
+    r.become_candidate();
+    r.become_leader();
+    let changes = vec![
+        new_conf_change_single(ConfChangeType::ConfChangeAddNode, 4),
+        new_conf_change_single(ConfChangeType::ConfChangeAddNode, 5),
+        new_conf_change_single(ConfChangeType::ConfChangeRemoveNode, 2),
+        new_conf_change_single(ConfChangeType::ConfChangeRemoveNode, 3),
+    ];
+    r.enter_joint(false, &changes).unwrap();
+    assert!(r.is_joint());
+    assert_eq!(r.nodes(), vec![1, 4, 5]);
+    assert_eq!(r.voters_outgoing(), vec![1, 2, 3]);
+    assert_eq!(r.tracker.prs.len(), 5);
+    assert_eq!(
+        r.enter_joint(false, &changes),
+        Err(Error::ConfChangeInvalid("config is already joint".to_string()))
+    );
+
+    let ents = vec![new_entry_with_data(b"foo".to_vec())];
+    r.step(new_message_with_entries(1, 1, MessageType::MsgProp, ents))
+        .unwrap();
+    let index = r.raft_log.last_index();
+    let mut m = new_message(4, 1, MessageType::MsgAppResp);
+    m.set_term(r.term);
+    m.set_index(index);
+    r.step(m.clone()).unwrap();
+    // the incoming config has a quorum, the outgoing one doesn't.
+    assert!(r.raft_log.committed < index);
+    m.set_from(2);
+    r.step(m).unwrap();
+    assert_eq!(r.raft_log.committed, index);
+
+    r.leave_joint().unwrap();
+    assert!(!r.is_joint());
+    assert_eq!(r.nodes(), vec![1, 4, 5]);
+    assert!(r.leave_joint().is_err());
+}
+
+// ensures that a joint config is restored from a snapshot.
+#[test]
+fn test_restore_joint_config() {
+    let mut s = new_snapshot(11, 11, vec![], vec![1, 2, 4]);
+    s.mut_metadata().mut_conf_state().set_voters_outgoing(vec![1, 2, 3]);
+    let mut sm = new_test_raft(1, vec![1, 2], 10, 1, MemStorage::new());
+    assert!(sm.restore(s));
+    assert!(sm.is_joint());
+    assert_eq!(sm.nodes(), vec![1, 2, 4]);
+    assert_eq!(sm.voters_outgoing(), vec![1, 2, 3]);
+    assert_eq!(sm.tracker.prs.len(), 4);
+
+    // a voter demoted by the joint config stays in prs until it is left.
+    let mut s = new_snapshot(12, 12, vec![], vec![1, 2]);
+    s.mut_metadata().mut_conf_state().set_voters_outgoing(vec![1, 2, 3]);
+    s.mut_metadata().mut_conf_state().set_learners_next(vec![3]);
+    assert!(sm.restore(s.clone()));
+    assert_eq!(sm.learners_next(), vec![3]);
+    assert!(sm.learner_nodes().is_empty());
+    assert_eq!(sm.tracker.prs.len(), 3);
+    assert_eq!(&sm.conf_state(), s.get_metadata().get_conf_state());
+}
+
+// ensures that a voter demoted in a joint config keeps voting in the outgoing
+// config, and becomes a learner once the joint config is left.
+#[test]
+fn test_joint_config_demote_voter() {
+    let mut r = new_test_raft(1, vec![1, 2, 3], 10, 1, MemStorage::new());
+    r.become_candidate();
+    r.become_leader();
+
+    let changes = vec![new_conf_change_single(ConfChangeType::ConfChangeAddLearnerNode, 3)];
+    r.enter_joint(false, &changes).unwrap();
+    assert_eq!(r.nodes(), vec![1, 2]);
+    assert_eq!(r.voters_outgoing(), vec![1, 2, 3]);
+    assert_eq!(r.learners_next(), vec![3]);
+    assert!(r.learner_nodes().is_empty());
+    assert!(r.tracker.prs.contains_key(&3));
+    assert_eq!(r.conf_state().get_learners_next(), &[3]);
+
+    r.leave_joint().unwrap();
+    assert_eq!(r.nodes(), vec![1, 2]);
+    assert_eq!(r.learner_nodes(), vec![3]);
+    assert!(r.learners_next().is_empty());
+    assert!(r.tracker.learner_prs[&3].is_learner);
+    assert!(r.conf_state().get_learners_next().is_empty());
+}
+
+// ensures that a node does not vote for a lower priority candidate whose
+// log is not ahead of its own, unless leadership is being transferred.
+#[test]
+fn test_priority_election() {
+    // (candidate priority, candidate last index, transfer, wreject)
+    let tests = vec![
+        (1, 1, false, true),
+        (2, 1, false, false),
+        (3, 1, false, false),
+        (1, 2, false, false),
+        (1, 1, true, false),
+    ];
+    for (i, (priority, index, transfer, wreject)) in tests.into_iter().enumerate() {
+        let mut r = new_test_raft(1, vec![1, 2], 10, 1, MemStorage::new());
+        r.priority = 2;
+        r.term = 1;
+        r.raft_log.append(&[new_entry(1, 1)]);
+        for &t in &[MessageType::MsgPreVote, MessageType::MsgVote] {
+            let mut m = new_message(2, 1, t);
+            m.set_term(2);
+            m.set_log_term(1);
+            m.set_index(index);
+            m.set_priority(priority);
+            if transfer {
+                m.set_context(b"CampaignTransfer".to_vec());
+            }
+            r.step(m).unwrap();
+            let msgs: Vec<Message> = r.msgs.drain(..).collect();
+            assert_eq!(msgs.len(), 1, "#{}", i);
+            assert_eq!(msgs[0].get_reject(), wreject, "#{}.{:?}", i, t);
+        }
+    }
+}
+
 #[test]
 fn test_promotable() {
     let id = 1;
@@ -2879,6 +3937,38 @@ fn test_leader_transfer_to_up_to_date_node() {
     assert_eq!(nt.peers.get(&1).unwrap().lead_transferee, NONE);
 }
 
+// ensures that an automatic transfer picks the most caught up recently
+// active voter.
+#[test]
+fn test_leader_transfer_auto() {
+    let mut nt = Network::new(vec![None, None, None]);
+    nt.send(vec![new_message(1, 1, MessageType::MsgHup)]);
+    assert_eq!(nt.peers.get_mut(&2).unwrap().transfer_leadership_auto(), None);
+
+    nt.isolate(2);
+    nt.send(vec![new_message_with_entries(
+        1,
+        1,
+        MessageType::MsgProp,
+        vec![Entry::new()],
+    )]);
+    nt.recover();
+
+    {
+        // the most caught up voter is skipped if it wasn't recently active.
+        let lead = nt.peers.get_mut(&1).unwrap();
+        lead.tracker.prs.get_mut(&3).unwrap().recent_active = false;
+        assert_eq!(lead.transfer_leadership_auto(), Some(2));
+        lead.abort_leader_transfer();
+        lead.tracker.prs.get_mut(&3).unwrap().recent_active = true;
+        assert_eq!(lead.transfer_leadership_auto(), Some(3));
+    }
+    let msgs = nt.peers.get_mut(&1).unwrap().read_messages();
+    nt.send(msgs);
+    assert_eq!(nt.peers.get(&1).unwrap().state, StateType::Follower);
+    assert_eq!(nt.peers.get(&3).unwrap().state, StateType::Leader);
+}
+
 #[test]
 fn test_leader_transfer_to_up_to_date_node_from_follower() {
     let mut nt = Network::new(vec![None, None, None]);
@@ -2954,7 +4044,7 @@ fn test_leader_transfer_to_slow_follwer() {
 
     nt.recover();
 
-    assert_eq!(nt.peers.get(&1).unwrap().prs.get(&3).unwrap().matched, 1);
+    assert_eq!(nt.peers.get(&1).unwrap().tracker.prs.get(&3).unwrap().matched, 1);
 
     nt.send(vec![new_message(3, 1, MessageType::MsgTransferLeader)]);
     assert_eq!(nt.peers.get(&1).unwrap().state, StateType::Follower);
@@ -2995,7 +4085,7 @@ fn test_leader_transfer_after_snapshot() {
 
     nt.recover();
 
-    assert_eq!(nt.peers.get(&1).unwrap().prs.get(&3).unwrap().matched, 1);
+    assert_eq!(nt.peers.get(&1).unwrap().tracker.prs.get(&3).unwrap().matched, 1);
 
     nt.send(vec![new_message(3, 1, MessageType::MsgTransferLeader)]);
     nt.send(vec![new_message(3, 1, MessageType::MsgHeartbeatResp)]);
@@ -3077,7 +4167,7 @@ fn test_leader_transfer_ignore_proposal() {
     ));
 
     assert_eq!(err, Err(Error::ProposalDropped));
-    assert_eq!(nt.peers.get(&1).unwrap().prs.get(&1).unwrap().matched, 1);
+    assert_eq!(nt.peers.get(&1).unwrap().tracker.prs.get(&1).unwrap().matched, 1);
 }
 
 #[test]
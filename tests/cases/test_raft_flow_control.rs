@@ -1,7 +1,8 @@
-use cases::test_raft::new_test_raft;
+use cases::test_raft::{new_test_config, new_test_raft};
+use libraft::raft::Raft;
 use libraft::raftpb::{Entry, Message, MessageType};
 use libraft::storage::{MemStorage, Storage};
-use protobuf::RepeatedField;
+use protobuf::{Message as PbMessage, RepeatedField};
 
 // ensure
 // 1. msgApp can fill the sending window until full
@@ -12,7 +13,7 @@ fn test_msg_app_flow_control_full() {
     let mut r = new_test_raft(1, vec![1, 2], 5, 1, MemStorage::new());
     r.become_candidate();
     r.become_leader();
-    r.prs.get_mut(&2).unwrap().become_replicate();
+    r.tracker.prs.get_mut(&2).unwrap().become_replicate();
 
     for _ in 0..r.max_inflight {
         let mut m = Message::new();
@@ -27,7 +28,7 @@ fn test_msg_app_flow_control_full() {
         assert_eq!(ms.len(), 1);
     }
 
-    assert!(r.prs.get(&2).unwrap().ins.full());
+    assert!(r.tracker.prs.get(&2).unwrap().ins.full());
 
     for _ in 0..10 {
         let mut m = Message::new();
@@ -48,7 +49,7 @@ fn test_msg_app_flow_control_move_forward() {
     let mut r = new_test_raft(1, vec![1, 2], 5, 1, MemStorage::new());
     r.become_candidate();
     r.become_leader();
-    r.prs.get_mut(&2).unwrap().become_replicate();
+    r.tracker.prs.get_mut(&2).unwrap().become_replicate();
 
     for _ in 0..r.max_inflight {
         let mut m = Message::new();
@@ -89,7 +90,7 @@ fn test_msg_app_flow_control_move_forward() {
             assert_eq!(ms.len(), 1);
         }
 
-        assert!(r.prs.get(&2).unwrap().ins.full());
+        assert!(r.tracker.prs.get(&2).unwrap().ins.full());
 
          for ii in 0..i {
             let mut m = Message::new();
@@ -99,7 +100,7 @@ fn test_msg_app_flow_control_move_forward() {
             m.set_index(ii);
             let _ = r.step(m);
             let _: Vec<Message> = r.msgs.drain(..).collect();
-            assert!(r.prs.get(&2).unwrap().ins.full());
+            assert!(r.tracker.prs.get(&2).unwrap().ins.full());
         }
     }
 }
@@ -111,7 +112,7 @@ fn test_msg_app_flow_control_recv_heartbeat() {
     let mut r = new_test_raft(1, vec![1, 2], 5, 1, MemStorage::new());
     r.become_candidate();
     r.become_leader();
-    r.prs.get_mut(&2).unwrap().become_replicate();
+    r.tracker.prs.get_mut(&2).unwrap().become_replicate();
 
     for _ in 0..r.max_inflight {
         let mut m = Message::new();
@@ -127,7 +128,7 @@ fn test_msg_app_flow_control_recv_heartbeat() {
     }
 
     for tt in 0..5 {
-        assert!(r.prs.get_mut(&2).unwrap().ins.full());
+        assert!(r.tracker.prs.get_mut(&2).unwrap().ins.full());
 
         // recv tt msgHeartbeatResp and expect one free slot
         for _ in 0..tt {
@@ -137,7 +138,7 @@ fn test_msg_app_flow_control_recv_heartbeat() {
             m.set_msg_type(MessageType::MsgHeartbeatResp);
             let _ = r.step(m);
             let _: Vec<Message> = r.msgs.drain(..).collect();
-            assert!(!r.prs.get_mut(&2).unwrap().ins.full());
+            assert!(!r.tracker.prs.get_mut(&2).unwrap().ins.full());
 
             // one slot
             let mut m = Message::new();
@@ -174,3 +175,266 @@ fn test_msg_app_flow_control_recv_heartbeat() {
         }
     }
 }
+
+fn propose(r: &mut Raft<MemStorage>) -> Vec<Message> {
+    let mut m = Message::new();
+    m.set_from(1);
+    m.set_to(1);
+    m.set_msg_type(MessageType::MsgProp);
+    let mut e = Entry::new();
+    e.set_data(Vec::from("somedata"));
+    m.set_entries(RepeatedField::from_vec(vec![e]));
+    let _ = r.step(m);
+    r.msgs.drain(..).collect()
+}
+
+// ensures that with adaptive inflight an unreachable report halves the
+// sending window, and acknowledgements grow it back.
+#[test]
+fn test_msg_app_flow_control_adaptive_window() {
+    let mut r = new_test_raft(1, vec![1, 2], 5, 1, MemStorage::new());
+    r.adaptive_inflight = true;
+    r.become_candidate();
+    r.become_leader();
+    r.tracker.prs.get_mut(&2).unwrap().become_replicate();
+
+    let mut m = Message::new();
+    m.set_from(2);
+    m.set_to(1);
+    m.set_msg_type(MessageType::MsgUnreachable);
+    let _ = r.step(m);
+    let window = r.max_inflight as usize / 2;
+    assert_eq!(r.tracker.prs.get(&2).unwrap().inflight_window, window);
+    r.tracker.prs.get_mut(&2).unwrap().become_replicate();
+
+    for _ in 0..window {
+        assert_eq!(propose(&mut r).len(), 1);
+    }
+    assert!(!r.tracker.prs.get(&2).unwrap().ins.full());
+    assert_eq!(propose(&mut r).len(), 0);
+
+    // a window worth of acknowledgements allows one more inflight message.
+    let first = r.tracker.prs.get(&2).unwrap().matched + 1;
+    for index in first..first + window as u64 {
+        let mut m = Message::new();
+        m.set_from(2);
+        m.set_to(1);
+        m.set_msg_type(MessageType::MsgAppResp);
+        m.set_index(index);
+        let _ = r.step(m);
+    }
+    assert_eq!(r.tracker.prs.get(&2).unwrap().inflight_window, window + 1);
+}
+
+// ensures that the sending window is also limited by the total size of the
+// inflight messages, and that acknowledgements free the budget.
+#[test]
+fn test_msg_app_flow_control_max_bytes() {
+    let mut c = new_test_config(1, vec![1, 2], 5, 1);
+    let size = {
+        let mut e = Entry::new();
+        e.set_data(Vec::from("somedata"));
+        e.set_index(1);
+        e.set_term(1);
+        u64::from(e.compute_size())
+    };
+    c.max_inflight_bytes = size * 3;
+    let mut r = Raft::new(&mut c, MemStorage::new());
+    r.become_candidate();
+    r.become_leader();
+    r.tracker.prs.get_mut(&2).unwrap().become_replicate();
+
+    for _ in 0..3 {
+        assert_eq!(propose(&mut r).len(), 1);
+    }
+    assert!(r.tracker.prs.get(&2).unwrap().ins.full());
+    assert_eq!(propose(&mut r).len(), 0);
+
+    let mut m = Message::new();
+    m.set_from(2);
+    m.set_to(1);
+    m.set_msg_type(MessageType::MsgAppResp);
+    m.set_index(2);
+    let _ = r.step(m);
+    // the freed budget is used right away for the pending entry.
+    let ms: Vec<Message> = r.msgs.drain(..).collect();
+    assert_eq!(ms.len(), 1);
+    assert_eq!(ms[0].get_entries()[0].get_index(), 5);
+}
+
+// ensures that a full send queue reported by the transport pauses appends to
+// the peer, and that draining it sends the pending entries right away.
+#[test]
+fn test_msg_app_flow_control_send_queue_full() {
+    let mut r = new_test_raft(1, vec![1, 2], 5, 1, MemStorage::new());
+    r.become_candidate();
+    r.become_leader();
+    r.tracker.prs.get_mut(&2).unwrap().become_replicate();
+
+    assert!(!r.report_send_queue(3, true));
+    assert!(r.report_send_queue(2, true));
+    for _ in 0..3 {
+        assert_eq!(propose(&mut r).len(), 0);
+    }
+
+    // heartbeats are not affected.
+    r.tick();
+    let ms: Vec<Message> = r.msgs.drain(..).collect();
+    assert_eq!(ms.len(), 1);
+    assert_eq!(ms[0].get_msg_type(), MessageType::MsgHeartbeat);
+
+    assert!(r.report_send_queue(2, false));
+    let ms: Vec<Message> = r.msgs.drain(..).collect();
+    assert_eq!(ms.len(), 1);
+    assert_eq!(ms[0].get_msg_type(), MessageType::MsgApp);
+    // the empty entry of the new leader and the three proposals.
+    assert_eq!(ms[0].get_entries().len(), 4);
+}
+
+// ensures that a follower advertises its append budget in MsgAppResp, and that
+// the leader sizes the next MsgApp to it.
+#[test]
+fn test_msg_app_flow_control_append_budget() {
+    let mut follower = new_test_raft(2, vec![1, 2], 5, 1, MemStorage::new());
+    follower.set_append_budget(100);
+    let mut m = Message::new();
+    m.set_from(1);
+    m.set_to(2);
+    m.set_term(1);
+    m.set_msg_type(MessageType::MsgApp);
+    let _ = follower.step(m);
+    let ms: Vec<Message> = follower.msgs.drain(..).collect();
+    assert_eq!(ms.len(), 1);
+    assert_eq!(ms[0].get_msg_type(), MessageType::MsgAppResp);
+    assert_eq!(ms[0].get_append_budget(), 100);
+
+    let mut r = new_test_raft(1, vec![1, 2], 5, 1, MemStorage::new());
+    r.become_candidate();
+    r.become_leader();
+    r.tracker.prs.get_mut(&2).unwrap().pause();
+    for _ in 0..10 {
+        propose(&mut r);
+    }
+
+    // one entry is 14 bytes, so 3 fit into the budget.
+    let mut m = Message::new();
+    m.set_from(2);
+    m.set_to(1);
+    m.set_msg_type(MessageType::MsgAppResp);
+    m.set_index(1);
+    m.set_append_budget(45);
+    let _ = r.step(m);
+    let ms: Vec<Message> = r.msgs.drain(..).collect();
+    assert_eq!(ms.len(), 1);
+    assert_eq!(ms[0].get_entries().len(), 3);
+
+    // the budget is lifted by the next response not carrying one.
+    let mut m = Message::new();
+    m.set_from(2);
+    m.set_to(1);
+    m.set_msg_type(MessageType::MsgAppResp);
+    m.set_index(4);
+    let _ = r.step(m);
+    let ms: Vec<Message> = r.msgs.drain(..).collect();
+    assert_eq!(ms[0].get_entries().len(), 7);
+}
+
+// ensures that once a probe is acked, the leader sends the whole backlog in
+// max_size_per_msg chunks instead of one message per response.
+#[test]
+fn test_msg_app_flow_control_chunked_backlog() {
+    let mut r = new_test_raft(1, vec![1, 2], 5, 1, MemStorage::new());
+    r.become_candidate();
+    r.become_leader();
+    r.tracker.prs.get_mut(&2).unwrap().pause();
+    for _ in 0..10 {
+        propose(&mut r);
+    }
+
+    // one entry is 14 bytes, so 2 fit into a message.
+    r.max_msg_size = 30;
+    let mut m = Message::new();
+    m.set_from(2);
+    m.set_to(1);
+    m.set_msg_type(MessageType::MsgAppResp);
+    m.set_index(1);
+    let _ = r.step(m);
+    let ms: Vec<Message> = r.msgs.drain(..).collect();
+    assert_eq!(ms.len(), 5);
+    let mut next = 2;
+    for m in &ms {
+        assert_eq!(m.get_msg_type(), MessageType::MsgApp);
+        assert_eq!(m.get_index(), next - 1);
+        assert_eq!(m.get_entries().len(), 2);
+        next += 2;
+    }
+    let pr = r.tracker.prs.get(&2).unwrap();
+    assert_eq!(pr.next, 12);
+    assert_eq!(pr.ins.count, 5);
+}
+
+// ensures that a peer in probe state is only sent a small append, and the
+// rest of the backlog once the probe is acked.
+#[test]
+fn test_msg_app_flow_control_probe_size() {
+    let mut r = new_test_raft(1, vec![1, 2], 5, 1, MemStorage::new());
+    r.become_candidate();
+    r.become_leader();
+    r.msgs.clear();
+    for _ in 0..5 {
+        let mut m = Message::new();
+        m.set_from(1);
+        m.set_to(1);
+        m.set_msg_type(MessageType::MsgProp);
+        let mut e = Entry::new();
+        e.set_data(vec![0; 3000]);
+        m.set_entries(RepeatedField::from_vec(vec![e]));
+        let _ = r.step(m);
+    }
+    // the noop entry and a single proposal fit in the probe, which then
+    // pauses the peer.
+    let ms: Vec<Message> = r.msgs.drain(..).collect();
+    assert_eq!(ms.len(), 1);
+    assert_eq!(ms[0].get_index(), 0);
+    assert_eq!(ms[0].get_entries().len(), 2);
+
+    let mut m = Message::new();
+    m.set_from(2);
+    m.set_to(1);
+    m.set_msg_type(MessageType::MsgAppResp);
+    m.set_index(2);
+    let _ = r.step(m);
+    let ms: Vec<Message> = r.msgs.drain(..).collect();
+    assert_eq!(ms.len(), 1);
+    assert_eq!(ms[0].get_index(), 2);
+    assert_eq!(ms[0].get_entries().len(), 4);
+}
+
+// ensures that under a replication budget voters are always sent to, and
+// learners share what is left round-robin.
+#[test]
+fn test_msg_app_flow_control_replication_budget() {
+    let mut c = new_test_config(1, vec![1, 2], 5, 1);
+    c.learners = vec![3, 4];
+    let mut r = Raft::new(&mut c, MemStorage::new());
+    r.become_candidate();
+    r.become_leader();
+    for id in 2..5 {
+        r.get_mut_progress(id).unwrap().become_replicate();
+    }
+
+    // the first append also carries the empty entry of the new leader.
+    assert_eq!(propose(&mut r).len(), 3);
+    let ms = propose(&mut r);
+    assert_eq!(ms.len(), 3);
+    // room for the voter and a single learner.
+    r.replication_budget = 2 * u64::from(ms[0].compute_size());
+
+    for &wlearner in &[3, 4, 3] {
+        r.tick();
+        r.msgs.clear();
+        let mut tos: Vec<u64> = propose(&mut r).iter().map(|m| m.get_to()).collect();
+        tos.sort();
+        assert_eq!(tos, vec![2, wlearner]);
+    }
+}
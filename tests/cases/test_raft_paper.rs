@@ -149,7 +149,7 @@ fn non_leader_start_election(state: StateType) {
 
     assert_eq!(r.term, 2);
     assert_eq!(r.state, StateType::Candidate);
-    assert_eq!(r.votes.get(&r.id).unwrap(), &true);
+    assert_eq!(r.tracker.votes.get(&r.id).unwrap(), &true);
 
     let mut msgs: Vec<Message> = r.msgs.drain(..).collect();
     msgs.sort_by(|a, b| a.get_to().cmp(&b.get_to()));
@@ -660,15 +660,16 @@ fn test_follower_check_msg_app() {
     let ents = vec![new_entry(1, 1), new_entry(2, 2)];
 
     let tests = vec![
-        (0, 0, 1, false, 0),
-        (ents[0].get_term(), ents[0].get_index(), 1, false, 0),
-        (ents[1].get_term(), ents[1].get_index(), 2, false, 0),
+        (0, 0, 1, false, 0, 0),
+        (ents[0].get_term(), ents[0].get_index(), 1, false, 0, 0),
+        (ents[1].get_term(), ents[1].get_index(), 2, false, 0, 0),
         (
             ents[0].get_term(),
             ents[1].get_index(),
             ents[1].get_index(),
             true,
-            2,
+            1,
+            1,
         ),
         (
             ents[0].get_term() + 1,
@@ -676,10 +677,11 @@ fn test_follower_check_msg_app() {
             ents[1].get_index() + 1,
             true,
             2,
+            2,
         ),
     ];
 
-    for (term, index, windex, wreject, wreject_hint) in tests {
+    for (term, index, windex, wreject, wreject_hint, wlog_term) in tests {
         let mut s = MemStorage::new();
         let _ = s.append(&ents);
         let mut r = new_test_raft(1, vec![1, 2, 3], 10, 1, s);
@@ -706,6 +708,7 @@ fn test_follower_check_msg_app() {
         m1.set_index(windex);
         m1.set_reject(wreject);
         m1.set_reject_hint(wreject_hint);
+        m1.set_log_term(wlog_term);
 
         let wmsgs = vec![m1];
         assert_eq!(wmsgs, msgs);
@@ -1,8 +1,13 @@
-use libraft::raftpb::{Entry, Message, MessageType, Snapshot};
+use std::cell::Cell;
+use std::rc::Rc;
+
+use libraft::errors::{Error, Result, StorageError};
+use libraft::progress::ProgressState;
+use libraft::raftpb::{ConfState, Entry, HardState, Message, MessageType, Snapshot};
 use libraft::storage::{MemStorage, Storage};
 use protobuf::RepeatedField;
 
-use cases::test_raft::{new_snapshot, new_test_raft};
+use cases::test_raft::{new_message, new_snapshot, new_test_raft};
 
 fn test_snapshot() -> Snapshot {
     new_snapshot(11, 11, vec![], vec![1, 2])
@@ -16,16 +21,16 @@ fn test_sending_snapshot_set_pending_snapshot() {
     sm.become_candidate();
     sm.become_leader();
 
-    sm.prs.get_mut(&2).unwrap().next = sm.raft_log.first_index();
+    sm.tracker.prs.get_mut(&2).unwrap().next = sm.raft_log.first_index();
 
     let mut m = Message::new();
     m.set_from(2);
     m.set_to(1);
     m.set_msg_type(MessageType::MsgAppResp);
-    m.set_index(sm.prs.get(&2).unwrap().next - 1);
+    m.set_index(sm.tracker.prs.get(&2).unwrap().next - 1);
     m.set_reject(true);
     let _ = sm.step(m);
-    assert_eq!(sm.prs.get(&2).unwrap().pending_snapshot, 11);
+    assert_eq!(sm.tracker.prs.get(&2).unwrap().pending_snapshot, 11);
 }
 
 #[test]
@@ -35,7 +40,7 @@ fn test_pending_snapshot_pause_replcation() {
     sm.become_candidate();
     sm.become_leader();
 
-    sm.prs.get_mut(&2).unwrap().become_snapshot(11);
+    sm.tracker.prs.get_mut(&2).unwrap().become_snapshot(11);
 
     let mut m = Message::new();
     m.set_from(1);
@@ -56,8 +61,8 @@ fn test_snapshot_failure() {
     sm.become_candidate();
     sm.become_leader();
 
-    sm.prs.get_mut(&2).unwrap().next = 1;
-    sm.prs.get_mut(&2).unwrap().become_snapshot(11);
+    sm.tracker.prs.get_mut(&2).unwrap().next = 1;
+    sm.tracker.prs.get_mut(&2).unwrap().become_snapshot(11);
 
     let mut m = Message::new();
     m.set_from(2);
@@ -65,9 +70,9 @@ fn test_snapshot_failure() {
     m.set_msg_type(MessageType::MsgSnapStatus);
     m.set_reject(true);
     let _ = sm.step(m);
-    assert_eq!(sm.prs.get(&2).unwrap().pending_snapshot, 0);
-    assert_eq!(sm.prs.get(&2).unwrap().next, 1);
-    assert!(sm.prs.get(&2).unwrap().paused);
+    assert_eq!(sm.tracker.prs.get(&2).unwrap().pending_snapshot, 0);
+    assert_eq!(sm.tracker.prs.get(&2).unwrap().next, 1);
+    assert!(sm.tracker.prs.get(&2).unwrap().paused);
 }
 
 #[test]
@@ -77,17 +82,17 @@ fn test_snapshot_succeed() {
     sm.become_candidate();
     sm.become_leader();
 
-    sm.prs.get_mut(&2).unwrap().next = 1;
-    sm.prs.get_mut(&2).unwrap().become_snapshot(11);
+    sm.tracker.prs.get_mut(&2).unwrap().next = 1;
+    sm.tracker.prs.get_mut(&2).unwrap().become_snapshot(11);
     let mut m = Message::new();
     m.set_from(2);
     m.set_to(1);
     m.set_msg_type(MessageType::MsgSnapStatus);
     m.set_reject(false);
     let _ = sm.step(m);
-    assert_eq!(sm.prs.get(&2).unwrap().pending_snapshot, 0);
-    assert_eq!(sm.prs.get(&2).unwrap().next, 12);
-    assert!(sm.prs.get(&2).unwrap().paused);
+    assert_eq!(sm.tracker.prs.get(&2).unwrap().pending_snapshot, 0);
+    assert_eq!(sm.tracker.prs.get(&2).unwrap().next, 12);
+    assert!(sm.tracker.prs.get(&2).unwrap().paused);
 }
 
 #[test]
@@ -97,14 +102,127 @@ fn test_snapshot_abort() {
     sm.become_candidate();
     sm.become_leader();
 
-    sm.prs.get_mut(&2).unwrap().next = 1;
-    sm.prs.get_mut(&2).unwrap().become_snapshot(11);
+    sm.tracker.prs.get_mut(&2).unwrap().next = 1;
+    sm.tracker.prs.get_mut(&2).unwrap().become_snapshot(11);
     let mut m = Message::new();
     m.set_from(2);
     m.set_to(1);
     m.set_msg_type(MessageType::MsgAppResp);
     m.set_index(11);
     let _ = sm.step(m);
-    assert_eq!(sm.prs.get(&2).unwrap().pending_snapshot, 0);
-    assert_eq!(sm.prs.get(&2).unwrap().next, 12);
+    assert_eq!(sm.tracker.prs.get(&2).unwrap().pending_snapshot, 0);
+    assert_eq!(sm.tracker.prs.get(&2).unwrap().next, 12);
+}
+
+#[test]
+fn test_force_snapshot() {
+    let mut sm = new_test_raft(1, vec![1, 2], 10, 1, MemStorage::new());
+    sm.restore(test_snapshot());
+    assert!(!sm.force_snapshot(2));
+    sm.become_candidate();
+    sm.become_leader();
+    sm.tracker.prs.get_mut(&2).unwrap().maybe_update(12);
+    sm.tracker.prs.get_mut(&2).unwrap().become_replicate();
+
+    assert!(!sm.force_snapshot(1));
+    assert!(!sm.force_snapshot(3));
+    assert!(sm.force_snapshot(2));
+    let msgs: Vec<Message> = sm.msgs.drain(..).collect();
+    assert_eq!(msgs.len(), 1);
+    assert_eq!(msgs[0].get_msg_type(), MessageType::MsgSnap);
+    assert_eq!(msgs[0].get_to(), 2);
+    assert_eq!(msgs[0].get_snapshot().get_metadata().get_index(), 11);
+    let pr = sm.tracker.prs.get(&2).unwrap();
+    assert_eq!(pr.state, ProgressState::Snapshot);
+    assert_eq!(pr.pending_snapshot, 11);
+    assert_eq!(pr.matched, 0);
+}
+
+#[test]
+fn test_force_snapshot_unavailable() {
+    let mut sm = new_test_raft(1, vec![1, 2], 10, 1, MemStorage::new());
+    sm.become_candidate();
+    sm.become_leader();
+    assert!(!sm.force_snapshot(2));
+    assert!(sm.msgs.is_empty());
+    assert_eq!(sm.tracker.prs.get(&2).unwrap().state, ProgressState::Probe);
+}
+
+// SnapshotLaterStorage reports its snapshot as temporarily unavailable until
+// ready is set.
+struct SnapshotLaterStorage {
+    storage: MemStorage,
+    ready: Rc<Cell<bool>>,
+}
+
+impl Storage for SnapshotLaterStorage {
+    fn initial_state(&self) -> Result<(HardState, ConfState)> {
+        self.storage.initial_state()
+    }
+
+    fn entries(&self, low: u64, high: u64, max_size: u64) -> Result<Vec<Entry>> {
+        self.storage.entries(low, high, max_size)
+    }
+
+    fn term(&self, idx: u64) -> Result<u64> {
+        self.storage.term(idx)
+    }
+
+    fn first_index(&self) -> Result<u64> {
+        self.storage.first_index()
+    }
+
+    fn last_index(&self) -> Result<u64> {
+        self.storage.last_index()
+    }
+
+    fn snapshot(&self) -> Result<Snapshot> {
+        if !self.ready.get() {
+            return Err(Error::Storage(StorageError::SnapshotTemporarilyUnavailable));
+        }
+        self.storage.snapshot()
+    }
+}
+
+// ensures that a snapshot which was temporarily unavailable is sent as soon
+// as the application reports it generated.
+#[test]
+fn test_snapshot_ready() {
+    let mut s = MemStorage::new();
+    s.apply_snapshot(test_snapshot()).unwrap();
+    let ready = Rc::new(Cell::new(false));
+    let storage = SnapshotLaterStorage {
+        storage: s,
+        ready: ready.clone(),
+    };
+    let mut sm = new_test_raft(1, vec![], 10, 1, storage);
+    sm.become_candidate();
+    sm.become_leader();
+    sm.msgs.clear();
+
+    sm.tracker.prs.get_mut(&2).unwrap().recent_active = true;
+    let mut m = new_message(2, 1, MessageType::MsgAppResp);
+    m.set_term(sm.term);
+    m.set_index(sm.tracker.prs.get(&2).unwrap().next - 1);
+    m.set_reject(true);
+    m.set_reject_hint(5);
+    sm.step(m).unwrap();
+    assert!(sm.msgs.is_empty());
+    assert_eq!(sm.tracker.prs.get(&2).unwrap().state, ProgressState::Probe);
+
+    // a stale snapshot doesn't help.
+    ready.set(true);
+    sm.snapshot_ready(5);
+    assert!(sm.msgs.is_empty());
+
+    sm.snapshot_ready(11);
+    let msgs: Vec<Message> = sm.msgs.drain(..).collect();
+    assert_eq!(msgs.len(), 1);
+    assert_eq!(msgs[0].get_msg_type(), MessageType::MsgSnap);
+    assert_eq!(msgs[0].get_to(), 2);
+    assert_eq!(sm.tracker.prs.get(&2).unwrap().state, ProgressState::Snapshot);
+
+    // nobody is waiting any more.
+    sm.snapshot_ready(11);
+    assert!(sm.msgs.is_empty());
 }
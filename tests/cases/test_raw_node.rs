@@ -1,17 +1,19 @@
+use libraft::chunk::{is_chunk, ChunkAssembler};
 use libraft::errors::Error;
 use libraft::raft::{Peer, StateType, Status, NONE};
 use libraft::raftpb::{
-    ConfChange, ConfChangeType, ConfState, Entry, EntryType, HardState, Message, MessageType,
-    Snapshot, SnapshotMetadata,
+    ConfChange, ConfChangeSingle, ConfChangeTransition, ConfChangeType, ConfChangeV2, ConfState,
+    Entry, EntryType, HardState, HardStateExtension, Message, MessageType, Snapshot,
+    SnapshotMetadata,
 };
-use libraft::raw_node::RawNode;
+use libraft::raw_node::{RawNode, Ready};
 use libraft::read_only::ReadState;
 use libraft::storage::{MemStorage, Storage};
 use libraft::util::{is_local_msg, NO_LIMIT};
 
-use protobuf::{self, ProtobufEnum};
+use protobuf::{self, Message as PbMessage, ProtobufEnum};
 
-use cases::test_raft::new_test_config;
+use cases::test_raft::{new_message, new_snapshot, new_test_config};
 
 #[test]
 fn test_raw_node_step() {
@@ -76,10 +78,10 @@ fn test_raw_node_proposal_and_conf_change() {
         let rd = raw_node.ready();
         let _ = s.append(&rd.entries);
         if !proposed && rd.soft_state.as_ref().unwrap().lead == raw_node.raft.id {
-            let _ = raw_node.propose(Vec::from("somedata"));
+            let _ = raw_node.propose(vec![], Vec::from("somedata"));
             let mut cc = ConfChange::new();
             cc.set_change_type(ConfChangeType::ConfChangeAddNode);
-            cc.set_node_id(1);
+            cc.set_node_id(2);
             ccdata = protobuf::Message::write_to_bytes(&cc).expect("unexpected marshal error");
 
             let _ = raw_node.propose_conf_change(&cc);
@@ -101,8 +103,8 @@ fn test_raw_node_proposal_and_conf_change() {
     assert_eq!(ents[1].take_data(), ccdata);
 }
 
-// ensures that two proposes to add the same node should
-// not affect the later propose to add new node.
+// ensures that proposals to add a node already in the config are rejected,
+// and don't affect the later propose to add new node.
 #[test]
 fn test_raw_node_proposal_add_duplicate_node() {
     let mut s = MemStorage::new();
@@ -131,39 +133,46 @@ fn test_raw_node_proposal_add_duplicate_node() {
         raw_node.advance(rd);
     }
 
-    let mut propose_conf_change_and_apply = |cc: &ConfChange| {
-        let _ = raw_node.propose_conf_change(cc);
+    let mut propose_conf_change_and_apply = |cc: &ConfChange| -> Result<(), Error> {
+        raw_node.propose_conf_change(cc)?;
         let rd = raw_node.ready();
         let _ = s.write_lock().append(&rd.entries);
         for e in &rd.committed_entries {
             if e.get_entry_type() == EntryType::EntryConfChange {
                 let conf_change = protobuf::parse_from_bytes(e.get_data()).unwrap();
-                raw_node.apply_conf_change(&conf_change);
+                raw_node.apply_conf_change(&conf_change).unwrap();
             }
         }
 
         raw_node.advance(rd);
+        Ok(())
     };
 
     let mut cc1 = ConfChange::new();
     cc1.set_change_type(ConfChangeType::ConfChangeAddNode);
     cc1.set_node_id(1);
-    let ccdata1 = protobuf::Message::write_to_bytes(&cc1).expect("unexpected marshal error");
-    propose_conf_change_and_apply(&cc1);
-    propose_conf_change_and_apply(&cc1);
+    assert_eq!(
+        propose_conf_change_and_apply(&cc1),
+        Err(Error::ConfChangeInvalid("can't add 1, already a voter".to_string()))
+    );
 
     let mut cc2 = ConfChange::new();
     cc2.set_change_type(ConfChangeType::ConfChangeAddNode);
     cc2.set_node_id(2);
     let ccdata2 = protobuf::Message::write_to_bytes(&cc2).expect("unexpected marshal error");
+    assert_eq!(propose_conf_change_and_apply(&cc2), Ok(()));
+    assert_eq!(
+        propose_conf_change_and_apply(&cc2),
+        Err(Error::ConfChangeInvalid("can't add 2, already a voter".to_string()))
+    );
 
-    propose_conf_change_and_apply(&cc2);
-
+    // only the valid proposal made it to the log, after the leader's empty
+    // entry.
     let last_index = s.last_index().unwrap();
-    let mut entries = s.entries(last_index - 2, last_index + 1, NO_LIMIT).unwrap();
-    assert_eq!(entries.len(), 3);
-    assert_eq!(entries[0].take_data(), ccdata1);
-    assert_eq!(entries[2].take_data(), ccdata2);
+    let mut entries = s.entries(last_index - 1, last_index + 1, NO_LIMIT).unwrap();
+    assert_eq!(entries.len(), 2);
+    assert!(entries[0].get_data().is_empty());
+    assert_eq!(entries[1].take_data(), ccdata2);
 }
 
 #[test]
@@ -256,7 +265,7 @@ fn test_raw_node_start() {
     let _ = s.append(&rd.entries);
     raw_node.advance(rd.clone());
 
-    let _ = raw_node.propose(Vec::from("foo"));
+    let _ = raw_node.propose(vec![], Vec::from("foo"));
     let rd = raw_node.ready();
 
     assert_eq!(rd.hard_state.get_term(), 2);
@@ -307,6 +316,146 @@ fn test_raw_node_restart() {
     assert_eq!(raw_node.has_ready(), false);
 }
 
+// ensures that a restarted node returns its committed entries across several
+// Readies when they exceed max_committed_size_per_ready.
+#[test]
+fn test_raw_node_restart_committed_size_per_ready() {
+    let mut st = HardState::new();
+    st.set_term(1);
+    st.set_commit(5);
+    let entries: Vec<Entry> = (1..6)
+        .map(|i| {
+            let mut e = Entry::new();
+            e.set_term(1);
+            e.set_index(i);
+            e.set_data(b"somedata".to_vec());
+            e
+        })
+        .collect();
+    let size = u64::from(entries[0].compute_size());
+    let mut s = MemStorage::new();
+    s.set_hard_state(st);
+    let _ = s.append(&entries);
+
+    let mut c = new_test_config(1, vec![1], 10, 1);
+    c.max_committed_size_per_ready = size * 2;
+    let mut raw_node = RawNode::new(&mut c, s, vec![]).unwrap();
+    for wents in &[&entries[0..2], &entries[2..4], &entries[4..5]] {
+        assert!(raw_node.has_ready());
+        let rd = raw_node.ready();
+        assert_eq!(rd.committed_entries, wents.to_vec());
+        raw_node.advance(rd);
+    }
+    assert!(!raw_node.has_ready());
+    assert_eq!(raw_node.raft.raft_log.applied, 5);
+}
+
+// ensures that a restarted node skips the entries up to the applied index
+// persisted in storage.
+#[test]
+fn test_raw_node_restart_with_stored_applied() {
+    let mut st = HardState::new();
+    st.set_term(1);
+    st.set_commit(3);
+    let entries: Vec<Entry> = (1..4)
+        .map(|i| {
+            let mut e = Entry::new();
+            e.set_term(1);
+            e.set_index(i);
+            e
+        })
+        .collect();
+    let mut s = MemStorage::new();
+    s.set_hard_state(st);
+    let _ = s.append(&entries);
+    s.set_applied(2);
+
+    let mut raw_node = RawNode::new(&mut new_test_config(1, vec![1], 10, 1), s, vec![]).unwrap();
+    let rd = raw_node.ready();
+    assert_eq!(rd.committed_entries.len(), 1);
+    assert_eq!(rd.committed_entries[0].get_index(), 3);
+}
+
+// ensures that fields of the persisted HardState unknown to this version
+// are written back with the next HardState instead of being dropped.
+#[test]
+fn test_raw_node_restart_preserves_hard_state_unknown_fields() {
+    let mut st = HardState::new();
+    st.set_term(1);
+    st.set_commit(1);
+    st.mut_unknown_fields().add_varint(100, 42);
+    let mut e1 = Entry::new();
+    e1.set_term(1);
+    e1.set_index(1);
+    let mut s = MemStorage::new();
+    s.set_hard_state(st);
+    let _ = s.append(&[e1]);
+
+    let mut c = new_test_config(1, vec![1], 10, 1);
+    let mut raw_node = RawNode::new(
+        &mut c,
+        s.clone(),
+        vec![Peer {
+            context: Default::default(),
+            id: 1,
+        }],
+    ).unwrap();
+    let rd = raw_node.ready();
+    raw_node.advance(rd);
+
+    let _ = raw_node.campaign();
+    let rd = raw_node.ready();
+    assert_eq!(rd.hard_state.get_term(), 2);
+    assert_eq!(
+        rd.hard_state.get_unknown_fields().get(100).unwrap().varint,
+        vec![42]
+    );
+}
+
+// ensures that HardState extensions are loaded on restart, and that updating
+// them produces a HardState which must be synced, keeping unrelated ones.
+#[test]
+fn test_raw_node_hard_state_extensions() {
+    let mut st = HardState::new();
+    st.set_term(1);
+    st.set_commit(1);
+    for &(id, data) in &[(7, b"seven"), (1, b"one..")] {
+        let mut ext = HardStateExtension::new();
+        ext.set_id(id);
+        ext.set_data(data.to_vec());
+        st.mut_extensions().push(ext);
+    }
+    let mut e1 = Entry::new();
+    e1.set_term(1);
+    e1.set_index(1);
+    let mut s = MemStorage::new();
+    s.set_hard_state(st);
+    let _ = s.append(&[e1]);
+
+    let mut raw_node = RawNode::new(&mut new_test_config(1, vec![1], 10, 1), s, vec![]).unwrap();
+    let rd = raw_node.ready();
+    raw_node.advance(rd);
+    assert_eq!(raw_node.raft.hard_state_extension(1), Some(&b"one.."[..]));
+    assert_eq!(raw_node.raft.hard_state_extension(3), None);
+    assert!(!raw_node.has_ready());
+
+    raw_node.raft.set_hard_state_extension(3, b"three".to_vec());
+    raw_node.raft.set_hard_state_extension(7, vec![]);
+    assert!(raw_node.has_ready());
+    let rd = raw_node.ready();
+    assert!(rd.must_sync);
+    let ids: Vec<u32> = rd
+        .hard_state
+        .get_extensions()
+        .iter()
+        .map(|e| e.get_id())
+        .collect();
+    assert_eq!(ids, vec![1, 3]);
+    assert_eq!(rd.hard_state.get_extensions()[1].get_data(), b"three");
+    raw_node.advance(rd);
+    assert!(!raw_node.has_ready());
+}
+
 #[test]
 fn test_raw_node_restart_from_snapshot() {
     let mut snap = Snapshot::new();
@@ -350,6 +499,40 @@ fn test_raw_node_restart_from_snapshot() {
     assert_eq!(raw_node.has_ready(), false);
 }
 
+// ensures that a snapshot received by a follower is reported in a single
+// Ready, and no longer once that Ready has been advanced.
+#[test]
+fn test_raw_node_ready_snapshot() {
+    assert!(!Ready::default().contains_updates());
+
+    let mut s = MemStorage::new();
+    let mut c = new_test_config(1, vec![], 10, 1);
+    let peers = vec![1, 2]
+        .into_iter()
+        .map(|id| Peer {
+            context: Default::default(),
+            id,
+        }).collect();
+    let mut raw_node = RawNode::new(&mut c, s.clone(), peers).unwrap();
+    let rd = raw_node.ready();
+    let _ = s.append(&rd.entries);
+    raw_node.advance(rd);
+    assert!(!raw_node.has_ready());
+
+    let mut m = new_message(2, 1, MessageType::MsgSnap);
+    m.set_term(2);
+    m.set_snapshot(new_snapshot(11, 2, vec![], vec![1, 2]));
+    raw_node.step(m).unwrap();
+    let rd = raw_node.ready();
+    assert!(rd.contains_updates());
+    assert_eq!(rd.snapshot.get_metadata().get_index(), 11);
+    let _ = s.apply_snapshot(rd.snapshot.clone());
+    raw_node.advance(rd);
+
+    assert!(!raw_node.has_ready());
+    assert!(!raw_node.ready().contains_updates());
+}
+
 #[test]
 fn test_raw_node_status() {
     let mut c = new_test_config(1, vec![], 10, 1);
@@ -375,3 +558,639 @@ fn test_raw_node_status() {
         }
     );
 }
+
+// ensures that a conf change rejected by the registered filter is
+// not applied to the local node.
+#[test]
+fn test_raw_node_conf_change_filter() {
+    let mut raw_node = RawNode::new(
+        &mut new_test_config(1, vec![], 10, 1),
+        MemStorage::new(),
+        vec![Peer {
+            context: Default::default(),
+            id: 1,
+        }],
+    ).unwrap();
+    raw_node.set_conf_change_filter(|cc| cc.get_context() != b"stale");
+
+    let mut cc = ConfChange::new();
+    cc.set_change_type(ConfChangeType::ConfChangeAddNode);
+    cc.set_node_id(2);
+    cc.set_context(b"stale".to_vec());
+    let cs = raw_node.apply_conf_change(&cc).unwrap();
+    assert_eq!(cs.get_nodes(), &[1]);
+    assert_eq!(raw_node.raft.nodes(), vec![1]);
+
+    cc.set_context(b"current".to_vec());
+    let cs = raw_node.apply_conf_change(&cc).unwrap();
+    assert_eq!(cs.get_nodes(), &[1, 2]);
+    assert_eq!(raw_node.raft.nodes(), vec![1, 2]);
+}
+
+// ensures that the filter sees the context of a ConfChangeV2, both for a
+// single change applied directly and for a joint change.
+#[test]
+fn test_raw_node_conf_change_v2_filter() {
+    let mut raw_node = RawNode::new(
+        &mut new_test_config(1, vec![], 10, 1),
+        MemStorage::new(),
+        vec![Peer {
+            context: Default::default(),
+            id: 1,
+        }],
+    ).unwrap();
+    raw_node.set_conf_change_filter(|cc| cc.get_context() != b"stale");
+
+    let conf_change = |ids: &[u64], ctx: &[u8]| {
+        let mut cc = ConfChangeV2::new();
+        for &id in ids {
+            let mut c = ConfChangeSingle::new();
+            c.set_change_type(ConfChangeType::ConfChangeAddNode);
+            c.set_node_id(id);
+            cc.mut_changes().push(c);
+        }
+        cc.set_context(ctx.to_vec());
+        cc
+    };
+
+    let cs = raw_node.apply_conf_change_v2(&conf_change(&[2], b"stale")).unwrap();
+    assert_eq!(cs.get_nodes(), &[1]);
+    let cs = raw_node.apply_conf_change_v2(&conf_change(&[2], b"current")).unwrap();
+    assert_eq!(cs.get_nodes(), &[1, 2]);
+
+    let cs = raw_node.apply_conf_change_v2(&conf_change(&[3, 4], b"stale")).unwrap();
+    assert_eq!(cs.get_nodes(), &[1, 2]);
+    assert!(!raw_node.raft.is_joint());
+    let cs = raw_node.apply_conf_change_v2(&conf_change(&[3, 4], b"current")).unwrap();
+    assert_eq!(cs.get_nodes(), &[1, 2, 3, 4]);
+    assert!(raw_node.raft.is_joint());
+}
+
+// ensures that conf changes which would leave the group without a voter, or
+// change a node twice, are rejected without touching the peer set.
+#[test]
+fn test_raw_node_conf_change_invalid() {
+    let mut raw_node = RawNode::new(
+        &mut new_test_config(1, vec![], 10, 1),
+        MemStorage::new(),
+        vec![Peer {
+            context: Default::default(),
+            id: 1,
+        }],
+    ).unwrap();
+
+    let mut cc = ConfChange::new();
+    cc.set_change_type(ConfChangeType::ConfChangeRemoveNode);
+    cc.set_node_id(1);
+    assert_eq!(
+        raw_node.apply_conf_change(&cc),
+        Err(Error::ConfChangeInvalid(
+            "can't remove 1, the last voter".to_string()
+        ))
+    );
+    cc.set_change_type(ConfChangeType::ConfChangeAddLearnerNode);
+    assert_eq!(
+        raw_node.apply_conf_change(&cc),
+        Err(Error::ConfChangeInvalid(
+            "can't demote 1, the only voter".to_string()
+        ))
+    );
+    assert_eq!(raw_node.raft.nodes(), vec![1]);
+    assert!(raw_node.raft.learner_nodes().is_empty());
+
+    let mut cc = ConfChangeV2::new();
+    for &(t, id) in &[
+        (ConfChangeType::ConfChangeAddNode, 2),
+        (ConfChangeType::ConfChangeRemoveNode, 2),
+    ] {
+        let mut c = ConfChangeSingle::new();
+        c.set_change_type(t);
+        c.set_node_id(id);
+        cc.mut_changes().push(c);
+    }
+    assert_eq!(
+        raw_node.apply_conf_change_v2(&cc),
+        Err(Error::ConfChangeInvalid(
+            "node 2 is changed more than once".to_string()
+        ))
+    );
+
+    let mut c = ConfChangeSingle::new();
+    c.set_change_type(ConfChangeType::ConfChangeRemoveNode);
+    c.set_node_id(1);
+    let mut cc = ConfChangeV2::new();
+    cc.set_transition(ConfChangeTransition::ConfChangeTransitionJointExplicit);
+    cc.mut_changes().push(c);
+    assert_eq!(
+        raw_node.apply_conf_change_v2(&cc),
+        Err(Error::ConfChangeInvalid("removed all voters".to_string()))
+    );
+    assert!(!raw_node.raft.is_joint());
+    assert_eq!(raw_node.raft.nodes(), vec![1]);
+    assert!(!raw_node.raft.tracker.prs.contains_key(&2));
+}
+
+// ensures that aborting a leadership transfer lets the leader accept
+// proposals again.
+#[test]
+fn test_raw_node_abort_leader_transfer() {
+    let mut raw_node = RawNode::new(
+        &mut new_test_config(1, vec![1, 2], 10, 1),
+        MemStorage::new(),
+        vec![],
+    ).unwrap();
+    raw_node.campaign().unwrap();
+    let mut m = new_message(2, 1, MessageType::MsgVoteResp);
+    m.set_term(raw_node.raft.term);
+    raw_node.step(m).unwrap();
+    assert_eq!(raw_node.raft.state, StateType::Leader);
+
+    let rd = raw_node.ready();
+    raw_node.advance(rd);
+    assert!(!raw_node.has_ready());
+    raw_node.transfer_leader(2);
+    assert_eq!(raw_node.raft.lead_transferee, 2);
+    assert!(raw_node.has_ready());
+    let rd = raw_node.ready();
+    assert_eq!(rd.soft_state.unwrap().lead_transferee, 2);
+    assert_eq!(raw_node.status().soft_state.lead_transferee, 2);
+    assert_eq!(
+        raw_node.propose(vec![], b"foo".to_vec()),
+        Err(Error::ProposalDropped)
+    );
+
+    raw_node.abort_leader_transfer();
+    assert_eq!(raw_node.raft.lead_transferee, NONE);
+    assert_eq!(raw_node.propose(vec![], b"foo".to_vec()), Ok(()));
+}
+
+// ensures that a chunked proposal is appended and committed as consecutive
+// entries which reassemble into the original payload.
+#[test]
+fn test_raw_node_propose_chunked() {
+    let mut s = MemStorage::new();
+    let mut raw_node = RawNode::new(
+        &mut new_test_config(1, vec![], 10, 1),
+        s.clone(),
+        vec![Peer {
+            context: Default::default(),
+            id: 1,
+        }],
+    ).unwrap();
+    let rd = raw_node.ready();
+    let _ = s.append(&rd.entries);
+    raw_node.advance(rd);
+    let _ = raw_node.campaign();
+    let rd = raw_node.ready();
+    let _ = s.append(&rd.entries);
+    raw_node.advance(rd);
+
+    let data: Vec<u8> = (0..10).collect();
+    raw_node.propose_chunked(1, &data, 4).unwrap();
+    let rd = raw_node.ready();
+    assert_eq!(rd.entries.len(), 3);
+    assert_eq!(rd.committed_entries.len(), 3);
+
+    let mut assembler = ChunkAssembler::new();
+    let mut applied = vec![];
+    for e in &rd.committed_entries {
+        assert!(is_chunk(e.get_data()));
+        if let Some(payload) = assembler.push(e.get_data()) {
+            applied.push(payload);
+        }
+    }
+    assert_eq!(applied, vec![data]);
+}
+
+// ensures that apply_committed_entries applies the committed conf changes and
+// hands only the normal entries to the application, in order.
+#[test]
+fn test_raw_node_apply_committed_entries() {
+    let mut s = MemStorage::new();
+    let mut raw_node = RawNode::new(
+        &mut new_test_config(1, vec![], 10, 1),
+        s.clone(),
+        vec![Peer {
+            context: Default::default(),
+            id: 1,
+        }],
+    ).unwrap();
+    let rd = raw_node.ready();
+    let _ = s.append(&rd.entries);
+    let cs = raw_node
+        .apply_committed_entries(&rd.committed_entries, |_| panic!("unexpected normal entry"))
+        .unwrap();
+    assert_eq!(cs.unwrap().get_nodes(), &[1]);
+    raw_node.advance(rd);
+    let _ = raw_node.campaign();
+    let rd = raw_node.ready();
+    let _ = s.append(&rd.entries);
+    raw_node.advance(rd);
+
+    let _ = raw_node.propose(vec![], b"a".to_vec());
+    let mut cc = ConfChange::new();
+    cc.set_change_type(ConfChangeType::ConfChangeAddLearnerNode);
+    cc.set_node_id(2);
+    let _ = raw_node.propose_conf_change(&cc);
+    let rd = raw_node.ready();
+    let _ = s.append(&rd.entries);
+    raw_node.advance(rd);
+    let _ = raw_node.propose(vec![], b"b".to_vec());
+    let rd = raw_node.ready();
+    let _ = s.append(&rd.entries);
+
+    let committed = s.entries(3, s.last_index().unwrap() + 1, NO_LIMIT).unwrap();
+    let mut applied = vec![];
+    let cs = raw_node
+        .apply_committed_entries(&committed, |e| applied.push(e.get_data().to_vec()))
+        .unwrap();
+    assert_eq!(applied, vec![b"a".to_vec(), b"b".to_vec()]);
+    let cs = cs.unwrap();
+    assert_eq!(cs.get_nodes(), &[1]);
+    assert_eq!(cs.get_learners(), &[2]);
+    assert_eq!(raw_node.raft.learner_nodes(), vec![2]);
+
+    assert!(raw_node.apply_committed_entries(&committed[..1], |_| {}).unwrap().is_none());
+}
+
+// ensures that invalid committed conf changes are skipped without stopping
+// the entries after them from being applied.
+#[test]
+fn test_raw_node_apply_committed_entries_invalid_conf_change() {
+    let mut raw_node = RawNode::new(
+        &mut new_test_config(1, vec![], 10, 1),
+        MemStorage::new(),
+        vec![Peer {
+            context: Default::default(),
+            id: 1,
+        }],
+    ).unwrap();
+
+    let normal = |data: &[u8]| {
+        let mut e = Entry::new();
+        e.set_data(data.to_vec());
+        e
+    };
+    let conf_change = |t: ConfChangeType, id: u64| {
+        let mut cc = ConfChange::new();
+        cc.set_change_type(t);
+        cc.set_node_id(id);
+        let mut e = Entry::new();
+        e.set_entry_type(EntryType::EntryConfChange);
+        e.set_data(protobuf::Message::write_to_bytes(&cc).unwrap());
+        e
+    };
+    // leaving a joint config while not in one.
+    let mut leave = Entry::new();
+    leave.set_entry_type(EntryType::EntryConfChangeV2);
+    leave.set_data(protobuf::Message::write_to_bytes(&ConfChangeV2::new()).unwrap());
+
+    let ents = vec![
+        normal(b"a"),
+        conf_change(ConfChangeType::ConfChangeRemoveNode, 1),
+        normal(b"b"),
+        leave.clone(),
+        conf_change(ConfChangeType::ConfChangeAddLearnerNode, 2),
+        normal(b"c"),
+    ];
+    let mut applied = vec![];
+    let cs = raw_node
+        .apply_committed_entries(&ents, |e| applied.push(e.get_data().to_vec()))
+        .unwrap()
+        .unwrap();
+    assert_eq!(applied, vec![b"a".to_vec(), b"b".to_vec(), b"c".to_vec()]);
+    assert_eq!(cs.get_nodes(), &[1]);
+    assert_eq!(cs.get_learners(), &[2]);
+
+    // a batch of only invalid conf changes returns no ConfState.
+    let cs = raw_node.apply_committed_entries(&[leave], |_| {}).unwrap();
+    assert!(cs.is_none());
+    assert_eq!(raw_node.raft.nodes(), vec![1]);
+
+    // an entry which can't be decoded is still an error.
+    let mut e = Entry::new();
+    e.set_entry_type(EntryType::EntryConfChange);
+    e.set_data(b"garbage".to_vec());
+    assert!(raw_node.apply_committed_entries(&[e], |_| {}).is_err());
+}
+
+// ensures that a ConfChangeV2 with several changes goes through a joint
+// config, which an empty ConfChangeV2 leaves.
+#[test]
+fn test_raw_node_conf_change_v2() {
+    let mut s = MemStorage::new();
+    let mut raw_node = RawNode::new(
+        &mut new_test_config(1, vec![], 10, 1),
+        s.clone(),
+        vec![Peer {
+            context: Default::default(),
+            id: 1,
+        }],
+    ).unwrap();
+    let rd = raw_node.ready();
+    let _ = s.append(&rd.entries);
+    raw_node.advance(rd);
+    let _ = raw_node.campaign();
+    let rd = raw_node.ready();
+    let _ = s.append(&rd.entries);
+    raw_node.advance(rd);
+
+    let mut cc = ConfChangeV2::new();
+    for &(change_type, id) in &[
+        (ConfChangeType::ConfChangeAddNode, 2),
+        (ConfChangeType::ConfChangeAddLearnerNode, 3),
+    ] {
+        let mut c = ConfChangeSingle::new();
+        c.set_change_type(change_type);
+        c.set_node_id(id);
+        cc.mut_changes().push(c);
+    }
+    raw_node.propose_conf_change_v2(&cc).unwrap();
+    let rd = raw_node.ready();
+    let _ = s.append(&rd.entries);
+    let e = rd.committed_entries.last().unwrap();
+    assert_eq!(e.get_entry_type(), EntryType::EntryConfChangeV2);
+    let cs = raw_node
+        .apply_committed_entries(&rd.committed_entries, |_| {})
+        .unwrap()
+        .unwrap();
+    raw_node.advance(rd);
+    assert_eq!(cs.get_nodes(), &[1, 2]);
+    assert_eq!(cs.get_voters_outgoing(), &[1]);
+    assert_eq!(cs.get_learners(), &[3]);
+    assert!(cs.get_auto_leave());
+
+    let cs = raw_node.apply_conf_change_v2(&ConfChangeV2::new()).unwrap();
+    assert_eq!(cs.get_nodes(), &[1, 2]);
+    assert!(cs.get_voters_outgoing().is_empty());
+    assert!(!cs.get_auto_leave());
+    assert!(raw_node.apply_conf_change_v2(&ConfChangeV2::new()).is_err());
+}
+
+// ensures that a ConfChangeV2 which is invalid in the current config is
+// refused when proposed, instead of being rejected on every node once
+// committed.
+#[test]
+fn test_raw_node_propose_invalid_conf_change_v2() {
+    let mut s = MemStorage::new();
+    let mut raw_node = RawNode::new(
+        &mut new_test_config(1, vec![], 10, 1),
+        s.clone(),
+        vec![Peer {
+            context: Default::default(),
+            id: 1,
+        }],
+    ).unwrap();
+    let rd = raw_node.ready();
+    let _ = s.append(&rd.entries);
+    raw_node.advance(rd);
+    let _ = raw_node.campaign();
+    let rd = raw_node.ready();
+    let _ = s.append(&rd.entries);
+    raw_node.advance(rd);
+
+    let new_cc = |transition, changes: &[(ConfChangeType, u64)]| {
+        let mut cc = ConfChangeV2::new();
+        cc.set_transition(transition);
+        for &(change_type, id) in changes {
+            let mut c = ConfChangeSingle::new();
+            c.set_change_type(change_type);
+            c.set_node_id(id);
+            cc.mut_changes().push(c);
+        }
+        cc
+    };
+    let invalid = |s: &str| Err(Error::ConfChangeInvalid(s.to_string()));
+    let auto = ConfChangeTransition::ConfChangeTransitionAuto;
+    let explicit = ConfChangeTransition::ConfChangeTransitionJointExplicit;
+    let last_index = raw_node.raft.raft_log.last_index();
+
+    let tests = vec![
+        (new_cc(auto, &[]), invalid("can't leave a non-joint config")),
+        (
+            new_cc(auto, &[(ConfChangeType::ConfChangeRemoveNode, 1)]),
+            invalid("can't remove 1, the last voter"),
+        ),
+        (
+            new_cc(explicit, &[(ConfChangeType::ConfChangeRemoveNode, 1)]),
+            invalid("removed all voters"),
+        ),
+        (
+            new_cc(
+                auto,
+                &[
+                    (ConfChangeType::ConfChangeAddNode, 2),
+                    (ConfChangeType::ConfChangeAddLearnerNode, 2),
+                ],
+            ),
+            invalid("node 2 is changed more than once"),
+        ),
+    ];
+    for (i, (cc, w)) in tests.into_iter().enumerate() {
+        assert_eq!(raw_node.propose_conf_change_v2(&cc), w, "#{}", i);
+    }
+    assert_eq!(raw_node.raft.raft_log.last_index(), last_index);
+
+    let cc = new_cc(explicit, &[(ConfChangeType::ConfChangeAddLearnerNode, 2)]);
+    raw_node.propose_conf_change_v2(&cc).unwrap();
+    let rd = raw_node.ready();
+    let _ = s.append(&rd.entries);
+    raw_node
+        .apply_committed_entries(&rd.committed_entries, |_| {})
+        .unwrap();
+    raw_node.advance(rd);
+    assert!(raw_node.raft.is_joint());
+
+    let last_index = raw_node.raft.raft_log.last_index();
+    let tests = vec![
+        (
+            new_cc(auto, &[(ConfChangeType::ConfChangeAddNode, 3)]),
+            invalid("can't apply a simple config change in a joint config"),
+        ),
+        (
+            new_cc(explicit, &[(ConfChangeType::ConfChangeAddNode, 3)]),
+            invalid("config is already joint"),
+        ),
+    ];
+    for (i, (cc, w)) in tests.into_iter().enumerate() {
+        assert_eq!(raw_node.propose_conf_change_v2(&cc), w, "#{}", i);
+    }
+    assert_eq!(raw_node.raft.raft_log.last_index(), last_index);
+    raw_node.propose_conf_change_v2(&ConfChangeV2::new()).unwrap();
+}
+
+// ensures that the leader leaves a joint config on its own once it is
+// applied, unless the transition is explicit.
+#[test]
+fn test_raw_node_conf_change_v2_auto_leave() {
+    for &(transition, wleave) in &[
+        (ConfChangeTransition::ConfChangeTransitionJointImplicit, true),
+        (ConfChangeTransition::ConfChangeTransitionJointExplicit, false),
+    ] {
+        let mut s = MemStorage::new();
+        let mut raw_node = RawNode::new(
+            &mut new_test_config(1, vec![], 10, 1),
+            s.clone(),
+            vec![Peer {
+                context: Default::default(),
+                id: 1,
+            }],
+        ).unwrap();
+        let rd = raw_node.ready();
+        let _ = s.append(&rd.entries);
+        raw_node.advance(rd);
+        let _ = raw_node.campaign();
+        let rd = raw_node.ready();
+        let _ = s.append(&rd.entries);
+        raw_node.advance(rd);
+
+        let mut cc = ConfChangeV2::new();
+        cc.set_transition(transition);
+        let mut c = ConfChangeSingle::new();
+        c.set_change_type(ConfChangeType::ConfChangeAddLearnerNode);
+        c.set_node_id(2);
+        cc.mut_changes().push(c);
+        raw_node.propose_conf_change_v2(&cc).unwrap();
+        let rd = raw_node.ready();
+        let _ = s.append(&rd.entries);
+        raw_node
+            .apply_committed_entries(&rd.committed_entries, |_| {})
+            .unwrap();
+        raw_node.advance(rd);
+        assert!(raw_node.raft.is_joint());
+
+        let rd = raw_node.ready();
+        let _ = s.append(&rd.entries);
+        let leave = rd
+            .committed_entries
+            .iter()
+            .any(|e| e.get_entry_type() == EntryType::EntryConfChangeV2);
+        assert_eq!(leave, wleave, "{:?}", transition);
+        raw_node
+            .apply_committed_entries(&rd.committed_entries, |_| {})
+            .unwrap();
+        raw_node.advance(rd);
+        assert_eq!(raw_node.raft.is_joint(), !wleave, "{:?}", transition);
+        assert_eq!(raw_node.raft.learner_nodes(), vec![2]);
+    }
+}
+
+// ensures that with ack_persisted_only a single node leader commits its
+// entries only once they are reported persisted by advance.
+#[test]
+fn test_raw_node_commit_after_persist() {
+    let mut s = MemStorage::new();
+    let mut c = new_test_config(1, vec![], 10, 1);
+    c.ack_persisted_only = true;
+    let mut raw_node = RawNode::new(
+        &mut c,
+        s.clone(),
+        vec![Peer {
+            context: Default::default(),
+            id: 1,
+        }],
+    ).unwrap();
+    let rd = raw_node.ready();
+    let _ = s.append(&rd.entries);
+    raw_node.advance(rd);
+    let _ = raw_node.campaign();
+    let rd = raw_node.ready();
+    assert!(rd.committed_entries.is_empty());
+    let _ = s.append(&rd.entries);
+    raw_node.advance(rd);
+    // the empty entry of the new leader is committed once persisted.
+    let rd = raw_node.ready();
+    assert_eq!(rd.committed_entries.len(), 1);
+    raw_node.advance(rd);
+
+    raw_node.propose(vec![], b"somedata".to_vec()).unwrap();
+    let rd = raw_node.ready();
+    assert_eq!(rd.entries.len(), 1);
+    assert!(rd.committed_entries.is_empty());
+    let last = rd.entries[0].get_index();
+    let _ = s.append(&rd.entries);
+    raw_node.advance(rd);
+
+    let rd = raw_node.ready();
+    assert!(rd.entries.is_empty());
+    assert_eq!(rd.committed_entries.len(), 1);
+    assert_eq!(rd.committed_entries[0].get_index(), last);
+    assert_eq!(rd.committed_entries[0].get_data(), b"somedata");
+}
+
+// ensures that the context of a proposal is committed along with its data,
+// and that a node without leader drops proposals.
+#[test]
+fn test_raw_node_propose_context() {
+    let mut s = MemStorage::new();
+    let mut c = new_test_config(1, vec![], 10, 1);
+    let mut raw_node = RawNode::new(
+        &mut c,
+        s.clone(),
+        vec![Peer {
+            context: Default::default(),
+            id: 1,
+        }],
+    ).unwrap();
+    let rd = raw_node.ready();
+    let _ = s.append(&rd.entries);
+    raw_node.advance(rd);
+    assert_eq!(
+        raw_node.propose(b"ctx".to_vec(), b"somedata".to_vec()),
+        Err(Error::ProposalDropped)
+    );
+
+    let _ = raw_node.campaign();
+    let rd = raw_node.ready();
+    let _ = s.append(&rd.entries);
+    raw_node.advance(rd);
+
+    raw_node.propose(b"ctx".to_vec(), b"somedata".to_vec()).unwrap();
+    let rd = raw_node.ready();
+    assert_eq!(rd.entries.len(), 1);
+    assert_eq!(rd.entries[0].get_context(), b"ctx");
+    let e = rd.committed_entries.last().unwrap();
+    assert_eq!(e.get_data(), b"somedata");
+    assert_eq!(e.get_context(), b"ctx");
+}
+
+// ensures that client proposals over the configured rate are refused with
+// ProposalRateLimited until enough ticks have passed, and that dropped
+// proposals don't count against the rate.
+#[test]
+fn test_raw_node_propose_rate_limited() {
+    let mut s = MemStorage::new();
+    let mut c = new_test_config(1, vec![], 10, 1);
+    c.max_proposals_per_tick = 2;
+    let mut raw_node = RawNode::new(
+        &mut c,
+        s.clone(),
+        vec![Peer {
+            context: Default::default(),
+            id: 1,
+        }],
+    ).unwrap();
+    let rd = raw_node.ready();
+    let _ = s.append(&rd.entries);
+    raw_node.advance(rd);
+    for _ in 0..3 {
+        assert_eq!(
+            raw_node.propose(vec![], b"x".to_vec()),
+            Err(Error::ProposalDropped)
+        );
+    }
+    let _ = raw_node.campaign();
+
+    raw_node.propose(vec![], b"a".to_vec()).unwrap();
+    raw_node.propose(vec![], b"b".to_vec()).unwrap();
+    assert_eq!(
+        raw_node.propose(vec![], b"c".to_vec()),
+        Err(Error::ProposalRateLimited)
+    );
+    raw_node.tick();
+    raw_node.propose(vec![], b"c".to_vec()).unwrap();
+
+    let rd = raw_node.ready();
+    let data: Vec<&[u8]> = rd.entries[1..].iter().map(|e| e.get_data()).collect();
+    assert_eq!(data, vec![b"a", b"b", b"c"]);
+}